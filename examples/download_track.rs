@@ -22,6 +22,8 @@
 // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
 // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+#![allow(clippy::needless_borrow, clippy::useless_format)]
+
 use tokio::fs::File;
 use tokio_util::compat::TokioAsyncWriteCompatExt;
 
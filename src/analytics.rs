@@ -0,0 +1,232 @@
+//! Building blocks for audience analysis.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use futures::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::apis::{TrackLikers, TrackPlaylists};
+use crate::client::Client;
+use crate::error::Result;
+use crate::models::{Playlist, Track, User};
+use crate::page::PageOptions;
+use crate::streaming_api::StreamingApiExt;
+
+/// Number of items requested per page while collecting all tracks of a user.
+const USER_TRACKS_PAGE_SIZE: u32 = 200;
+
+/// Returns the users who liked both `track_a` and `track_b`, in the order in which they appear
+/// in the likers of `track_a`.
+///
+/// Both likers lists are streamed concurrently and at most `limit` likers are read from each of
+/// them, so for popular tracks the overlap is computed from a sample.
+///
+/// # Examples
+///
+/// ```
+/// use soundcloud::Client;
+/// use soundcloud::analytics::common_likers;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+///   let users = common_likers(&client, 262681089, 263801976, 1000).await;
+///
+///   assert!(users.is_ok());
+/// }
+/// ```
+pub async fn common_likers(
+    client: &Client,
+    track_a: usize,
+    track_b: usize,
+    limit: usize,
+) -> Result<Vec<User>> {
+    let likers_a = TrackLikers::new(client.clone(), track_a);
+    let likers_b = TrackLikers::new(client.clone(), track_b);
+    let (likers_a, likers_b): (Vec<User>, HashSet<User>) = future::try_join(
+        likers_a
+            .iter(PageOptions::default())
+            .take(limit)
+            .try_collect(),
+        likers_b
+            .iter(PageOptions::default())
+            .take(limit)
+            .try_collect(),
+    )
+    .await?;
+
+    Ok(likers_a
+        .into_iter()
+        .filter(|user| likers_b.contains(user))
+        .collect())
+}
+
+/// Returns the playlists, excluding albums, which contain at least one track of the user, each
+/// playlist once.
+///
+/// The playlists of up to `concurrency` tracks are fetched at the same time. Playlists are
+/// ordered by the first track of the user they contain, in the order of the user's tracks.
+///
+/// # Examples
+///
+/// ```
+/// use soundcloud::Client;
+/// use soundcloud::analytics::playlists_featuring_user;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+///   let playlists = playlists_featuring_user(&client, 8553751, 4).await;
+///
+///   assert!(playlists.is_ok());
+/// }
+/// ```
+pub async fn playlists_featuring_user(
+    client: &Client,
+    user_id: usize,
+    concurrency: usize,
+) -> Result<Vec<Playlist>> {
+    let tracks: Vec<Track> = client
+        .user(user_id)
+        .tracks()
+        .iter(user_tracks_page_options())
+        .try_collect()
+        .await?;
+
+    let mut seen = HashSet::new();
+    let mut playlists = vec![];
+    let mut pages = stream::iter(tracks)
+        .map(|track| async move {
+            TrackPlaylists::new(client.clone(), track.id as usize)
+                .iter(user_tracks_page_options())
+                .try_collect::<Vec<Playlist>>()
+                .await
+        })
+        .buffered(concurrency.max(1));
+
+    while let Some(page) = pages.try_next().await? {
+        playlists.extend(
+            page.into_iter()
+                .filter(|playlist: &Playlist| seen.insert(playlist.id)),
+        );
+    }
+
+    Ok(playlists)
+}
+
+/// Counters of a user at one point in time, see [`SnapshotStore`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct StatsSnapshot {
+    /// Time of the snapshot, in seconds since the unix epoch.
+    pub recorded_at: u64,
+    /// Number of followers.
+    pub followers_count: Option<usize>,
+    /// Number of followed users.
+    pub followings_count: Option<usize>,
+    /// Number of public tracks.
+    pub track_count: Option<usize>,
+    /// Sum of the likes of the public tracks.
+    pub likes_count: u64,
+    /// Sum of the playbacks of the public tracks.
+    pub playback_count: u64,
+}
+
+/// Stores snapshots of the counters of users in a JSON file, to track their growth over time.
+///
+/// # Examples
+///
+/// ```no_run
+/// use soundcloud::Client;
+/// use soundcloud::analytics::SnapshotStore;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+///   let mut store = SnapshotStore::open(client, "stats.json").await.unwrap();
+///   store.record(8553751).await.unwrap();
+///
+///   for snapshot in store.history(8553751) {
+///     println!("{}: {:?} followers", snapshot.recorded_at, snapshot.followers_count);
+///   }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SnapshotStore {
+    client: Client,
+    path: PathBuf,
+    users: BTreeMap<usize, Vec<StatsSnapshot>>,
+}
+
+impl SnapshotStore {
+    /// Opens the store in the file at `path`, which is created on the first recorded snapshot.
+    pub async fn open<P: AsRef<Path>>(client: Client, path: P) -> Result<Self> {
+        let path = path.as_ref().to_owned();
+        let users = match fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(error) => return Err(error.into()),
+        };
+
+        Ok(SnapshotStore {
+            client,
+            path,
+            users,
+        })
+    }
+
+    /// Takes a snapshot of the counters of the user and saves it to the file.
+    pub async fn record(&mut self, user_id: usize) -> Result<StatsSnapshot> {
+        let user = self.client.fetch::<User>(user_id).await?;
+        let tracks: Vec<Track> = self
+            .client
+            .user(user_id)
+            .tracks()
+            .iter(user_tracks_page_options())
+            .try_collect()
+            .await?;
+        let recorded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+
+        let snapshot = StatsSnapshot {
+            recorded_at,
+            followers_count: user.followers_count,
+            followings_count: user.followings_count,
+            track_count: user.track_count,
+            likes_count: tracks.iter().filter_map(|track| track.likes_count).sum(),
+            playback_count: tracks.iter().filter_map(|track| track.playback_count).sum(),
+        };
+        self.users
+            .entry(user_id)
+            .or_default()
+            .push(snapshot.clone());
+        self.save().await?;
+
+        Ok(snapshot)
+    }
+
+    /// Returns the recorded snapshots of the user, from the oldest to the newest.
+    pub fn history(&self, user_id: usize) -> &[StatsSnapshot] {
+        self.users.get(&user_id).map_or(&[], Vec::as_slice)
+    }
+
+    async fn save(&self) -> Result<()> {
+        let mut partial = self.path.as_os_str().to_owned();
+        partial.push(".part");
+        fs::write(&partial, serde_json::to_vec_pretty(&self.users)?)?;
+        fs::rename(&partial, &self.path)?;
+        Ok(())
+    }
+}
+
+fn user_tracks_page_options() -> PageOptions {
+    PageOptions::builder()
+        .page_size(USER_TRACKS_PAGE_SIZE)
+        .build()
+}
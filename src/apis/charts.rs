@@ -0,0 +1,61 @@
+use std::fmt;
+
+use crate::client::Client;
+use crate::endpoint::Endpoint;
+use crate::models::{ChartEntry, Genre};
+use crate::streaming_api::StreamingApi;
+
+/// The kind of chart to fetch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartKind {
+    Top,
+    Trending,
+}
+
+impl fmt::Display for ChartKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_str())
+    }
+}
+
+impl ChartKind {
+    pub fn to_str(&self) -> &str {
+        match *self {
+            ChartKind::Top => "top",
+            ChartKind::Trending => "trending",
+        }
+    }
+}
+
+/// Provides access to the SoundCloud charts of a genre
+pub struct Charts {
+    client: Client,
+    kind: ChartKind,
+    genre: Genre,
+}
+
+impl Charts {
+    /// create a new instance of a souncloud chart
+    pub fn new(client: Client, kind: ChartKind, genre: Genre) -> Self {
+        Charts {
+            client,
+            kind,
+            genre,
+        }
+    }
+}
+
+impl StreamingApi for Charts {
+    type Model = ChartEntry;
+
+    fn path(&self) -> String {
+        Endpoint::new("/charts")
+            .param("kind", self.kind)
+            .param("genre", self.genre.urn())
+            .to_string()
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
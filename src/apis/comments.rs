@@ -1,31 +1,321 @@
-use futures::stream::BoxStream;
+use futures::future;
+use futures::io::{AsyncWrite, AsyncWriteExt};
+use futures::stream::{BoxStream, TryStreamExt};
+use serde::{Deserialize, Serialize};
 
 use crate::client::Client;
-use crate::error::Result;
+use crate::endpoint::Endpoint;
+use crate::error::{Error, Result};
 use crate::models::Comment;
-use crate::streaming_api::StreamingApi;
+use crate::page::PageOptions;
+use crate::query_params::QueryParams;
+use crate::retry::RetryPolicy;
+use crate::streaming_api::{StreamingApi, StreamingApiExt};
+
+/// Error message of a comment rejected until a captcha has been solved on the website.
+const CAPTCHA_REQUIRED: &str = "Captcha required";
+
+/// Order of the comments of a track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentOrder {
+    /// Most recent comments first, the default of the API.
+    Newest,
+    /// Oldest comments first.
+    Oldest,
+    /// Ordered by their timestamp on the track.
+    TrackTimestamp,
+}
+
+impl CommentOrder {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            CommentOrder::Newest => "newest",
+            CommentOrder::Oldest => "oldest",
+            CommentOrder::TrackTimestamp => "track-timestamp",
+        }
+    }
+}
 
 /// Provides access to operations available for comments
 pub struct Comments {
     client: Client,
     track_id: usize,
+    order: Option<CommentOrder>,
+    threaded: Option<bool>,
+    since: Option<String>,
 }
 
 impl StreamingApi for Comments {
     type Model = Comment;
 
     fn path(&self) -> String {
-        format!("/tracks/{}/comments", self.track_id)
+        let mut params = QueryParams::new();
+        params
+            .push_opt("sort", self.order.map(|order| order.as_str()))
+            .push_opt("threaded", self.threaded.map(|threaded| threaded as u8));
+
+        Endpoint::new("/tracks")
+            .segment(self.track_id)
+            .segment("comments")
+            .params(&params)
+            .to_string()
     }
 
-    fn get_stream(&self, url: &str, pages: Option<u64>) -> BoxStream<'_, Result<Self::Model>> {
-        self.client.get_stream(url, pages)
+    fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Applies the [`since`](Comments::since) watermark to the `stream`.
+    fn adapt_stream<'a>(
+        &self,
+        stream: BoxStream<'a, Result<Comment>>,
+    ) -> BoxStream<'a, Result<Comment>> {
+        let since = match self.since {
+            Some(ref since) => since.clone(),
+            None => return stream,
+        };
+
+        match self.order {
+            None | Some(CommentOrder::Newest) => Box::pin(
+                stream.try_take_while(move |comment| future::ready(Ok(comment.created_at > since))),
+            ),
+            Some(_) => Box::pin(
+                stream.try_filter(move |comment| future::ready(comment.created_at > since)),
+            ),
+        }
     }
 }
 
 impl Comments {
     /// create a new instance of a souncloud track's comments
     pub fn track(client: Client, track_id: usize) -> Self {
-        Comments { client, track_id }
+        Comments {
+            client,
+            track_id,
+            order: None,
+            threaded: None,
+            since: None,
+        }
+    }
+
+    /// Sets the order of the comments
+    pub fn order(self, order: CommentOrder) -> Self {
+        Comments {
+            order: Some(order),
+            ..self
+        }
+    }
+
+    /// Sets whether replies are grouped under the comment they reply to, instead of a linear
+    /// list
+    pub fn threaded(self, threaded: bool) -> Self {
+        Comments {
+            threaded: Some(threaded),
+            ..self
+        }
+    }
+
+    /// Only includes comments created after the `watermark`, usually the `created_at` of the
+    /// newest comment seen by a previous run.
+    ///
+    /// With newest-first order the stream ends at the first older comment, so the history
+    /// before the watermark isn't fetched at all. Timestamps are compared as strings, which
+    /// orders the timestamps returned by the API correctly.
+    pub fn since<S: Into<String>>(self, watermark: S) -> Self {
+        Comments {
+            since: Some(watermark.into()),
+            ..self
+        }
+    }
+
+    /// Writes all comments of this track to the `writer` as newline-delimited JSON, one object
+    /// per comment with the fields of the commenter flattened into `user_*` fields.
+    ///
+    /// Failed page requests are retried with the default [`RetryPolicy`].
+    ///
+    /// Returns:
+    ///     the number of written comments.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use soundcloud::Client;
+    /// use tokio::fs::File;
+    /// use tokio_util::compat::TokioAsyncWriteCompatExt;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let outfile = File::create("comments.ndjson").await.unwrap().compat_write();
+    ///   let count = client.track(263801976).comments().export_ndjson(outfile).await.unwrap();
+    ///   println!("exported {} comments", count);
+    /// }
+    /// ```
+    pub async fn export_ndjson<W: AsyncWrite + Unpin>(&self, mut writer: W) -> Result<u64> {
+        let options = PageOptions::builder()
+            .page_size(self.max_page_size())
+            .build();
+        let mut comments = self.iter_resilient(options, RetryPolicy::default());
+        let mut count = 0;
+
+        while let Some(comment) = comments.try_next().await? {
+            let mut line = serde_json::to_vec(&CommentRecord::new(&comment))?;
+            line.push(b'\n');
+            writer.write_all(&line).await?;
+            count += 1;
+        }
+        writer.flush().await?;
+
+        Ok(count)
+    }
+
+    /// Retrieve all comments of this track which are pending moderation.
+    ///
+    /// Only available to the owner of the track.
+    ///
+    /// Returns:
+    ///     an instance of PendingComments
+    pub fn pending(&self) -> PendingComments {
+        PendingComments {
+            client: self.client.clone(),
+            track_id: self.track_id,
+        }
+    }
+
+    /// Posts a comment on this track on behalf of the authenticated user, optionally at a
+    /// `timestamp` in milliseconds of the track.
+    ///
+    /// Comments are spaced out by the
+    /// [comment interval](crate::ClientBuilder::comment_interval) of the client. When SoundCloud
+    /// nevertheless stops accepting comments until a captcha is solved, this fails with
+    /// [`Error::CaptchaRequired`](crate::Error::CaptchaRequired).
+    ///
+    /// Returns:
+    ///     the posted comment.
+    pub async fn post(&self, body: &str, timestamp: Option<usize>) -> Result<Comment> {
+        self.client.comment_pacer.wait().await;
+
+        let url = self.client.api_url(
+            &Endpoint::new("/tracks")
+                .segment(self.track_id)
+                .segment("comments")
+                .to_string(),
+        )?;
+        let request = self
+            .client
+            .request(reqwest::Method::POST, url)?
+            .json(&serde_json::json!({
+                "comment": { "body": body, "timestamp": timestamp }
+            }));
+        let response = self.client.send_unchecked(request.build()?).await?;
+        if let Err(error) = response.error_for_status_ref() {
+            let body = self.client.read_body(response).await.unwrap_or_default();
+            return Err(if requires_captcha(&body) {
+                Error::CaptchaRequired
+            } else {
+                Error::HttpError(error)
+            });
+        }
+        let comment: Comment = self.client.read_json(response).await?;
+
+        Ok(comment)
+    }
+
+    /// Deletes a comment on this track.
+    ///
+    /// Only available to the author of the comment or the owner of the track.
+    pub async fn delete_comment(&self, comment_id: usize) -> Result<()> {
+        self.client
+            .delete(
+                &Endpoint::new("/tracks")
+                    .segment(self.track_id)
+                    .segment("comments")
+                    .segment(comment_id)
+                    .to_string(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// A comment with the fields of the commenter flattened, as exported by
+/// [`Comments::export_ndjson`].
+#[derive(Serialize)]
+struct CommentRecord<'a> {
+    id: usize,
+    track_id: usize,
+    created_at: &'a str,
+    body: &'a str,
+    timestamp: Option<usize>,
+    user_id: usize,
+    user_username: &'a str,
+    user_permalink: &'a str,
+    user_full_name: Option<&'a str>,
+    user_country: Option<&'a str>,
+    user_city: Option<&'a str>,
+    user_followers_count: Option<usize>,
+}
+
+impl<'a> CommentRecord<'a> {
+    fn new(comment: &'a Comment) -> Self {
+        CommentRecord {
+            id: comment.id,
+            track_id: comment.track_id,
+            created_at: &comment.created_at,
+            body: &comment.body,
+            timestamp: comment.timestamp,
+            user_id: comment.user.id,
+            user_username: &comment.user.username,
+            user_permalink: &comment.user.permalink,
+            user_full_name: comment.user.full_name.as_deref(),
+            user_country: comment.user.country.as_deref(),
+            user_city: comment.user.city.as_deref(),
+            user_followers_count: comment.user.followers_count,
+        }
+    }
+}
+
+/// Error body of a rejected request, like `{"errors": [{"error_message": "Captcha required"}]}`.
+#[derive(Deserialize)]
+struct ErrorBody {
+    #[serde(default)]
+    errors: Vec<ErrorMessage>,
+}
+
+#[derive(Deserialize)]
+struct ErrorMessage {
+    #[serde(default)]
+    error_message: String,
+}
+
+/// Returns whether the error `body` of a posted comment asks for a captcha to be solved.
+fn requires_captcha(body: &[u8]) -> bool {
+    serde_json::from_slice::<ErrorBody>(body).is_ok_and(|body| {
+        body.errors
+            .iter()
+            .any(|error| error.error_message.eq_ignore_ascii_case(CAPTCHA_REQUIRED))
+    })
+}
+
+/// Provides access to the comments of a track which are pending moderation
+pub struct PendingComments {
+    client: Client,
+    track_id: usize,
+}
+
+impl StreamingApi for PendingComments {
+    type Model = Comment;
+
+    fn path(&self) -> String {
+        Endpoint::new("/tracks")
+            .segment(self.track_id)
+            .segment("comments")
+            .segment("pending")
+            .to_string()
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
     }
 }
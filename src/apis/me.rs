@@ -0,0 +1,67 @@
+use reqwest::multipart::Form;
+use serde::Serialize;
+
+use crate::apis::FileUpload;
+
+/// Changes to the authenticated user's profile.
+///
+/// Fields which are left as `None` are not changed.
+#[derive(Debug, Clone, Default)]
+pub struct ProfileUpdate {
+    /// Username.
+    pub username: Option<String>,
+    /// First and last name.
+    pub full_name: Option<String>,
+    /// Description, written by the user.
+    pub description: Option<String>,
+    /// City.
+    pub city: Option<String>,
+    /// Country.
+    pub country: Option<String>,
+    /// URL to a website.
+    pub website: Option<String>,
+    /// Custom title for the website.
+    pub website_title: Option<String>,
+    /// New avatar image.
+    pub avatar: Option<FileUpload>,
+}
+
+impl ProfileUpdate {
+    pub(crate) fn into_form(self) -> Form {
+        let mut form = Form::new();
+        let fields = [
+            ("user[username]", self.username),
+            ("user[full_name]", self.full_name),
+            ("user[description]", self.description),
+            ("user[city]", self.city),
+            ("user[country]", self.country),
+            ("user[website]", self.website),
+            ("user[website_title]", self.website_title),
+        ];
+
+        for (name, value) in fields {
+            if let Some(value) = value {
+                form = form.text(name, value);
+            }
+        }
+
+        if let Some(avatar) = self.avatar {
+            form = form.part("user[avatar_data]", avatar.into_part());
+        }
+
+        form
+    }
+}
+
+/// A link on the authenticated user's profile, to be created or changed.
+///
+/// Fields which are left as `None` are not changed.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct WebProfileUpdate {
+    /// URL of the link.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Title of the link, shown instead of the URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
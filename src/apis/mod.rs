@@ -1,25 +1,45 @@
+pub use self::charts::*;
 pub use self::comments::*;
+pub use self::me::*;
+pub use self::oembed::*;
 pub use self::playlist::*;
+pub use self::playlist_tracks::*;
 pub use self::related_tracks::*;
+pub use self::search::*;
+pub use self::station::*;
 pub use self::track::*;
 pub use self::track_likers::*;
+pub use self::track_playlists::*;
+pub use self::upload::*;
 pub use self::user::*;
 pub use self::user_followers::*;
 pub use self::user_followings::*;
+pub use self::user_liked_playlists::*;
 pub use self::user_likes::*;
 pub use self::user_playlists::*;
+pub use self::user_reposts::*;
 pub use self::user_tracks::*;
 pub use self::user_web_profile::*;
 
+mod charts;
 mod comments;
+mod me;
+mod oembed;
 mod playlist;
+mod playlist_tracks;
 mod related_tracks;
+mod search;
+mod station;
 mod track;
 mod track_likers;
+mod track_playlists;
+mod upload;
 mod user;
 mod user_followers;
 mod user_followings;
+mod user_liked_playlists;
 mod user_likes;
 mod user_playlists;
+mod user_reposts;
 mod user_tracks;
 mod user_web_profile;
@@ -0,0 +1,30 @@
+use crate::query_params::QueryParams;
+
+/// Options for an oEmbed request.
+#[derive(Debug, Clone, Default)]
+pub struct OEmbedOptions {
+    /// Maximum width of the embedded player in pixels.
+    pub max_width: Option<u32>,
+    /// Maximum height of the embedded player in pixels.
+    pub max_height: Option<u32>,
+    /// Primary color of the player as a hex triplet, e.g. `ff5500`.
+    pub color: Option<String>,
+    /// Whether the player should start playing on load.
+    pub auto_play: Option<bool>,
+    /// Whether the player should display timed comments.
+    pub show_comments: Option<bool>,
+}
+
+impl OEmbedOptions {
+    pub(crate) fn request_params(&self) -> QueryParams {
+        let mut params = QueryParams::new();
+        params
+            .push("format", "json")
+            .push_opt("maxwidth", self.max_width)
+            .push_opt("maxheight", self.max_height)
+            .push_opt("color", self.color.as_ref())
+            .push_opt("auto_play", self.auto_play)
+            .push_opt("show_comments", self.show_comments);
+        params
+    }
+}
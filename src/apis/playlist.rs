@@ -1,6 +1,59 @@
+use reqwest::multipart::Form;
+
+use crate::apis::{FileUpload, PlaylistTracks};
 use crate::client::Client;
+use crate::endpoint::Endpoint;
 use crate::error::{Error, Result};
-use crate::models::Playlist;
+use crate::models::{Playlist, Sharing};
+use crate::query_params::QueryParams;
+use crate::response_meta::ResponseMeta;
+
+/// Changes to a playlist's metadata.
+///
+/// Fields which are left as `None` are not changed.
+#[derive(Debug, Clone, Default)]
+pub struct PlaylistUpdate {
+    /// Title.
+    pub title: Option<String>,
+    /// HTML description.
+    pub description: Option<String>,
+    /// Genre.
+    pub genre: Option<String>,
+    /// Space separated list of tags.
+    pub tags: Option<String>,
+    /// Sharing status.
+    pub sharing: Option<Sharing>,
+    /// New artwork image.
+    pub artwork: Option<FileUpload>,
+}
+
+impl PlaylistUpdate {
+    pub(crate) fn into_form(self) -> Form {
+        let mut form = Form::new();
+        let fields = [
+            ("playlist[title]", self.title),
+            ("playlist[description]", self.description),
+            ("playlist[genre]", self.genre),
+            ("playlist[tag_list]", self.tags),
+            (
+                "playlist[sharing]",
+                self.sharing.map(|s| s.as_str().to_owned()),
+            ),
+        ];
+
+        for (name, value) in fields {
+            if let Some(value) = value {
+                form = form.text(name, value);
+            }
+        }
+
+        if let Some(artwork) = self.artwork {
+            form = form.part("playlist[artwork_data]", artwork.into_part());
+        }
+
+        form
+    }
+}
 
 #[derive(Debug)]
 pub struct PlaylistRequestBuilder<'a> {
@@ -12,24 +65,157 @@ pub struct PlaylistRequestBuilder<'a> {
 pub struct SinglePlaylistRequestBuilder<'a> {
     client: &'a Client,
     pub id: usize,
+    /// The secret token of a private playlist.
+    pub secret_token: Option<String>,
 }
 
 impl<'a> SinglePlaylistRequestBuilder<'a> {
     /// Constructs a new track request.
-    pub fn new(client: &'a Client, id: usize) -> SinglePlaylistRequestBuilder {
-        SinglePlaylistRequestBuilder { client, id }
+    pub fn new(client: &'a Client, id: usize) -> SinglePlaylistRequestBuilder<'a> {
+        SinglePlaylistRequestBuilder {
+            client,
+            id,
+            secret_token: None,
+        }
     }
 
-    /// Sends the request and return the tracks.
-    pub async fn get(&mut self) -> Result<Playlist> {
-        let no_params: Option<&[(&str, &str)]> = None;
-        let response = self
+    /// Sets the secret token used to access a private playlist.
+    pub fn secret_token<S: AsRef<str>>(&mut self, secret_token: S) -> &mut Self {
+        self.secret_token = Some(secret_token.as_ref().to_owned());
+        self
+    }
+
+    /// Retrieve all tracks of this playlist, page by page
+    ///
+    /// Returns:
+    ///     an instance of PlaylistTracks
+    pub fn tracks(&self) -> PlaylistTracks {
+        let tracks = PlaylistTracks::new(self.client.clone(), self.id);
+        match self.secret_token {
+            Some(ref secret_token) => tracks.with_secret_token(secret_token),
+            None => tracks,
+        }
+    }
+
+    /// Likes this playlist on behalf of the authenticated user, adding it to their library.
+    pub async fn like(&self) -> Result<()> {
+        self.client
+            .put(
+                &Endpoint::new("/me/playlist_likes")
+                    .segment(self.id)
+                    .to_string(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Removes the authenticated user's like of this playlist.
+    pub async fn unlike(&self) -> Result<()> {
+        self.client
+            .delete(
+                &Endpoint::new("/me/playlist_likes")
+                    .segment(self.id)
+                    .to_string(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Reposts this playlist on behalf of the authenticated user.
+    pub async fn repost(&self) -> Result<()> {
+        self.client
+            .put(
+                &Endpoint::new("/me/playlist_reposts")
+                    .segment(self.id)
+                    .to_string(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Reposts this playlist on behalf of the authenticated user with a `caption`, which is shown
+    /// along with the repost.
+    pub async fn repost_with_caption(&self, caption: &str) -> Result<()> {
+        let url = self.client.api_url(
+            &Endpoint::new("/me/playlist_reposts")
+                .segment(self.id)
+                .to_string(),
+        )?;
+        let request = self
             .client
-            .get(&format!("/playlists/{}", self.id), no_params)
+            .request(reqwest::Method::PUT, url)?
+            .json(&serde_json::json!({ "caption": caption }));
+        self.client.send(request).await?;
+
+        Ok(())
+    }
+
+    /// Removes the authenticated user's repost of this playlist.
+    pub async fn unrepost(&self) -> Result<()> {
+        self.client
+            .delete(
+                &Endpoint::new("/me/playlist_reposts")
+                    .segment(self.id)
+                    .to_string(),
+            )
             .await?;
-        let track: Playlist = response.json().await?;
 
-        Ok(track)
+        Ok(())
+    }
+
+    /// Updates the metadata of this playlist, which has to be owned by the authenticated user.
+    ///
+    /// Returns the updated playlist on success, an error otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use soundcloud::{Client, FileUpload, PlaylistUpdate};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let mut client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   client.authenticate_with_token(env!("SOUNDCLOUD_AUTH_TOKEN").to_owned());
+    ///   let artwork = std::fs::read("cover.jpg").unwrap();
+    ///   let update = PlaylistUpdate {
+    ///     title: Some("Summer 2024".to_owned()),
+    ///     artwork: Some(FileUpload::new("cover.jpg", artwork)),
+    ///     ..Default::default()
+    ///   };
+    ///   let playlist = client.playlist(405726).update(update).await.unwrap();
+    ///
+    ///   println!("{}", playlist.title);
+    /// }
+    /// ```
+    pub async fn update(&self, update: PlaylistUpdate) -> Result<Playlist> {
+        let url = self
+            .client
+            .api_url(&Endpoint::new("/playlists").segment(self.id).to_string())?;
+        let request = self
+            .client
+            .request(reqwest::Method::PUT, url)?
+            .multipart(update.into_form());
+        let response = self.client.send(request).await?;
+        let playlist: Playlist = self.client.read_json(response).await?;
+
+        Ok(playlist)
+    }
+
+    /// Sends the request and return the tracks.
+    pub async fn get(&mut self) -> Result<Playlist> {
+        self.client
+            .fetch_secret::<Playlist>(self.id as u64, self.secret_token.as_deref())
+            .await
+    }
+
+    /// Sends the request and return the playlist along with the metadata of the response.
+    pub async fn get_with_meta(&self) -> Result<(Playlist, ResponseMeta)> {
+        self.client
+            .fetch_secret_with_meta::<Playlist>(self.id as u64, self.secret_token.as_deref())
+            .await
     }
 }
 
@@ -43,7 +229,7 @@ impl<'a> PlaylistRequestBuilder<'a> {
     }
 
     /// Sets the search query filter, which will only return playlists with a matching query.
-    pub fn query<S>(&'a mut self, query: S) -> &mut Self
+    pub fn query<S>(&mut self, query: S) -> &mut Self
     where
         S: AsRef<str>,
     {
@@ -52,11 +238,8 @@ impl<'a> PlaylistRequestBuilder<'a> {
     }
 
     /// Returns a builder for a single playlist.
-    pub fn id(&'a mut self, id: usize) -> SinglePlaylistRequestBuilder {
-        SinglePlaylistRequestBuilder {
-            client: &self.client,
-            id,
-        }
+    pub fn id(&self, id: usize) -> SinglePlaylistRequestBuilder<'a> {
+        SinglePlaylistRequestBuilder::new(self.client, id)
     }
 
     /// Performs the request and returns a list of playlists or an error if one occurred.
@@ -65,15 +248,15 @@ impl<'a> PlaylistRequestBuilder<'a> {
 
         let response = self
             .client
-            .get("/playlists", Some(self.request_params()))
+            .get("/playlists", Some(&self.request_params()))
             .await?;
-        let playlist_list: Value = response.json().await?;
+        let playlist_list: Value = self.client.read_json(response).await?;
 
         if let Some(playlist_list) = playlist_list.as_array() {
-            let playlists: Vec<Playlist> = playlist_list
+            let playlists = playlist_list
                 .iter()
-                .map(|p| serde_json::from_value::<Playlist>(p.clone()).unwrap())
-                .collect();
+                .map(|p| serde_json::from_value::<Playlist>(p.clone()))
+                .collect::<serde_json::Result<Vec<_>>>()?;
 
             Ok(playlists)
         } else {
@@ -83,13 +266,9 @@ impl<'a> PlaylistRequestBuilder<'a> {
         }
     }
 
-    fn request_params(&self) -> Vec<(&str, String)> {
-        let mut result = vec![];
-
-        if let Some(ref query) = self.query {
-            result.push(("q", query.clone()));
-        }
-
-        result
+    fn request_params(&self) -> QueryParams {
+        let mut params = QueryParams::new();
+        params.push_opt("q", self.query.as_ref());
+        params
     }
 }
@@ -0,0 +1,49 @@
+use crate::client::Client;
+use crate::endpoint::Endpoint;
+use crate::models::Track;
+use crate::streaming_api::StreamingApi;
+
+/// Provides access to operations available for a playlist's tracks
+pub struct PlaylistTracks {
+    client: Client,
+    playlist_id: usize,
+    secret_token: Option<String>,
+}
+
+impl PlaylistTracks {
+    /// create a new instance of a souncloud playlist's tracks
+    pub fn new(client: Client, playlist_id: usize) -> Self {
+        PlaylistTracks {
+            client,
+            playlist_id,
+            secret_token: None,
+        }
+    }
+
+    /// Access the tracks of a private playlist with its secret token
+    pub fn with_secret_token<S: AsRef<str>>(self, secret_token: S) -> Self {
+        PlaylistTracks {
+            secret_token: Some(secret_token.as_ref().to_owned()),
+            ..self
+        }
+    }
+}
+
+impl StreamingApi for PlaylistTracks {
+    type Model = Track;
+
+    fn path(&self) -> String {
+        let endpoint = Endpoint::new("/playlists")
+            .segment(self.playlist_id)
+            .segment("tracks");
+
+        match self.secret_token {
+            Some(ref secret_token) => endpoint.param("secret_token", secret_token).to_string(),
+            None => endpoint.to_string(),
+        }
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
@@ -0,0 +1,72 @@
+use std::collections::HashSet;
+
+use futures::future;
+use futures::stream::{BoxStream, TryStreamExt};
+
+use crate::client::Client;
+use crate::endpoint::Endpoint;
+use crate::error::Result;
+use crate::models::SearchResult;
+use crate::query_params::QueryParams;
+use crate::streaming_api::StreamingApi;
+
+/// Provides access to the results of a search across tracks, users and playlists
+pub struct Search {
+    client: Client,
+    query: String,
+    dedupe: bool,
+}
+
+impl Search {
+    /// create a new search for the `query`
+    pub fn new<S: Into<String>>(client: Client, query: S) -> Self {
+        Search {
+            client,
+            query: query.into(),
+            dedupe: false,
+        }
+    }
+
+    /// Sets whether results already seen are skipped, compared by URN across kinds.
+    ///
+    /// Tracks included in a playlist result count as seen, so a track found both directly and
+    /// inside a playlist is only returned within the playlist if the playlist comes first.
+    pub fn dedupe(self, dedupe: bool) -> Self {
+        Search { dedupe, ..self }
+    }
+}
+
+impl StreamingApi for Search {
+    type Model = SearchResult;
+
+    fn path(&self) -> String {
+        let mut params = QueryParams::new();
+        params.push("q", &self.query);
+
+        Endpoint::new("/search").params(&params).to_string()
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Skips the results already seen if [`dedupe`](Search::dedupe) is enabled.
+    fn adapt_stream<'a>(
+        &self,
+        stream: BoxStream<'a, Result<SearchResult>>,
+    ) -> BoxStream<'a, Result<SearchResult>> {
+        if !self.dedupe {
+            return stream;
+        }
+
+        let mut seen = HashSet::new();
+        Box::pin(stream.try_filter(move |result| {
+            let new = match result.urn() {
+                Some(urn) => seen.insert(urn),
+                None => true,
+            };
+            seen.extend(result.track_urns());
+            future::ready(new)
+        }))
+    }
+}
@@ -0,0 +1,41 @@
+use crate::client::Client;
+use crate::endpoint::Endpoint;
+use crate::models::Track;
+use crate::streaming_api::StreamingApi;
+
+/// Provides access to the tracks of a station, an endless stream of tracks similar to a seed
+/// track or artist
+pub struct Station {
+    client: Client,
+    urn: String,
+}
+
+impl Station {
+    /// create a new instance of a souncloud station from its urn
+    pub fn new<S: AsRef<str>>(client: Client, urn: S) -> Self {
+        Station {
+            client,
+            urn: urn.as_ref().to_owned(),
+        }
+    }
+
+    /// The urn of the station.
+    pub fn urn(&self) -> &str {
+        &self.urn
+    }
+}
+
+impl StreamingApi for Station {
+    type Model = Track;
+
+    fn path(&self) -> String {
+        Endpoint::new("/stations")
+            .segment(&self.urn)
+            .segment("tracks")
+            .to_string()
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
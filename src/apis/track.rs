@@ -1,10 +1,15 @@
 use std::fmt;
 use std::str::FromStr;
 
-use crate::apis::{Comments, RelatedTracks, TrackLikers};
+use reqwest::multipart::Form;
+
+use crate::apis::{Comments, FileUpload, RelatedTracks, TrackLikers, TrackPlaylists};
 use crate::client::Client;
+use crate::endpoint::Endpoint;
 use crate::error::{Error, Result};
-use crate::models::Track;
+use crate::models::{DownloadInfo, EmbeddableBy, SecretToken, Sharing, Track, TrackEngagement};
+use crate::query_params::QueryParams;
+use crate::response_meta::ResponseMeta;
 
 #[derive(Debug)]
 pub enum Filter {
@@ -42,6 +47,75 @@ impl Filter {
     }
 }
 
+/// Changes to a track's metadata.
+///
+/// Fields which are left as `None` are not changed.
+#[derive(Debug, Clone, Default)]
+pub struct TrackUpdate {
+    /// Title.
+    pub title: Option<String>,
+    /// HTML description.
+    pub description: Option<String>,
+    /// Genre.
+    pub genre: Option<String>,
+    /// Space separated list of tags.
+    pub tags: Option<String>,
+    /// Sharing status.
+    pub sharing: Option<Sharing>,
+    /// Who is allowed to embed the player.
+    pub embeddable_by: Option<EmbeddableBy>,
+    /// If the track is available for download.
+    pub downloadable: Option<bool>,
+    /// Release date shown on the track, as `YYYY-MM-DD`.
+    pub release_date: Option<String>,
+    /// Time at which the track is scheduled to be made public, as an RFC 3339 timestamp like
+    /// `2024-05-01T12:00:00Z`.
+    ///
+    /// The track stays private until then, so [`sharing`](TrackUpdate::sharing) should be left
+    /// as private.
+    pub publish_at: Option<String>,
+    /// New artwork image.
+    pub artwork: Option<FileUpload>,
+}
+
+impl TrackUpdate {
+    pub(crate) fn into_form(self) -> Form {
+        let mut form = Form::new();
+        let fields = [
+            ("track[title]", self.title),
+            ("track[description]", self.description),
+            ("track[genre]", self.genre),
+            ("track[tag_list]", self.tags),
+            (
+                "track[sharing]",
+                self.sharing.map(|s| s.as_str().to_owned()),
+            ),
+            (
+                "track[embeddable_by]",
+                self.embeddable_by.map(|e| e.as_str().to_owned()),
+            ),
+            (
+                "track[downloadable]",
+                self.downloadable.map(|d| d.to_string()),
+            ),
+            ("track[release_date]", self.release_date),
+            ("track[publish_at]", self.publish_at),
+        ];
+
+        for (name, value) in fields {
+            if let Some(value) = value {
+                form = form.text(name, value);
+            }
+        }
+
+        if let Some(artwork) = self.artwork {
+            form = form.part("track[artwork_data]", artwork.into_part());
+        }
+
+        form
+    }
+}
+
 #[derive(Debug)]
 pub struct TrackRequestBuilder<'a> {
     client: &'a Client,
@@ -49,6 +123,7 @@ pub struct TrackRequestBuilder<'a> {
     tags: Option<String>,
     filter: Option<Filter>,
     license: Option<String>,
+    isrc: Option<String>,
     ids: Option<Vec<usize>>,
     duration: Option<(usize, usize)>,
     bpm: Option<(usize, usize)>,
@@ -60,12 +135,24 @@ pub struct TrackRequestBuilder<'a> {
 pub struct SingleTrackRequestBuilder<'a> {
     client: &'a Client,
     pub id: usize,
+    /// The secret token of a private track.
+    pub secret_token: Option<String>,
 }
 
 impl<'a> SingleTrackRequestBuilder<'a> {
     /// Constructs a new track request.
-    pub fn new(client: &'a Client, id: usize) -> SingleTrackRequestBuilder {
-        SingleTrackRequestBuilder { client, id }
+    pub fn new(client: &'a Client, id: usize) -> SingleTrackRequestBuilder<'a> {
+        SingleTrackRequestBuilder {
+            client,
+            id,
+            secret_token: None,
+        }
+    }
+
+    /// Sets the secret token used to access a private track.
+    pub fn secret_token<S: AsRef<str>>(&mut self, secret_token: S) -> &mut Self {
+        self.secret_token = Some(secret_token.as_ref().to_owned());
+        self
     }
 
     /// Retrieve all comments for this track
@@ -92,28 +179,206 @@ impl<'a> SingleTrackRequestBuilder<'a> {
         TrackLikers::new(self.client.clone(), self.id)
     }
 
+    /// Retrieve all playlists containing this track, excluding albums
+    ///
+    /// Returns:
+    ///     an instance of TrackPlaylists
+    pub fn playlists(&mut self) -> TrackPlaylists {
+        TrackPlaylists::new(self.client.clone(), self.id)
+    }
+
+    /// Reposts this track on behalf of the authenticated user.
+    pub async fn repost(&self) -> Result<()> {
+        self.client
+            .put(
+                &Endpoint::new("/me/track_reposts")
+                    .segment(self.id)
+                    .to_string(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Reposts this track on behalf of the authenticated user with a `caption`, which is shown
+    /// along with the repost.
+    pub async fn repost_with_caption(&self, caption: &str) -> Result<()> {
+        let url = self.client.api_url(
+            &Endpoint::new("/me/track_reposts")
+                .segment(self.id)
+                .to_string(),
+        )?;
+        let request = self
+            .client
+            .request(reqwest::Method::PUT, url)?
+            .json(&serde_json::json!({ "caption": caption }));
+        self.client.send(request).await?;
+
+        Ok(())
+    }
+
+    /// Removes the authenticated user's repost of this track.
+    pub async fn unrepost(&self) -> Result<()> {
+        self.client
+            .delete(
+                &Endpoint::new("/me/track_reposts")
+                    .segment(self.id)
+                    .to_string(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Updates the metadata of this track, which has to be owned by the authenticated user.
+    ///
+    /// Returns the updated track on success, an error otherwise.
+    pub async fn update(&self, update: TrackUpdate) -> Result<Track> {
+        let url = self
+            .client
+            .api_url(&Endpoint::new("/tracks").segment(self.id).to_string())?;
+        let request = self
+            .client
+            .request(reqwest::Method::PUT, url)?
+            .multipart(update.into_form());
+        let response = self.client.send(request).await?;
+        let track: Track = self.client.read_json(response).await?;
+
+        Ok(track)
+    }
+
+    /// Replaces the artwork of this track, which has to be owned by the authenticated user,
+    /// without changing its audio or other metadata.
+    ///
+    /// `data` is a JPEG or PNG image.
+    ///
+    /// Returns the updated track on success, an error otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use soundcloud::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let mut client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   client.authenticate_with_token(env!("SOUNDCLOUD_AUTH_TOKEN").to_owned());
+    ///   let artwork = std::fs::read("cover.jpg").unwrap();
+    ///   let track = client.track(263801976).set_artwork(artwork).await.unwrap();
+    ///
+    ///   println!("{:?}", track.artwork_url);
+    /// }
+    /// ```
+    pub async fn set_artwork<B: Into<Vec<u8>>>(&self, data: B) -> Result<Track> {
+        let data = data.into();
+        let file_name = if data.starts_with(b"\x89PNG") {
+            "artwork.png"
+        } else {
+            "artwork.jpg"
+        };
+
+        self.update(TrackUpdate {
+            artwork: Some(FileUpload::new(file_name, data)),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Deletes this track, which has to be owned by the authenticated user.
+    pub async fn delete(&self) -> Result<()> {
+        self.client
+            .delete(&Endpoint::new("/tracks").segment(self.id).to_string())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Retrieves the secret token of this private track, which has to be owned by the
+    /// authenticated user.
+    pub async fn secret_token_info(&self) -> Result<SecretToken> {
+        let no_params: Option<&[(&str, &str)]> = None;
+        let response = self
+            .client
+            .get(&self.secret_token_path(), no_params)
+            .await?;
+        let secret_token: SecretToken = self.client.read_json(response).await?;
+
+        Ok(secret_token)
+    }
+
+    /// Replaces the secret token of this private track with a new one, which invalidates all
+    /// share links with the previous token.
+    pub async fn refresh_secret_token(&self) -> Result<SecretToken> {
+        let response = self.client.put(&self.secret_token_path()).await?;
+        let secret_token: SecretToken = self.client.read_json(response).await?;
+
+        Ok(secret_token)
+    }
+
+    fn secret_token_path(&self) -> String {
+        Endpoint::new("/tracks")
+            .segment(self.id)
+            .segment("secret-token")
+            .to_string()
+    }
+
     /// Sends the request and return the tracks.
     pub async fn get(&mut self) -> Result<Track> {
-        let no_params: Option<&[(&str, &str)]> = None;
+        self.client
+            .fetch_secret::<Track>(self.id as u64, self.secret_token.as_deref())
+            .await
+    }
+
+    /// Checks whether the original file of this track can actually be downloaded, see
+    /// [`Client::download_info`].
+    pub async fn download_info(&self) -> Result<DownloadInfo> {
+        let track = self
+            .client
+            .fetch_secret::<Track>(self.id as u64, self.secret_token.as_deref())
+            .await?;
+
+        self.client.download_info(&track).await
+    }
+
+    /// Retrieve the current engagement counters of this track
+    ///
+    /// Only the counters are deserialized from the track payload, which makes this cheap to
+    /// poll.
+    pub async fn engagement(&self) -> Result<TrackEngagement> {
+        let params = self
+            .secret_token
+            .as_deref()
+            .map(|token| [("secret_token", token)]);
         let response = self
             .client
-            .get(&format!("/tracks/{}", self.id), no_params)
+            .get(
+                &Endpoint::new("/tracks").segment(self.id).to_string(),
+                params,
+            )
             .await?;
-        let track: Track = response.json().await?;
+        let engagement: TrackEngagement = self.client.read_json(response).await?;
 
-        Ok(track)
+        Ok(engagement)
+    }
+
+    /// Sends the request and return the track along with the metadata of the response.
+    pub async fn get_with_meta(&self) -> Result<(Track, ResponseMeta)> {
+        self.client
+            .fetch_secret_with_meta::<Track>(self.id as u64, self.secret_token.as_deref())
+            .await
     }
 }
 
 impl<'a> TrackRequestBuilder<'a> {
     /// Creates a new track request builder, with no set parameters.
-    pub fn new(client: &'a Client) -> TrackRequestBuilder {
+    pub fn new(client: &'a Client) -> TrackRequestBuilder<'a> {
         TrackRequestBuilder {
             client,
             query: None,
             tags: None,
             filter: None,
             license: None,
+            isrc: None,
             ids: None,
             duration: None,
             bpm: None,
@@ -123,7 +388,7 @@ impl<'a> TrackRequestBuilder<'a> {
     }
 
     /// Sets the search query filter, which will only return tracks with a matching query.
-    pub fn query<S>(&'a mut self, query: Option<S>) -> &mut TrackRequestBuilder
+    pub fn query<S>(&mut self, query: Option<S>) -> &mut Self
     where
         S: AsRef<str>,
     {
@@ -132,7 +397,7 @@ impl<'a> TrackRequestBuilder<'a> {
     }
 
     /// Sets the tags filter, which will only return tracks with a matching tag.
-    pub fn tags<I, T>(&'a mut self, tags: Option<I>) -> &mut TrackRequestBuilder
+    pub fn tags<I, T>(&mut self, tags: Option<I>) -> &mut Self
     where
         I: AsRef<[T]>,
         T: AsRef<str>,
@@ -144,7 +409,10 @@ impl<'a> TrackRequestBuilder<'a> {
         self
     }
 
-    pub fn genres<I, T>(&'a mut self, genres: Option<I>) -> &mut TrackRequestBuilder
+    /// Sets the genres filter, which will only return tracks with a matching genre.
+    ///
+    /// Accepts plain genre names as well as [`Genre`](crate::Genre) constants.
+    pub fn genres<I, T>(&mut self, genres: Option<I>) -> &mut Self
     where
         I: AsRef<[T]>,
         T: AsRef<str>,
@@ -157,29 +425,35 @@ impl<'a> TrackRequestBuilder<'a> {
     }
 
     /// Sets whether to filter private or public tracks.
-    pub fn filter(&'a mut self, filter: Option<Filter>) -> &mut TrackRequestBuilder {
+    pub fn filter(&mut self, filter: Option<Filter>) -> &mut Self {
         self.filter = filter;
         self
     }
 
     /// Sets the license filter.
-    pub fn license<S: AsRef<str>>(&'a mut self, license: Option<S>) -> &mut TrackRequestBuilder {
+    pub fn license<S: AsRef<str>>(&mut self, license: Option<S>) -> &mut Self {
         self.license = license.map(|s| s.as_ref().to_owned());
         self
     }
 
+    /// Sets the ISRC filter, which will only return tracks of the recording with the given code.
+    ///
+    /// The code is matched against the publisher metadata of the tracks, ignoring case and
+    /// hyphens. Unless a search query is set, the code is searched for as well.
+    pub fn isrc<S: AsRef<str>>(&mut self, isrc: Option<S>) -> &mut Self {
+        self.isrc = isrc.map(|s| normalize_isrc(s.as_ref()));
+        self
+    }
+
     /// Sets a list of track ids to look up.
-    pub fn ids(&'a mut self, ids: Option<Vec<usize>>) -> &mut TrackRequestBuilder {
+    pub fn ids(&mut self, ids: Option<Vec<usize>>) -> &mut Self {
         self.ids = ids;
         self
     }
 
     /// Returns a builder for a single track.
-    pub fn id(&'a mut self, id: usize) -> SingleTrackRequestBuilder {
-        SingleTrackRequestBuilder {
-            client: &self.client,
-            id,
-        }
+    pub fn id(&self, id: usize) -> SingleTrackRequestBuilder<'a> {
+        SingleTrackRequestBuilder::new(self.client, id)
     }
 
     /// Performs the request and returns a list of tracks or an error if one occurred.
@@ -188,15 +462,21 @@ impl<'a> TrackRequestBuilder<'a> {
 
         let response = self
             .client
-            .get("/tracks", Some(self.request_params()))
+            .get("/tracks", Some(&self.request_params()))
             .await?;
-        let track_list: Value = response.json().await?;
+        let track_list: Value = self.client.read_json(response).await?;
 
         if let Some(track_list) = track_list.as_array() {
-            let tracks: Vec<Track> = track_list
+            let mut tracks = track_list
                 .iter()
-                .map(|t| serde_json::from_value::<Track>(t.clone()).unwrap())
-                .collect();
+                .map(|t| serde_json::from_value::<Track>(t.clone()))
+                .collect::<serde_json::Result<Vec<_>>>()?;
+
+            if let Some(ref isrc) = self.isrc {
+                tracks.retain(|track| {
+                    track.isrc.as_deref().map(normalize_isrc).as_ref() == Some(isrc)
+                });
+            }
 
             Ok(tracks)
         } else {
@@ -206,42 +486,29 @@ impl<'a> TrackRequestBuilder<'a> {
         }
     }
 
-    fn request_params(&self) -> Vec<(&str, String)> {
-        let mut result = vec![];
-
-        if let Some(ref query) = self.query {
-            result.push(("q", query.clone()));
-        }
-
-        if let Some(ref tags) = self.tags {
-            result.push(("tags", tags.clone()));
-        }
-
-        if let Some(ref filter) = self.filter {
-            result.push(("filter", filter.to_str().to_owned()));
-        }
-
-        if let Some(ref ids) = self.ids {
-            let ids_as_strings: Vec<String> = ids.iter().map(|id| format!("{}", id)).collect();
-            result.push(("ids", ids_as_strings.join(",")));
-        }
-
-        if let Some(ref _duration) = self.duration {
-            unimplemented!();
-        }
+    fn request_params(&self) -> QueryParams {
+        let mut params = QueryParams::new();
 
-        if let Some(ref _bpm) = self.bpm {
+        if self.duration.is_some() || self.bpm.is_some() {
             unimplemented!();
         }
 
-        if let Some(ref genres) = self.genres {
-            result.push(("genres", genres.clone()));
-        }
-
-        if let Some(ref types) = self.types {
-            result.push(("types", types.clone()));
-        }
-
-        result
+        params
+            .push_opt("q", self.query.as_ref().or(self.isrc.as_ref()))
+            .push_opt("tags", self.tags.as_ref())
+            .push_opt("filter", self.filter.as_ref().map(Filter::to_str))
+            .push_opt("license", self.license.as_ref())
+            .push_list("ids", self.ids.as_ref())
+            .push_opt("genres", self.genres.as_ref())
+            .push_opt("types", self.types.as_ref());
+        params
     }
 }
+
+/// Returns the ISRC in upper case and without hyphens, e.g. `USRC17607839`.
+fn normalize_isrc(isrc: &str) -> String {
+    isrc.chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
@@ -1,7 +1,9 @@
 use futures::stream::BoxStream;
 
+use crate::endpoint::Endpoint;
 use crate::error::Result;
-use crate::models::User;
+use crate::models::{TrackLiker, User};
+use crate::page::PageOptions;
 use crate::streaming_api::StreamingApi;
 use crate::Client;
 
@@ -16,16 +18,31 @@ impl TrackLikers {
     pub fn new(client: Client, track_id: usize) -> Self {
         TrackLikers { client, track_id }
     }
+
+    /// Return a stream of the likes of the track, with the time of each like along with the
+    /// user who liked it.
+    pub fn iter_with_timestamps(&self, options: PageOptions) -> BoxStream<'_, Result<TrackLiker>> {
+        let path = Endpoint::new("/tracks")
+            .segment(self.track_id)
+            .segment("likes")
+            .to_string();
+        let url = options.clamp_page_size(self.max_page_size()).apply(&path);
+
+        self.client.get_stream(&url, None)
+    }
 }
 
 impl StreamingApi for TrackLikers {
     type Model = User;
 
     fn path(&self) -> String {
-        format!("/tracks/{}/favoriters", self.track_id)
+        Endpoint::new("/tracks")
+            .segment(self.track_id)
+            .segment("favoriters")
+            .to_string()
     }
 
-    fn get_stream(&self, url: &str, pages: Option<u64>) -> BoxStream<'_, Result<Self::Model>> {
-        self.client.get_stream(url, pages)
+    fn client(&self) -> &Client {
+        &self.client
     }
 }
@@ -0,0 +1,32 @@
+use crate::endpoint::Endpoint;
+use crate::models::Playlist;
+use crate::streaming_api::StreamingApi;
+use crate::Client;
+
+/// Provides access to the playlists containing a track, excluding albums
+pub struct TrackPlaylists {
+    client: Client,
+    track_id: usize,
+}
+
+impl TrackPlaylists {
+    /// create a new instance of the playlists containing a souncloud track
+    pub fn new(client: Client, track_id: usize) -> Self {
+        TrackPlaylists { client, track_id }
+    }
+}
+
+impl StreamingApi for TrackPlaylists {
+    type Model = Playlist;
+
+    fn path(&self) -> String {
+        Endpoint::new("/tracks")
+            .segment(self.track_id)
+            .segment("playlists_without_albums")
+            .to_string()
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
@@ -0,0 +1,24 @@
+use reqwest::multipart::Part;
+
+/// A file to be uploaded, e.g. an avatar or artwork image.
+#[derive(Debug, Clone)]
+pub struct FileUpload {
+    /// Name of the file, including its extension.
+    pub file_name: String,
+    /// Binary contents of the file.
+    pub data: Vec<u8>,
+}
+
+impl FileUpload {
+    /// Creates a new upload from a file name and its contents.
+    pub fn new<S: Into<String>>(file_name: S, data: Vec<u8>) -> Self {
+        FileUpload {
+            file_name: file_name.into(),
+            data,
+        }
+    }
+
+    pub(crate) fn into_part(self) -> Part {
+        Part::bytes(self.data).file_name(self.file_name)
+    }
+}
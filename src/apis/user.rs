@@ -1,6 +1,10 @@
-use crate::apis::{Comments, Followers, Followings, Likes, Playlists, Tracks, WebProfiles};
+use crate::apis::{
+    Followers, Followings, LikedPlaylists, Likes, Playlists, Tracks, UserReposts, WebProfiles,
+};
 use crate::error::{Error, Result};
 use crate::models::User;
+use crate::query_params::QueryParams;
+use crate::response_meta::ResponseMeta;
 use crate::Client;
 
 #[derive(Debug)]
@@ -25,7 +29,7 @@ impl<'a> UserRequestBuilder<'a> {
     }
 
     /// Sets the search query filter, which will only return tracks with a matching query.
-    pub fn query<S>(&'a mut self, query: Option<S>) -> &mut UserRequestBuilder
+    pub fn query<S>(&mut self, query: Option<S>) -> &mut Self
     where
         S: AsRef<str>,
     {
@@ -34,7 +38,7 @@ impl<'a> UserRequestBuilder<'a> {
     }
 
     /// Returns a builder for a user request
-    pub fn id(&self, id: usize) -> SingleUserRequestBuilder {
+    pub fn id(&self, id: usize) -> SingleUserRequestBuilder<'a> {
         SingleUserRequestBuilder {
             client: self.client,
             id,
@@ -48,35 +52,48 @@ impl<'a> UserRequestBuilder<'a> {
     ///     a builder for a user request
     pub async fn permalink(&self, permalink: &str) -> Result<SingleUserRequestBuilder<'a>> {
         let permalink_url = &format!("https://soundcloud.com/{}", permalink);
-        let resource_url = self.client.resolve(permalink_url).await?;
-        let id = resource_url
-            .path_segments()
-            .map(|c| c.collect::<Vec<_>>())
-            .unwrap()
-            .pop()
-            .unwrap();
-        let id = usize::from_str_radix(id, 10).unwrap();
+        let (id, _) = self.client.resolve_id(permalink_url).await?;
         Ok(SingleUserRequestBuilder {
             client: self.client,
             id,
         })
     }
 
+    /// Searches for users named `username` and returns the best match, if any.
+    ///
+    /// Users with exactly this username are preferred over users whose username only differs in
+    /// case, followed by users with this full name. Ties are broken by the number of followers.
+    pub async fn exact_username(&self, username: &str) -> Result<Option<User>> {
+        let users = UserRequestBuilder::new(self.client)
+            .query(Some(username))
+            .get()
+            .await?;
+
+        let best = users
+            .into_iter()
+            .map(|user| (username_score(&user, username), user))
+            .filter(|(score, _)| *score > 0)
+            .max_by_key(|(score, user)| (*score, user.followers_count.unwrap_or(0)))
+            .map(|(_, user)| user);
+
+        Ok(best)
+    }
+
     /// Performs the request and returns a list of users or an error if one occurred.
     pub async fn get(&mut self) -> Result<Vec<User>> {
         use serde_json::Value;
 
         let response = self
             .client
-            .get("/users", Some(self.request_params()))
+            .get("/users", Some(&self.request_params()))
             .await?;
-        let user_list: Value = response.json().await?;
+        let user_list: Value = self.client.read_json(response).await?;
 
         if let Some(user_list) = user_list.as_array() {
-            let users: Vec<User> = user_list
+            let users = user_list
                 .iter()
-                .map(|t| serde_json::from_value::<User>(t.clone()).unwrap())
-                .collect();
+                .map(|t| serde_json::from_value::<User>(t.clone()))
+                .collect::<serde_json::Result<Vec<_>>>()?;
 
             Ok(users)
         } else {
@@ -86,14 +103,29 @@ impl<'a> UserRequestBuilder<'a> {
         }
     }
 
-    fn request_params(&self) -> Vec<(&str, String)> {
-        let mut result = vec![];
-
-        if let Some(ref query) = self.query {
-            result.push(("q", query.clone()));
-        }
+    fn request_params(&self) -> QueryParams {
+        let mut params = QueryParams::new();
+        params.push_opt("q", self.query.as_ref());
+        params
+    }
+}
 
-        result
+/// Scores how well the `user` matches the `username`, 0 meaning not at all.
+fn username_score(user: &User, username: &str) -> u8 {
+    let username = username.trim();
+
+    if user.username == username {
+        3
+    } else if user.username.eq_ignore_ascii_case(username) {
+        2
+    } else if user
+        .full_name
+        .as_deref()
+        .is_some_and(|full_name| full_name.eq_ignore_ascii_case(username))
+    {
+        1
+    } else {
+        0
     }
 }
 
@@ -119,6 +151,22 @@ impl<'a> SingleUserRequestBuilder<'a> {
         Likes::new(self.client.clone(), self.id)
     }
 
+    /// Retrieve all playlists and albums liked by the user
+    ///
+    /// Returns:
+    ///     an instance of LikedPlaylists
+    pub fn liked_playlists(&mut self) -> LikedPlaylists {
+        LikedPlaylists::new(self.client.clone(), self.id)
+    }
+
+    /// Retrieve all tracks and playlists reposted by the user, along with their captions
+    ///
+    /// Returns:
+    ///     an instance of UserReposts
+    pub fn reposts(&mut self) -> UserReposts {
+        UserReposts::new(self.client.clone(), self.id)
+    }
+
     /// Retrieve all playlists uploaded by the user
     ///
     /// Returns:
@@ -156,13 +204,11 @@ impl<'a> SingleUserRequestBuilder<'a> {
     /// Returns:
     ///     User data in JSON format
     pub async fn get(&mut self) -> Result<User> {
-        let no_params: Option<&[(&str, &str)]> = None;
-        let response = self
-            .client
-            .get(&format!("/users/{}", self.id), no_params)
-            .await?;
-        let user: User = response.json().await?;
+        self.client.fetch::<User>(self.id).await
+    }
 
-        Ok(user)
+    /// Retrieve the user along with the metadata of the response
+    pub async fn get_with_meta(&self) -> Result<(User, ResponseMeta)> {
+        self.client.fetch_with_meta::<User>(self.id).await
     }
 }
@@ -1,7 +1,5 @@
-use futures::stream::BoxStream;
-
-use crate::error::Result;
-use crate::models::User;
+use crate::endpoint::Endpoint;
+use crate::models::{Follow, User};
 use crate::streaming_api::StreamingApi;
 use crate::Client;
 
@@ -16,16 +14,75 @@ impl Followers {
     pub fn new(client: Client, user_id: usize) -> Self {
         Followers { client, user_id }
     }
+
+    /// Includes the time of each follow, which the API only returns for authenticated requests
+    ///
+    /// Returns:
+    ///     an instance of TimestampedFollowers
+    pub fn with_timestamps(&self) -> TimestampedFollowers {
+        TimestampedFollowers {
+            client: self.client.clone(),
+            user_id: self.user_id,
+        }
+    }
 }
 
 impl StreamingApi for Followers {
     type Model = User;
 
     fn path(&self) -> String {
-        format!("/users/{}/followers", self.user_id)
+        Endpoint::new("/users")
+            .segment(self.user_id)
+            .segment("followers")
+            .to_string()
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+/// Provides access to a user's followers, along with the time of each follow
+pub struct TimestampedFollowers {
+    client: Client,
+    user_id: usize,
+}
+
+impl StreamingApi for TimestampedFollowers {
+    type Model = Follow;
+
+    fn path(&self) -> String {
+        Endpoint::new("/users")
+            .segment(self.user_id)
+            .segment("followers")
+            .to_string()
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+/// Provides access to the authenticated user's followers
+pub struct MyFollowers {
+    client: Client,
+}
+
+impl MyFollowers {
+    /// create a new instance of the authenticated souncloud user's followers
+    pub fn new(client: Client) -> Self {
+        MyFollowers { client }
+    }
+}
+
+impl StreamingApi for MyFollowers {
+    type Model = User;
+
+    fn path(&self) -> String {
+        "/me/followers".to_owned()
     }
 
-    fn get_stream(&self, url: &str, pages: Option<u64>) -> BoxStream<'_, Result<Self::Model>> {
-        self.client.get_stream(url, pages)
+    fn client(&self) -> &Client {
+        &self.client
     }
 }
@@ -1,7 +1,5 @@
-use futures::stream::BoxStream;
-
-use crate::error::Result;
-use crate::models::User;
+use crate::endpoint::Endpoint;
+use crate::models::{Follow, User};
 use crate::streaming_api::StreamingApi;
 use crate::Client;
 
@@ -16,16 +14,75 @@ impl Followings {
     pub fn new(client: Client, user_id: usize) -> Self {
         Followings { client, user_id }
     }
+
+    /// Includes the time of each follow, which the API only returns for authenticated requests
+    ///
+    /// Returns:
+    ///     an instance of TimestampedFollowings
+    pub fn with_timestamps(&self) -> TimestampedFollowings {
+        TimestampedFollowings {
+            client: self.client.clone(),
+            user_id: self.user_id,
+        }
+    }
 }
 
 impl StreamingApi for Followings {
     type Model = User;
 
     fn path(&self) -> String {
-        format!("/users/{}/followings", self.user_id)
+        Endpoint::new("/users")
+            .segment(self.user_id)
+            .segment("followings")
+            .to_string()
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+/// Provides access to a user's followings, along with the time of each follow
+pub struct TimestampedFollowings {
+    client: Client,
+    user_id: usize,
+}
+
+impl StreamingApi for TimestampedFollowings {
+    type Model = Follow;
+
+    fn path(&self) -> String {
+        Endpoint::new("/users")
+            .segment(self.user_id)
+            .segment("followings")
+            .to_string()
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+/// Provides access to the authenticated user's followings
+pub struct MyFollowings {
+    client: Client,
+}
+
+impl MyFollowings {
+    /// create a new instance of the authenticated souncloud user's followings
+    pub fn new(client: Client) -> Self {
+        MyFollowings { client }
+    }
+}
+
+impl StreamingApi for MyFollowings {
+    type Model = User;
+
+    fn path(&self) -> String {
+        "/me/followings".to_owned()
     }
 
-    fn get_stream(&self, url: &str, pages: Option<u64>) -> BoxStream<'_, Result<Self::Model>> {
-        self.client.get_stream(url, pages)
+    fn client(&self) -> &Client {
+        &self.client
     }
 }
@@ -0,0 +1,32 @@
+use crate::endpoint::Endpoint;
+use crate::models::PlaylistLike;
+use crate::streaming_api::StreamingApi;
+use crate::Client;
+
+/// Provides access to operations available for a user's liked playlists and albums
+pub struct LikedPlaylists {
+    client: Client,
+    user_id: usize,
+}
+
+impl LikedPlaylists {
+    /// create a new instance of a souncloud user's liked playlists
+    pub fn new(client: Client, user_id: usize) -> Self {
+        LikedPlaylists { client, user_id }
+    }
+}
+
+impl StreamingApi for LikedPlaylists {
+    type Model = PlaylistLike;
+
+    fn path(&self) -> String {
+        Endpoint::new("/users")
+            .segment(self.user_id)
+            .segment("playlist_likes")
+            .to_string()
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
@@ -1,6 +1,4 @@
-use futures::stream::BoxStream;
-
-use crate::error::Result;
+use crate::endpoint::Endpoint;
 use crate::models::Playlist;
 use crate::streaming_api::StreamingApi;
 use crate::Client;
@@ -15,11 +13,14 @@ impl StreamingApi for Playlists {
     type Model = Playlist;
 
     fn path(&self) -> String {
-        format!("/users/{}/playlists", self.user_id)
+        Endpoint::new("/users")
+            .segment(self.user_id)
+            .segment("playlists")
+            .to_string()
     }
 
-    fn get_stream(&self, url: &str, pages: Option<u64>) -> BoxStream<'_, Result<Self::Model>> {
-        self.client.get_stream(url, pages)
+    fn client(&self) -> &Client {
+        &self.client
     }
 }
 
@@ -0,0 +1,32 @@
+use crate::endpoint::Endpoint;
+use crate::models::Repost;
+use crate::streaming_api::StreamingApi;
+use crate::Client;
+
+/// Provides access to the tracks and playlists reposted by a user
+pub struct UserReposts {
+    client: Client,
+    user_id: usize,
+}
+
+impl UserReposts {
+    /// create a new instance of a souncloud user's reposts
+    pub fn new(client: Client, user_id: usize) -> Self {
+        UserReposts { client, user_id }
+    }
+}
+
+impl StreamingApi for UserReposts {
+    type Model = Repost;
+
+    fn path(&self) -> String {
+        Endpoint::new("/stream/users")
+            .segment(self.user_id)
+            .segment("reposts")
+            .to_string()
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
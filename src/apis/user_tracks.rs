@@ -1,6 +1,4 @@
-use futures::stream::BoxStream;
-
-use crate::error::Result;
+use crate::endpoint::Endpoint;
 use crate::models::Track;
 use crate::streaming_api::StreamingApi;
 use crate::Client;
@@ -22,10 +20,13 @@ impl StreamingApi for Tracks {
     type Model = Track;
 
     fn path(&self) -> String {
-        format!("/users/{}/tracks", self.user_id)
+        Endpoint::new("/users")
+            .segment(self.user_id)
+            .segment("tracks")
+            .to_string()
     }
 
-    fn get_stream(&self, url: &str, pages: Option<u64>) -> BoxStream<'_, Result<Self::Model>> {
-        self.client.get_stream(url, pages)
+    fn client(&self) -> &Client {
+        &self.client
     }
 }
@@ -1,7 +1,5 @@
-use futures::stream::BoxStream;
-
 use crate::client::Client;
-use crate::error::Result;
+use crate::endpoint::Endpoint;
 use crate::models::WebProfile;
 use crate::streaming_api::StreamingApi;
 
@@ -22,10 +20,13 @@ impl StreamingApi for WebProfiles {
     type Model = WebProfile;
 
     fn path(&self) -> String {
-        format!("/users/{}/web-profiles", self.user_id)
+        Endpoint::new("/users")
+            .segment(self.user_id)
+            .segment("web-profiles")
+            .to_string()
     }
 
-    fn get_stream(&self, url: &str, pages: Option<u64>) -> BoxStream<Result<Self::Model>> {
-        self.client.get_stream(url, pages)
+    fn client(&self) -> &Client {
+        &self.client
     }
 }
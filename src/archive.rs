@@ -0,0 +1,405 @@
+//! Archiving the tracks of a user into a directory.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+use futures::io::AllowStdIo;
+use futures::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::error::{Error, Result};
+use crate::export::file_name;
+use crate::models::{Quality, Subscription, Track, Transcoding};
+use crate::page::PageOptions;
+use crate::streaming_api::StreamingApiExt;
+
+/// Name of the manifest file in an archive directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Number of tracks requested per page while archiving.
+const ARCHIVE_PAGE_SIZE: u32 = 200;
+
+/// Options of an archive, see [`Client::archive_user`](crate::Client::archive_user).
+#[derive(Clone, Debug)]
+pub struct ArchiveOptions {
+    pub(crate) concurrency: usize,
+    pub(crate) originals: bool,
+}
+
+impl Default for ArchiveOptions {
+    fn default() -> Self {
+        ArchiveOptions {
+            concurrency: 4,
+            originals: true,
+        }
+    }
+}
+
+impl ArchiveOptions {
+    pub fn builder() -> ArchiveOptionsBuilder {
+        ArchiveOptionsBuilder::new()
+    }
+}
+
+/// a mutable archive options builder
+pub struct ArchiveOptionsBuilder(ArchiveOptions);
+
+impl ArchiveOptionsBuilder {
+    fn new() -> Self {
+        ArchiveOptionsBuilder(ArchiveOptions::default())
+    }
+
+    /// Sets the number of tracks downloaded at the same time, which defaults to 4.
+    pub fn concurrency(&mut self, concurrency: usize) -> &mut Self {
+        self.0.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Sets whether the original files are downloaded where allowed, which is enabled by
+    /// default. Otherwise, or if there is no original, the stream is saved.
+    pub fn originals(&mut self, originals: bool) -> &mut Self {
+        self.0.originals = originals;
+        self
+    }
+
+    pub fn build(&self) -> ArchiveOptions {
+        self.0.clone()
+    }
+}
+
+/// Where the audio of an archived track comes from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioSource {
+    /// The original file, as uploaded.
+    Original,
+    /// The best stream available with the subscription of the client.
+    Stream,
+}
+
+/// Track saved in an archive.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct ArchivedTrack {
+    /// Integer ID.
+    pub id: u64,
+    /// Title, at the time of archiving.
+    pub title: String,
+    /// Time of which the track was uploaded, as an unparsed string.
+    pub created_at: String,
+    /// Name of the audio file, relative to the archive directory.
+    pub file: String,
+    /// Where the audio comes from.
+    pub source: AudioSource,
+    /// Size of the audio file in bytes.
+    pub size: u64,
+    /// Quality of the stream, if the audio comes from one of the encodings of the track.
+    #[serde(default)]
+    pub quality: Option<Quality>,
+}
+
+/// Contents of an archive, stored as [`MANIFEST_FILE`] in the archive directory.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct Manifest {
+    /// ID of the archived user.
+    pub user_id: usize,
+    /// The archived tracks, in the order they were saved.
+    pub tracks: Vec<ArchivedTrack>,
+    /// Upload time of the newest track of the last complete run, as an unparsed string.
+    ///
+    /// [`Client::archive_update`](crate::Client::archive_update) only saves tracks uploaded
+    /// after it.
+    #[serde(default)]
+    pub watermark: Option<String>,
+    /// IDs of archived tracks which have since been deleted from SoundCloud or made private, as
+    /// recorded by [`Client::archive_deletions`](crate::Client::archive_deletions).
+    ///
+    /// Their files are kept in the archive.
+    #[serde(default)]
+    pub deleted: Vec<u64>,
+}
+
+impl Manifest {
+    /// Reads the manifest of the archive in `dir`, if there is one.
+    pub async fn read<P: AsRef<Path>>(dir: P) -> Result<Option<Manifest>> {
+        match fs::read(dir.as_ref().join(MANIFEST_FILE)) {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Replaces the manifest of the archive in `dir`.
+    async fn write(&self, dir: &Path) -> Result<()> {
+        let path = dir.join(MANIFEST_FILE);
+        let partial = partial_path(&path);
+        fs::write(&partial, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(&partial, &path)?;
+        Ok(())
+    }
+}
+
+pub(crate) async fn archive_user(
+    client: &Client,
+    user_id: usize,
+    dir: &Path,
+    options: &ArchiveOptions,
+) -> Result<Manifest> {
+    fs::create_dir_all(dir)?;
+
+    let mut manifest = match Manifest::read(dir).await? {
+        Some(manifest) if manifest.user_id != user_id => {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} contains an archive of user {}",
+                    dir.display(),
+                    manifest.user_id
+                ),
+            )))
+        }
+        Some(manifest) => manifest,
+        None => Manifest {
+            user_id,
+            tracks: vec![],
+            watermark: None,
+            deleted: vec![],
+        },
+    };
+
+    // Resume by skipping the tracks which have already been saved completely.
+    let mut saved = HashSet::new();
+    for track in manifest.tracks.iter() {
+        if fs::metadata(dir.join(&track.file)).is_ok() {
+            saved.insert(track.id);
+        }
+    }
+    manifest.tracks.retain(|track| saved.contains(&track.id));
+
+    let tracks: Vec<Track> = user_tracks(client, user_id)
+        .await?
+        .into_iter()
+        .filter(|track| !saved.contains(&track.id))
+        .collect();
+
+    save(client, dir, manifest, tracks, options).await
+}
+
+pub(crate) async fn archive_update(
+    client: &Client,
+    dir: &Path,
+    options: &ArchiveOptions,
+) -> Result<Manifest> {
+    let manifest = existing_manifest(dir).await?;
+
+    let tracks = match manifest.watermark {
+        Some(ref watermark) => newer_tracks(client, manifest.user_id, watermark).await?,
+        None => user_tracks(client, manifest.user_id).await?,
+    };
+    let archived: HashSet<u64> = manifest.tracks.iter().map(|track| track.id).collect();
+    let tracks = tracks
+        .into_iter()
+        .filter(|track| !archived.contains(&track.id))
+        .collect();
+
+    save(client, dir, manifest, tracks, options).await
+}
+
+pub(crate) async fn archive_deletions(client: &Client, dir: &Path) -> Result<Manifest> {
+    let mut manifest = existing_manifest(dir).await?;
+
+    let tracks = user_tracks(client, manifest.user_id).await?;
+    let listed: HashSet<u64> = tracks.iter().map(|track| track.id).collect();
+
+    manifest.deleted.retain(|id| !listed.contains(id));
+    for track in manifest.tracks.iter() {
+        if !listed.contains(&track.id) && !manifest.deleted.contains(&track.id) {
+            manifest.deleted.push(track.id);
+        }
+    }
+    manifest.write(dir).await?;
+
+    Ok(manifest)
+}
+
+/// Reads the manifest of the archive in `dir`, failing if there is none.
+async fn existing_manifest(dir: &Path) -> Result<Manifest> {
+    match Manifest::read(dir).await? {
+        Some(manifest) => Ok(manifest),
+        None => Err(Error::Io(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} contains no archive", dir.display()),
+        ))),
+    }
+}
+
+/// Returns all tracks of the user.
+async fn user_tracks(client: &Client, user_id: usize) -> Result<Vec<Track>> {
+    let page_options = PageOptions::builder().page_size(ARCHIVE_PAGE_SIZE).build();
+    client
+        .user(user_id)
+        .tracks()
+        .iter(page_options)
+        .try_collect()
+        .await
+}
+
+/// Returns the tracks of the user uploaded after the `watermark`.
+///
+/// The tracks are listed from the newest to the oldest, so only the pages up to the first track
+/// at or before the watermark are requested.
+async fn newer_tracks(client: &Client, user_id: usize, watermark: &str) -> Result<Vec<Track>> {
+    let page_options = PageOptions::builder().page_size(ARCHIVE_PAGE_SIZE).build();
+    client
+        .user(user_id)
+        .tracks()
+        .iter(page_options)
+        .try_take_while(|track| future::ok(track.created_at.as_str() > watermark))
+        .try_collect()
+        .await
+}
+
+/// Saves the `tracks` in `dir`, adding them to the `manifest` as they complete.
+///
+/// The watermark only advances once all tracks have been saved or skipped, as they complete out
+/// of order and an interrupted update has to list the older ones again.
+async fn save(
+    client: &Client,
+    dir: &Path,
+    mut manifest: Manifest,
+    tracks: Vec<Track>,
+    options: &ArchiveOptions,
+) -> Result<Manifest> {
+    let newest = tracks
+        .iter()
+        .map(|track| &track.created_at)
+        .chain(manifest.tracks.iter().map(|track| &track.created_at))
+        .max()
+        .cloned();
+    let subscription = if client.is_authenticated() {
+        client.subscription().await?
+    } else {
+        Subscription::Free
+    };
+
+    let mut archived = stream::iter(tracks)
+        .map(|track| async move { archive_track(client, dir, &track, subscription, options).await })
+        .buffer_unordered(options.concurrency);
+
+    while let Some(track) = archived.try_next().await? {
+        if let Some(track) = track {
+            manifest.tracks.push(track);
+            manifest.write(dir).await?;
+        }
+    }
+    manifest.watermark = match (manifest.watermark.take(), newest) {
+        (Some(watermark), Some(newest)) => Some(watermark.max(newest)),
+        (watermark, newest) => watermark.or(newest),
+    };
+    manifest.write(dir).await?;
+
+    Ok(manifest)
+}
+
+/// Saves the audio of the `track` in `dir`, or returns `None` if it can't be retrieved.
+async fn archive_track(
+    client: &Client,
+    dir: &Path,
+    track: &Track,
+    subscription: Subscription,
+    options: &ArchiveOptions,
+) -> Result<Option<ArchivedTrack>> {
+    let original = if options.originals && track.downloadable && track.download_url.is_some() {
+        let info = client.download_info(track).await?;
+        if info.available {
+            Some(info.format().unwrap_or_else(|| "bin".to_owned()))
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    let transcoding = best_transcoding(track, subscription);
+    let (source, extension) = match (original, transcoding) {
+        (Some(format), _) => (AudioSource::Original, format),
+        (None, Some(transcoding)) => (AudioSource::Stream, extension(transcoding).to_owned()),
+        (None, None) => (AudioSource::Stream, "mp3".to_owned()),
+    };
+    let file = format!("{} - {}.{}", track.id, file_name(&track.title), extension);
+    let path = dir.join(&file);
+    let partial = partial_path(&path);
+    let mut writer = AllowStdIo::new(BufWriter::new(fs::File::create(&partial)?));
+
+    let (result, quality) = match (source, transcoding) {
+        (AudioSource::Original, _) => (client.download(track, &mut writer).await, None),
+        (AudioSource::Stream, Some(transcoding)) => (
+            client.stream_transcoding(transcoding, &mut writer).await,
+            Some(transcoding.quality),
+        ),
+        (AudioSource::Stream, None) => (client.stream(track, &mut writer).await, None),
+    };
+    let result = match result {
+        Ok(size) => writer.close().await.map(|_| size).map_err(Error::from),
+        Err(error) => Err(error),
+    };
+    let size = match result {
+        Ok(size) => size,
+        Err(error @ Error::TrackNotStreamable) | Err(error @ Error::GeoBlocked) => {
+            log::warn!("skipping track {}: {}", track.id, error);
+            drop(writer);
+            fs::remove_file(&partial)?;
+            return Ok(None);
+        }
+        Err(error) => {
+            drop(writer);
+            let _ = fs::remove_file(&partial);
+            return Err(error);
+        }
+    };
+    drop(writer);
+    fs::rename(&partial, &path)?;
+
+    Ok(Some(ArchivedTrack {
+        id: track.id,
+        title: track.title.clone(),
+        created_at: track.created_at.clone(),
+        file,
+        source,
+        size,
+        quality,
+    }))
+}
+
+/// Returns the progressive encoding of the highest quality the track can be streamed in with the
+/// `subscription`.
+fn best_transcoding(track: &Track, subscription: Subscription) -> Option<&Transcoding> {
+    track
+        .available_qualities(subscription)
+        .into_iter()
+        .rev()
+        .filter_map(|quality| track.transcoding(quality, subscription).ok())
+        .find(|transcoding| transcoding.format.protocol == "progressive")
+}
+
+/// Returns the file extension of the container of the `transcoding`.
+fn extension(transcoding: &Transcoding) -> &'static str {
+    let mime_type = transcoding.format.mime_type.split(';').next().unwrap_or("");
+    match mime_type.trim() {
+        "audio/mpeg" => "mp3",
+        "audio/mp4" | "audio/aac" => "m4a",
+        "audio/ogg" => "ogg",
+        _ => "bin",
+    }
+}
+
+/// Returns the path a file is written to before it's complete.
+fn partial_path(path: &Path) -> PathBuf {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    PathBuf::from(partial)
+}
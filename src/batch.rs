@@ -0,0 +1,116 @@
+use std::future::Future;
+
+use futures::prelude::*;
+
+use crate::client::Client;
+use crate::error::{Error, Result};
+use crate::models::{Playlist, Track, User};
+
+/// Outcome of a batch of requests.
+#[derive(Debug)]
+pub struct BatchResult<T> {
+    /// Successfully fetched items, in the order of their ids.
+    pub items: Vec<T>,
+    /// Ids which failed to be fetched, along with their error.
+    pub errors: Vec<(usize, Error)>,
+}
+
+impl<T> BatchResult<T> {
+    /// Returns true if all requests of the batch succeeded.
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Fetches many resources concurrently with a bounded number of requests in flight.
+///
+/// # Examples
+///
+/// ```
+/// use soundcloud::Client;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+///   let result = client.batch(4).tracks(vec![262681089, 263801976]).await;
+///
+///   assert!(result.is_complete());
+///   assert_eq!(result.items.len(), 2);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BatchFetcher {
+    client: Client,
+    limit: usize,
+}
+
+impl BatchFetcher {
+    /// Creates a new fetcher performing up to `limit` requests concurrently.
+    pub fn new(client: Client, limit: usize) -> Self {
+        BatchFetcher {
+            client,
+            limit: limit.max(1),
+        }
+    }
+
+    /// Fetches the tracks with the given ids.
+    pub async fn tracks<I>(&self, ids: I) -> BatchResult<Track>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.run(
+            ids,
+            |client, id| async move { client.track(id).get().await },
+        )
+        .await
+    }
+
+    /// Fetches the users with the given ids.
+    pub async fn users<I>(&self, ids: I) -> BatchResult<User>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.run(ids, |client, id| async move { client.user(id).get().await })
+            .await
+    }
+
+    /// Fetches the playlists with the given ids.
+    pub async fn playlists<I>(&self, ids: I) -> BatchResult<Playlist>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.run(
+            ids,
+            |client, id| async move { client.playlist(id).get().await },
+        )
+        .await
+    }
+
+    /// Runs `fetch` for each of the ids, collecting the results and errors.
+    pub async fn run<I, T, F, Fut>(&self, ids: I, fetch: F) -> BatchResult<T>
+    where
+        I: IntoIterator<Item = usize>,
+        F: Fn(Client, usize) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let results: Vec<(usize, Result<T>)> = stream::iter(ids)
+            .map(|id| fetch(self.client.clone(), id).map(move |result| (id, result)))
+            .buffered(self.limit)
+            .collect()
+            .await;
+
+        let mut batch = BatchResult {
+            items: Vec::with_capacity(results.len()),
+            errors: vec![],
+        };
+
+        for (id, result) in results {
+            match result {
+                Ok(item) => batch.items.push(item),
+                Err(error) => batch.errors.push((id, error)),
+            }
+        }
+
+        batch
+    }
+}
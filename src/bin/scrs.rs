@@ -0,0 +1,173 @@
+//! Command line client for SoundCloud, built with the `cli` feature.
+//!
+//! Reads the client id from `SOUNDCLOUD_CLIENT_ID` and, for private resources, an auth token from
+//! `SOUNDCLOUD_AUTH_TOKEN`.
+
+use std::env;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::process;
+
+use futures::io::AllowStdIo;
+use futures::prelude::*;
+use soundcloud::fmt::{format_count, format_duration};
+use soundcloud::{Client, SearchResult, StreamingApiExt};
+
+const USAGE: &str = "\
+Usage: scrs <command> [arguments]
+
+Commands:
+    search <query> [--limit <n>]    Search tracks, users and playlists
+    resolve <url>                   Print the API url of a SoundCloud url
+    download <track url> [file]     Download a track, or stream it if it isn't downloadable
+    export <user url or id> <dir>   Archive all tracks of a user into a directory";
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let client = match client() {
+        Ok(client) => client,
+        Err(error) => exit_with(&error.to_string()),
+    };
+    let result = match args.as_slice() {
+        ["search", query] => search(&client, query, 10).await,
+        ["search", query, "--limit", limit] => match limit.parse() {
+            Ok(limit) => search(&client, query, limit).await,
+            Err(_) => exit_with(USAGE),
+        },
+        ["resolve", url] => resolve(&client, url).await,
+        ["download", url] => download(&client, url, None).await,
+        ["download", url, file] => download(&client, url, Some(PathBuf::from(file))).await,
+        ["export", user, dir] => export(&client, user, dir).await,
+        _ => exit_with(USAGE),
+    };
+
+    if let Err(error) = result {
+        exit_with(&format!("error: {}", error));
+    }
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1)
+}
+
+fn client() -> Result<Client> {
+    let client_id =
+        env::var("SOUNDCLOUD_CLIENT_ID").map_err(|_| "SOUNDCLOUD_CLIENT_ID is not set")?;
+    let mut client = Client::try_new(&client_id)?;
+    if let Ok(token) = env::var("SOUNDCLOUD_AUTH_TOKEN") {
+        client.authenticate_with_token(token);
+    }
+
+    Ok(client)
+}
+
+async fn search(client: &Client, query: &str, limit: usize) -> Result<()> {
+    let search = client.search(query).dedupe(true);
+    let mut results = search.iter(Default::default()).take(limit);
+
+    while let Some(result) = results.try_next().await? {
+        match result {
+            SearchResult::Track(track) => println!(
+                "track     {:>12}  {} - {} [{}, {} plays]",
+                track.id,
+                track.user.username,
+                track.title,
+                track.duration_hms(),
+                format_count(track.playback_count.unwrap_or(0))
+            ),
+            SearchResult::User(user) => println!(
+                "user      {:>12}  {} [{} followers]",
+                user.id,
+                user.username,
+                format_count(user.followers_count.unwrap_or(0) as u64)
+            ),
+            SearchResult::Playlist(playlist) => println!(
+                "playlist  {:>12}  {} - {} [{} tracks, {}]",
+                playlist.id,
+                playlist.user.username,
+                playlist.title,
+                playlist.track_count,
+                format_duration(playlist.length())
+            ),
+            SearchResult::Other(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+async fn resolve(client: &Client, url: &str) -> Result<()> {
+    println!("{}", client.resolve(url).await?);
+
+    Ok(())
+}
+
+async fn download(client: &Client, url: &str, path: Option<PathBuf>) -> Result<()> {
+    let track = client.resolve_track(url).await?.get().await?;
+
+    // The original keeps its format, the stream is an mp3.
+    let original = if track.downloadable && track.download_url.is_some() {
+        let info = client.download_info(&track).await?;
+        if info.available {
+            Some(info.format().unwrap_or_else(|| "bin".to_owned()))
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+    let path = path.unwrap_or_else(|| {
+        let title = track
+            .title
+            .replace(&['/', '\\', ':', '*', '?', '"', '<', '>', '|'][..], "");
+        let extension = original.as_deref().unwrap_or("mp3");
+        PathBuf::from(format!("{}.{}", title, extension))
+    });
+    let mut file = AllowStdIo::new(BufWriter::new(File::create(&path)?));
+
+    let result = match original {
+        Some(_) => client.download(&track, &mut file).await,
+        None => client.stream(&track, &mut file).await,
+    };
+    let result = match result {
+        Ok(num_bytes) => file.close().await.map(|_| num_bytes).map_err(Into::into),
+        Err(error) => Err(error),
+    };
+    let num_bytes = match result {
+        Ok(num_bytes) => num_bytes,
+        Err(error) => {
+            drop(file);
+            let _ = fs::remove_file(&path);
+            return Err(error.into());
+        }
+    };
+    println!("{} ({} bytes)", path.display(), num_bytes);
+
+    Ok(())
+}
+
+async fn export(client: &Client, user: &str, dir: &str) -> Result<()> {
+    let user_id = match user.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            let url = client.resolve(user).await?;
+            url.path_segments()
+                .and_then(|mut segments| segments.next_back())
+                .and_then(|id| id.parse().ok())
+                .ok_or_else(|| format!("{} is not a user", url))?
+        }
+    };
+    let manifest = client
+        .archive_user(user_id, dir, Default::default())
+        .await?;
+    println!("archived {} tracks into {}", manifest.tracks.len(), dir);
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+
+/// Fails requests fast after repeated failures, shared between all clones of a client.
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            failure_threshold,
+            cooldown,
+            state: Default::default(),
+        }
+    }
+
+    /// Returns an error if the circuit is open.
+    pub(crate) fn check(&self) -> Result<()> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        match state.open_until {
+            Some(open_until) => {
+                let now = Instant::now();
+                if open_until > now {
+                    Err(Error::CircuitOpen(open_until - now))
+                } else {
+                    Ok(())
+                }
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn record_success(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.consecutive_failures = 0;
+        state.open_until = None;
+    }
+
+    /// Records a failed request and opens the circuit once the threshold is reached.
+    ///
+    /// After the cooldown a single failure opens the circuit again, until a request succeeds.
+    pub(crate) fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+
+        if state.consecutive_failures >= self.failure_threshold {
+            log::warn!(
+                "opening circuit after {} consecutive failures",
+                state.consecutive_failures
+            );
+            state.open_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+}
@@ -1,5 +1,11 @@
 use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
+use bytes::{Bytes, BytesMut};
 use futures::future::BoxFuture;
 use futures::io::AsyncWrite;
 use futures::prelude::*;
@@ -8,19 +14,72 @@ use serde::de::DeserializeOwned;
 use url::Url;
 
 use crate::apis::{
-    PlaylistRequestBuilder, SinglePlaylistRequestBuilder, SingleTrackRequestBuilder,
-    SingleUserRequestBuilder, TrackRequestBuilder, UserRequestBuilder,
+    ChartKind, Charts, MyFollowers, MyFollowings, OEmbedOptions, PlaylistRequestBuilder, Playlists,
+    ProfileUpdate, Search, SinglePlaylistRequestBuilder, SingleTrackRequestBuilder,
+    SingleUserRequestBuilder, Station, TrackRequestBuilder, Tracks, UserRequestBuilder,
+    WebProfileUpdate, WebProfiles,
 };
+use crate::archive::{self, ArchiveOptions, Manifest};
+use crate::batch::BatchFetcher;
+use crate::circuit_breaker::CircuitBreaker;
+use crate::client_builder::ClientBuilder;
+use crate::client_id::ClientIdRotation;
+use crate::download_options::{to_hex, DownloadOptions, Hasher};
+use crate::endpoint::Endpoint;
 use crate::error::{Error, Result};
-use crate::models::{Playlist, Track};
-use crate::page::Page;
+use crate::media_urls::MediaUrlCache;
+use crate::models::{
+    App, DownloadInfo, Genre, MyQuota, MySubscription, OEmbed, Playback, Playlist, PlaylistDiff,
+    Policy, Quota, Selection, StreamLocation, Subscription, SystemPlaylist, Track, Transcoding,
+    User, UserSnapshot, Waveform, WebProfile,
+};
+use crate::pacer::Pacer;
+use crate::page::{Page, PageOptions};
+use crate::request_options::RequestOptions;
+use crate::resource::Resource;
+use crate::response_meta::ResponseMeta;
+use crate::streaming_api::{StreamingApi, StreamingApiExt};
+use crate::telemetry;
+use crate::transform::MediaTransform;
+use crate::transport::HttpTransport;
+use crate::upload::{self, TrackUpload, UploadEvent};
+#[cfg(feature = "waveform-png")]
+use crate::waveform_png;
+
+/// Number of concurrent requests performed by [`Client::fetch_many`].
+const FETCH_MANY_CONCURRENCY: usize = 8;
+
+/// Number of times a media transfer is resumed after the connection failed.
+const MEDIA_RESUME_ATTEMPTS: usize = 3;
+
+/// Number of user ids looked up per request by [`Client::users_by_ids`].
+const USERS_BY_IDS_CHUNK_SIZE: usize = 50;
+
+/// Number of tracks and playlists included in a [`UserSnapshot`].
+const USER_SNAPSHOT_LIMIT: u32 = 20;
 
 #[derive(Clone, Debug)]
 pub struct Client {
-    host: String,
-    client_id: String,
-    auth_token: Option<String>,
-    http_client: reqwest::Client,
+    pub(crate) host: String,
+    pub(crate) client_id: ClientIdRotation,
+    pub(crate) auth_token: Option<String>,
+    /// Additional query parameters sent with every request.
+    pub(crate) params: Vec<(String, String)>,
+    /// Additional headers sent with every API request.
+    pub(crate) headers: reqwest::header::HeaderMap,
+    pub(crate) request_options: RequestOptions,
+    pub(crate) circuit_breaker: Option<Arc<CircuitBreaker>>,
+    /// Maximum size of API response bodies in bytes.
+    pub(crate) max_response_size: Option<u64>,
+    /// Spaces out posted comments.
+    pub(crate) comment_pacer: Arc<Pacer>,
+    /// HTTP client used to build requests.
+    pub(crate) http_client: reqwest::Client,
+    pub(crate) transport: Arc<dyn HttpTransport>,
+    /// Transport for media, which follows redirects to the CDN.
+    pub(crate) media_transport: Arc<dyn HttpTransport>,
+    /// Signed CDN urls of recently transferred media.
+    pub(crate) media_urls: Arc<MediaUrlCache>,
 }
 
 impl Client {
@@ -33,29 +92,173 @@ impl Client {
     ///
     /// let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the HTTP client can't be initialized, use [`Client::try_new`] to handle this
+    /// case. Any `client_id` is accepted.
     pub fn new(client_id: &str) -> Client {
-        let client = reqwest::ClientBuilder::new()
-            .redirect(reqwest::redirect::Policy::none())
+        ClientBuilder::new(client_id)
             .build()
-            .unwrap();
+            .expect("failed to construct soundcloud client")
+    }
 
-        Client {
-            host: super::API_HOST.to_owned(),
-            client_id: client_id.to_owned(),
-            http_client: client,
-            auth_token: None,
+    /// Constructs a new `Client` with the provided `client_id`.
+    ///
+    /// Returns an error if the `client_id` is empty or malformed, i.e. not made up of ASCII
+    /// letters and digits, or the HTTP client can't be initialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use soundcloud::{Client, Error};
+    ///
+    /// let client = Client::try_new(env!("SOUNDCLOUD_CLIENT_ID"));
+    /// assert!(client.is_ok());
+    ///
+    /// let client = Client::try_new("");
+    /// assert!(matches!(client, Err(Error::InvalidClientId(_))));
+    /// ```
+    pub fn try_new(client_id: &str) -> Result<Client> {
+        if client_id.is_empty() || !client_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(Error::InvalidClientId(client_id.to_owned()));
         }
+        ClientBuilder::new(client_id).build()
+    }
+
+    /// Returns a builder for a `Client` with the provided `client_id`.
+    pub fn builder(client_id: &str) -> ClientBuilder {
+        ClientBuilder::new(client_id)
     }
 
-    /// Returns the client id.
+    /// Returns the client id the client was constructed with.
     pub fn client_id(&self) -> &str {
-        &self.client_id
+        self.client_id.initial()
+    }
+
+    /// Returns the client id currently sent with requests.
+    ///
+    /// The client id may change over the lifetime of the client if a
+    /// [`ClientIdProvider`](crate::ClientIdProvider) is configured.
+    pub fn current_client_id(&self) -> String {
+        self.client_id.current()
     }
 
     pub fn authenticate_with_token(&mut self, token: String) {
         self.auth_token = Some(token);
     }
 
+    /// Revokes the auth token and removes it from the client, so it can be authenticated with
+    /// the token of another account.
+    ///
+    /// The token is removed even if revoking it fails, in which case the error is returned.
+    /// Copies of the client made before keep the token.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use soundcloud::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let mut client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   client.authenticate_with_token(env!("SOUNDCLOUD_AUTH_TOKEN").to_owned());
+    ///   client.sign_out().await.unwrap();
+    ///
+    ///   assert!(!client.is_authenticated());
+    /// }
+    /// ```
+    pub async fn sign_out(&mut self) -> Result<()> {
+        let token = match self.auth_token.take() {
+            Some(token) => token,
+            None => return Ok(()),
+        };
+
+        let request = self
+            .http_client
+            .post(crate::SIGN_OUT_URL)
+            .json(&serde_json::json!({ "access_token": token }))
+            .build()?;
+        let response = self.execute_once(&*self.transport, "api", request).await?;
+        response.error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Returns whether an auth token is set, which endpoints of the authenticated user like
+    /// `/me` and all writes require.
+    ///
+    /// Without one, these fail with [`Error::AuthenticationRequired`].
+    pub fn is_authenticated(&self) -> bool {
+        self.auth_token.is_some()
+    }
+
+    /// Returns a copy of this client which applies the `options` to all of its requests,
+    /// including those of the request builders and streaming APIs created from it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use soundcloud::{Client, RequestOptions};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let options = RequestOptions::builder().timeout(Duration::from_secs(5)).build();
+    ///   let track = client.with_options(options).track(262681089).get().await;
+    ///
+    ///   assert_eq!(track.unwrap().id, 262681089);
+    /// }
+    /// ```
+    pub fn with_options(&self, options: RequestOptions) -> Client {
+        Client {
+            request_options: options,
+            ..self.clone()
+        }
+    }
+
+    /// Performs a cheap request to verify that the client id, and the auth token if one is set,
+    /// are accepted by the API.
+    ///
+    /// Useful to fail early before starting a long running job.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use soundcloud::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///
+    ///   assert!(client.verify().await.is_ok());
+    /// }
+    /// ```
+    pub async fn verify(&self) -> Result<()> {
+        let result = if self.auth_token.is_some() {
+            let no_params: Option<&[(&str, &str)]> = None;
+            self.get("/me", no_params).await
+        } else {
+            self.get(
+                "/resolve",
+                Some(&[("url", "https://soundcloud.com/soundcloud")]),
+            )
+            .await
+        };
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(Error::HttpError(ref error))
+                if error.status() == Some(reqwest::StatusCode::UNAUTHORIZED)
+                    && self.auth_token.is_none() =>
+            {
+                Err(Error::InvalidClientId(self.current_client_id()))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
     /// Creates and sends a HTTP GET request to the API endpoint.
     ///
     /// A `client_id` parameter will automatically be added to the request.
@@ -86,84 +289,447 @@ impl Client {
         K: AsRef<str>,
         V: AsRef<str>,
     {
-        let mut url = Url::parse(&format!("{}", self.host.clone() + path))?;
-
-        {
-            let mut query_pairs = url.query_pairs_mut();
-            query_pairs.append_pair("client_id", &self.client_id);
+        let mut url = self.api_url(path)?;
 
-            if let Some(params) = params {
-                query_pairs.extend_pairs(params);
-            }
+        if let Some(params) = params {
+            url.query_pairs_mut().extend_pairs(params);
         }
 
-        let mut headers = reqwest::header::HeaderMap::new();
+        self.send(self.request(reqwest::Method::GET, url)?).await
+    }
+
+    /// Like [`Client::get`], but deserializes the response and returns it along with the
+    /// metadata of the response.
+    pub async fn get_with_meta<T, I, K, V>(
+        &self,
+        path: &str,
+        params: Option<I>,
+    ) -> Result<(T, ResponseMeta)>
+    where
+        T: DeserializeOwned,
+        I: IntoIterator,
+        I::Item: Borrow<(K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let start = Instant::now();
+        let response = self.get(path, params).await?;
+        let meta = ResponseMeta::new(&response, start.elapsed());
+        let body: T = self.read_json(response).await?;
+
+        Ok((body, meta))
+    }
+
+    /// Creates and sends a HTTP PUT request to the API endpoint.
+    ///
+    /// A `client_id` parameter will automatically be added to the request.
+    ///
+    /// Returns the HTTP response on success, an error otherwise.
+    pub async fn put(&self, path: &str) -> Result<reqwest::Response> {
+        let url = self.api_url(path)?;
+        self.send(self.request(reqwest::Method::PUT, url)?).await
+    }
+
+    /// Creates and sends a HTTP DELETE request to the API endpoint.
+    ///
+    /// A `client_id` parameter will automatically be added to the request.
+    ///
+    /// Returns the HTTP response on success, an error otherwise.
+    pub async fn delete(&self, path: &str) -> Result<reqwest::Response> {
+        let url = self.api_url(path)?;
+        self.send(self.request(reqwest::Method::DELETE, url)?).await
+    }
+
+    /// Returns the url of the API endpoint with the client_id query parameter set.
+    pub(crate) fn api_url(&self, path: &str) -> Result<Url> {
+        self.parse_url(self.host.clone() + path)
+    }
+
+    /// Creates a request with the authorization header set if the client is authenticated.
+    pub(crate) fn request(
+        &self,
+        method: reqwest::Method,
+        url: Url,
+    ) -> Result<reqwest::RequestBuilder> {
+        let mut headers = self.headers.clone();
 
-        if self.auth_token.is_some() {
-            let token = self.auth_token.clone().unwrap();
+        if let Some(ref token) = self.auth_token {
             headers.insert(
                 reqwest::header::AUTHORIZATION,
                 format!("OAuth {}", token).parse()?,
             );
         }
 
-        let response = self.http_client.get(url).headers(headers).send().await?;
-        response.error_for_status().map_err(Error::from)
+        let mut request = self.http_client.request(method, url).headers(headers);
+
+        if let Some(timeout) = self.request_options.timeout {
+            request = request.timeout(timeout);
+        }
+
+        Ok(request)
+    }
+
+    /// Sends the request, returning an error if the response has a non-success status.
+    ///
+    /// If the client id is rejected and a [`ClientIdProvider`](crate::ClientIdProvider) is
+    /// configured, the client id is rotated and the request is retried once.
+    pub(crate) async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        self.send_request(request.build()?).await
+    }
+
+    /// Like [`Client::send`], for a request which has already been built.
+    pub(crate) async fn send_request(
+        &self,
+        request: reqwest::Request,
+    ) -> Result<reqwest::Response> {
+        Ok(self.send_unchecked(request).await?.error_for_status()?)
+    }
+
+    /// Like [`Client::send_request`], but returns responses with an error status which isn't
+    /// handled by the client itself, e.g. to look at the error in their body.
+    pub(crate) async fn send_unchecked(
+        &self,
+        request: reqwest::Request,
+    ) -> Result<reqwest::Response> {
+        let method = request.method().clone();
+        let path = request.url().path().to_owned();
+
+        match self.circuit_breaker {
+            Some(ref circuit_breaker) => {
+                circuit_breaker.check()?;
+                let result = self.execute(request).await;
+                let failed = match result {
+                    Ok(ref response) => response.status().is_server_error(),
+                    Err(Error::HttpError(ref error)) => error.is_timeout(),
+                    Err(_) => false,
+                };
+
+                if failed {
+                    circuit_breaker.record_failure();
+                } else {
+                    circuit_breaker.record_success();
+                }
+
+                let response = self.check_authentication(&method, &path, result?)?;
+                self.check_scope(response).await
+            }
+            None => {
+                let response = self.execute(request).await?;
+                let response = self.check_authentication(&method, &path, response)?;
+                self.check_scope(response).await
+            }
+        }
+    }
+
+    /// Returns [`Error::AuthenticationRequired`] if the `response` rejects an unauthenticated
+    /// request to an endpoint which requires an auth token.
+    ///
+    /// Other rejected requests without a token, e.g. because of an invalid client id, are left to
+    /// fail with their status.
+    fn check_authentication(
+        &self,
+        method: &reqwest::Method,
+        path: &str,
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response> {
+        let requires_token =
+            path == "/me" || path.starts_with("/me/") || *method != reqwest::Method::GET;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED
+            && !self.is_authenticated()
+            && requires_token
+        {
+            return Err(Error::AuthenticationRequired(path.to_owned()));
+        }
+
+        Ok(response)
+    }
+
+    /// Returns [`Error::InsufficientScope`] if the `response` rejects the auth token because it
+    /// hasn't been granted the scopes the endpoint requires.
+    ///
+    /// The required scopes are taken from the `WWW-Authenticate` header or the JSON body of the
+    /// `403 Forbidden` response.
+    async fn check_scope(&self, response: reqwest::Response) -> Result<reqwest::Response> {
+        if response.status() != reqwest::StatusCode::FORBIDDEN || !self.is_authenticated() {
+            return Ok(response);
+        }
+        let error = match response.error_for_status_ref() {
+            Err(error) => error,
+            Ok(_) => return Ok(response),
+        };
+
+        let challenge = response
+            .headers()
+            .get(reqwest::header::WWW_AUTHENTICATE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(insufficient_scope_challenge);
+        let required = match challenge {
+            Some(required) => Some(required),
+            None => {
+                let body = self.read_body(response).await.unwrap_or_default();
+                insufficient_scope_body(&body)
+            }
+        };
+
+        match required {
+            Some(required) => Err(Error::InsufficientScope { required }),
+            None => Err(Error::HttpError(error)),
+        }
+    }
+
+    /// Reads the body of the API `response` into memory, failing as soon as it exceeds the
+    /// [maximum response size](crate::ClientBuilder::max_response_size).
+    pub(crate) async fn read_body(&self, mut response: reqwest::Response) -> Result<Bytes> {
+        if self.max_response_size.is_none() {
+            return Ok(response.bytes().await?);
+        }
+        self.check_body_size(response.content_length().unwrap_or(0))?;
+
+        let mut body = BytesMut::new();
+        while let Some(chunk) = response.chunk().await? {
+            self.check_body_size((body.len() + chunk.len()) as u64)?;
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(body.freeze())
+    }
+
+    /// Returns [`Error::ResponseTooLarge`] if an API response body of `size` bytes exceeds the
+    /// [maximum response size](crate::ClientBuilder::max_response_size).
+    pub(crate) fn check_body_size(&self, size: u64) -> Result<()> {
+        match self.max_response_size {
+            Some(limit) if size > limit => Err(Error::ResponseTooLarge(limit)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reads the body of the API `response` like [`Client::read_body`] and deserializes it.
+    pub(crate) async fn read_json<T: DeserializeOwned>(
+        &self,
+        response: reqwest::Response,
+    ) -> Result<T> {
+        let body = self.read_body(response).await?;
+
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Executes the request, rotating the client id if it has been rejected.
+    ///
+    /// A `401 Unauthorized` response to a request with an auth token only rotates the client id
+    /// if its body says that the client id was rejected, as the token itself may be invalid.
+    async fn execute(&self, request: reqwest::Request) -> Result<reqwest::Response> {
+        let retry = if self.client_id.can_rotate() {
+            request.try_clone()
+        } else {
+            None
+        };
+        let mut response = self.execute_once(&*self.transport, "api", request).await?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            if let Some(mut retry) = retry {
+                if retry.headers().contains_key(reqwest::header::AUTHORIZATION) {
+                    let error = match response.error_for_status_ref() {
+                        Err(error) => error,
+                        Ok(_) => return Ok(response),
+                    };
+                    let body = self.read_body(response).await.unwrap_or_default();
+                    if !rejects_client_id(&body) {
+                        return Err(Error::HttpError(error));
+                    }
+                }
+                let rejected = query_param(retry.url(), "client_id").unwrap_or_default();
+                let client_id = self.client_id.rotate(&rejected).await?;
+                set_query_param(retry.url_mut(), "client_id", &client_id);
+                response = self.execute_once(&*self.transport, "api", retry).await?;
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Executes the request with the `transport`, recording metrics of the given `kind`.
+    pub(crate) async fn execute_once(
+        &self,
+        transport: &dyn HttpTransport,
+        kind: &'static str,
+        request: reqwest::Request,
+    ) -> Result<reqwest::Response> {
+        let start = Instant::now();
+        let result = transport.execute(request).await;
+        telemetry::record_request(kind, &result, start.elapsed());
+
+        result
     }
 
-    pub fn get_stream<T>(&self, path: &str, num_pages: Option<u64>) -> BoxStream<Result<T>>
+    /// Streams the items of the collection at `url_or_path`, which is either a path relative to
+    /// the API host or an absolute URL like the `next_href` of a [`Page`].
+    pub fn get_stream<T>(
+        &self,
+        url_or_path: &str,
+        num_pages: Option<u64>,
+    ) -> BoxStream<'_, Result<T>>
     where
         T: DeserializeOwned + 'static + Send,
     {
         unfold(
             self.clone(),
-            self.get_pages(&path),
+            self.get_first_page(url_or_path),
             num_pages.unwrap_or(u64::MAX),
         )
     }
 
-    fn get_pages<T>(&self, path: &str) -> BoxFuture<Result<Page<T>>>
+    /// Streams the items of any paginated collection of the API, including endpoints which
+    /// aren't modeled by this crate yet.
+    ///
+    /// `url_or_path` is either a path relative to the API host, like `/users/1/followers`, or an
+    /// absolute URL, like the `next_href` of a [`Page`]. Each item is deserialized into `T`,
+    /// which can be [`serde_json::Value`] for unknown item types.
+    ///
+    /// A stream which failed to load a page can be resumed by passing the
+    /// [`resume_url`](Error::resume_url) of the error as `url_or_path`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::TryStreamExt;
+    /// use soundcloud::{Client, User};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let followers: Vec<User> = client
+    ///     .stream_collection("/users/31506117/followers", Default::default())
+    ///     .try_collect()
+    ///     .await
+    ///     .unwrap();
+    ///
+    ///   assert!(!followers.is_empty());
+    /// }
+    /// ```
+    pub fn stream_collection<T>(
+        &self,
+        url_or_path: &str,
+        options: PageOptions,
+    ) -> BoxStream<'_, Result<T>>
     where
         T: DeserializeOwned + 'static + Send,
     {
-        self.get_page(&(self.host.clone() + path))
+        let url = options.apply(url_or_path);
+
+        unfold(self.clone(), self.get_first_page(&url), u64::MAX)
     }
 
-    fn get_pages_url<T>(&self, url: &str) -> BoxFuture<Result<Page<T>>>
+    /// Like [`Client::get_stream`], but items which fail to deserialize are yielded as
+    /// [`Error::ItemDecode`] instead of ending the stream.
+    pub fn get_stream_lenient<T>(
+        &self,
+        path: &str,
+        num_pages: Option<u64>,
+    ) -> BoxStream<'_, Result<T>>
     where
         T: DeserializeOwned + 'static + Send,
     {
-        self.get_page(url)
+        Box::pin(
+            self.get_stream::<serde_json::Value>(path, num_pages)
+                .map(|item| {
+                    let json = item?;
+                    serde_json::from_value(json.clone())
+                        .map_err(|source| Error::ItemDecode { json, source })
+                }),
+        )
     }
 
-    fn get_page<T>(&self, path: &str) -> BoxFuture<Result<Page<T>>>
+    pub(crate) fn get_pages<T>(&self, path: &str) -> BoxFuture<'_, Result<Page<T>>>
     where
         T: DeserializeOwned + 'static + Send,
     {
-        let mut url = Url::parse(path).unwrap();
+        self.get_page(&(self.host.clone() + path))
+    }
 
-        if !url.query_pairs().any(|(q, _)| q == "client_id") {
-            url.query_pairs_mut()
-                .append_pair("client_id", &self.client_id);
+    /// Fetches the page at `url_or_path`, which is either a path or an absolute URL.
+    pub(crate) fn get_first_page<T>(&self, url_or_path: &str) -> BoxFuture<'_, Result<Page<T>>>
+    where
+        T: DeserializeOwned + 'static + Send,
+    {
+        if url_or_path.starts_with("http://") || url_or_path.starts_with("https://") {
+            self.get_pages_url(url_or_path)
+        } else {
+            self.get_pages(url_or_path)
         }
+    }
+
+    pub(crate) fn get_pages_url<T>(&self, url: &str) -> BoxFuture<'_, Result<Page<T>>>
+    where
+        T: DeserializeOwned + 'static + Send,
+    {
+        self.get_page(url)
+    }
 
-        let mut headers = reqwest::header::HeaderMap::new();
+    /// Fetches the undecoded body of the page at `url_or_path`, which is either a path or an
+    /// absolute URL.
+    ///
+    /// Errors are wrapped in [`Error::Pagination`] like those of [`Client::get_stream`].
+    pub(crate) fn get_raw_page(&self, url_or_path: &str) -> BoxFuture<'_, Result<Bytes>> {
+        let page_url = self.page_url(url_or_path);
+        let response = self.get_page_response(url_or_path);
 
-        if let Some(ref token) = self.auth_token {
-            headers.insert(
-                reqwest::header::AUTHORIZATION,
-                format!("OAuth {}", token).parse().unwrap(),
-            );
+        Box::pin(async move {
+            let body = async { self.read_body(response.await?).await };
+
+            body.await.map_err(|source| Error::Pagination {
+                page_url,
+                source: Box::new(source),
+            })
+        })
+    }
+
+    /// Sends the request for the page at `url_or_path`, without reading the body.
+    pub(crate) fn get_page_response(
+        &self,
+        url_or_path: &str,
+    ) -> BoxFuture<'_, Result<reqwest::Response>> {
+        let request = self
+            .parse_url(self.page_url(url_or_path))
+            .and_then(|url| self.request(reqwest::Method::GET, url));
+
+        Box::pin(async move { self.send(request?).await })
+    }
+
+    /// Returns the absolute url of the page at `url_or_path`.
+    pub(crate) fn page_url(&self, url_or_path: &str) -> String {
+        if url_or_path.starts_with("http://") || url_or_path.starts_with("https://") {
+            url_or_path.to_owned()
+        } else {
+            self.host.clone() + url_or_path
         }
+    }
 
-        let response = self
-            .http_client
-            .get(url)
-            .headers(headers)
-            .send()
-            .map_err(Error::from);
+    /// Fetches the page at the absolute `url`.
+    ///
+    /// Errors are wrapped in [`Error::Pagination`], to tell them apart from errors of the items.
+    fn get_page<T>(&self, url: &str) -> BoxFuture<'_, Result<Page<T>>>
+    where
+        T: DeserializeOwned + 'static + Send,
+    {
+        let page_url = url.to_owned();
+        let request = self
+            .parse_url(url)
+            .and_then(|url| self.request(reqwest::Method::GET, url));
+
+        Box::pin(async move {
+            let page = async {
+                let response = self.send(request?).await?;
+                let page: Page<T> = self.read_json(response).await?;
 
-        Box::pin(response.and_then(move |response| response.json().map_err(Error::from)))
+                Ok(page)
+            };
+
+            page.await.map_err(|source| Error::Pagination {
+                page_url,
+                source: Box::new(source),
+            })
+        })
     }
 
     /// Starts streaming the track provided in the track's `stream_url` to the `writer` if the track
@@ -190,77 +756,606 @@ impl Client {
     ///   assert!(num_bytes > 0);
     /// }
     /// ```
-    pub async fn stream<W: AsyncWrite + Unpin>(&self, track: &Track, mut writer: W) -> Result<u64> {
-        if !track.streamable {
-            return Err(Error::TrackNotStreamable);
-        }
-        self.read_url(&track.stream_url.as_ref().unwrap(), &mut writer)
-            .await
+    pub async fn stream<W: AsyncWrite + Unpin>(&self, track: &Track, writer: W) -> Result<u64> {
+        self.read_url(stream_url(track)?, writer, None, None).await
     }
 
-    /// Starts downloading the track provided in the tracks `download_url` to the `writer` if the track
-    /// is downloadable via the API.
+    /// Like [`Client::stream`], but also returns whether only a preview of the track has been
+    /// streamed, so players can show a seek bar of the [effective
+    /// duration](Track::effective_stream_duration).
     ///
     /// Returns:
-    ///     Number of bytes written if the track was downloaded successfully, an error otherwise.
+    ///     Number of bytes written and the playback if the track was streamed successfully, an
+    ///     error otherwise.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use std::path::Path;
-    /// use soundcloud::Client;
-    /// use tokio::fs::File;
-    /// use tokio_util::compat::TokioAsyncWriteCompatExt;
+    /// use soundcloud::{Client, Playback};
     ///
     /// #[tokio::main]
     /// async fn main() {
     ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
-    ///   let path = Path::new("hi.mp3");
     ///   let track = client.tracks().id(263801976).get().await.unwrap();
-    ///   let mut outfile = File::create(path).await.unwrap().compat_write();
-    ///   let num_bytes = client.download(&track, &mut outfile).await.unwrap();
-    ///   assert!(num_bytes > 0);
+    ///   let mut data = vec![];
+    ///   let (_, playback) = client.stream_with_playback(&track, &mut data).await.unwrap();
+    ///
+    ///   if playback == Playback::PreviewOnly {
+    ///     println!("preview of {:?}", track.effective_stream_duration());
+    ///   }
     /// }
     /// ```
-    pub async fn download<W: AsyncWrite + Unpin>(
+    pub async fn stream_with_playback<W: AsyncWrite + Unpin>(
         &self,
         track: &Track,
-        mut writer: W,
+        writer: W,
+    ) -> Result<(u64, Playback)> {
+        let num_bytes = self.stream(track, writer).await?;
+        Ok((num_bytes, track.playback()))
+    }
+
+    /// Streams the encoding `transcoding` of a track into the `writer`, resolving its API url
+    /// to the url of the stream first.
+    pub(crate) async fn stream_transcoding<W: AsyncWrite + Unpin>(
+        &self,
+        transcoding: &Transcoding,
+        writer: W,
     ) -> Result<u64> {
-        if !track.downloadable {
-            return Err(Error::TrackNotDownloadable);
-        }
-        self.read_url(&track.download_url.as_ref().unwrap(), &mut writer)
-            .await
+        let request = self.request(reqwest::Method::GET, self.parse_url(&transcoding.url)?)?;
+        let response = self.send(request).await?;
+        let location: StreamLocation = self.read_json(response).await?;
+
+        self.read_url(&location.url, writer, None, None).await
     }
 
-    /// Copies the data provided from reading in the `url` to the `writer`
-    /// if the track is streamable via the API.
+    /// Like [`Client::stream`], but passes the media data through the `transform` before it's
+    /// written to the `writer`.
     ///
     /// Returns:
-    ///     number of bytes written if the resource's data was copied successfully,
-    ///     an error otherwise.
-    ///
-    /// ```
-    async fn read_url<W: AsyncWrite + Unpin>(&self, url: &str, mut writer: W) -> Result<u64> {
-        let url = self.parse_url(url)?;
-        let mut response = self.http_client.get(url).send().await?;
-        // Follow the redirect just this once.
-        if let Some(header) = response.headers().get(reqwest::header::LOCATION).cloned() {
-            let url = Url::parse(header.to_str()?).unwrap();
-            response = self.http_client.get(url).send().await?;
-        }
-        let stream = response.bytes_stream();
-        // convert the reqwest::Error into a futures::io::Error
-        let stream = stream
-            .map_err(|e| futures::io::Error::new(futures::io::ErrorKind::Other, e))
-            .into_async_read();
-
-        let num_bytes = futures::io::copy(stream, &mut writer).await?;
+    ///     Number of bytes written if the track was streamed successfully, an error otherwise.
+    pub async fn stream_with<W: AsyncWrite + Unpin>(
+        &self,
+        track: &Track,
+        writer: W,
+        transform: &dyn MediaTransform,
+    ) -> Result<u64> {
+        self.read_url(stream_url(track)?, writer, Some(transform), None)
+            .await
+    }
+
+    /// Starts downloading the track provided in the tracks `download_url` to the `writer` if the track
+    /// is downloadable via the API.
+    ///
+    /// Returns:
+    ///     Number of bytes written if the track was downloaded successfully, an error otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use soundcloud::Client;
+    /// use tokio::fs::File;
+    /// use tokio_util::compat::TokioAsyncWriteCompatExt;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let path = Path::new("hi.mp3");
+    ///   let track = client.tracks().id(263801976).get().await.unwrap();
+    ///   let mut outfile = File::create(path).await.unwrap().compat_write();
+    ///   let num_bytes = client.download(&track, &mut outfile).await.unwrap();
+    ///   assert!(num_bytes > 0);
+    /// }
+    /// ```
+    pub async fn download<W: AsyncWrite + Unpin>(&self, track: &Track, writer: W) -> Result<u64> {
+        self.read_url(download_url(track)?, writer, None, None)
+            .await
+    }
+
+    /// Like [`Client::download`], but passes the media data through the `transform` before it's
+    /// written to the `writer`.
+    ///
+    /// Returns:
+    ///     Number of bytes written if the track was downloaded successfully, an error otherwise.
+    pub async fn download_with<W: AsyncWrite + Unpin>(
+        &self,
+        track: &Track,
+        writer: W,
+        transform: &dyn MediaTransform,
+    ) -> Result<u64> {
+        self.read_url(download_url(track)?, writer, Some(transform), None)
+            .await
+    }
+
+    /// Like [`Client::download`], but with the given `options`.
+    ///
+    /// If a checksum should be verified but doesn't match the one provided by the CDN,
+    /// [`Error::ChecksumMismatch`] is returned after all data has been written to the `writer`.
+    ///
+    /// Returns:
+    ///     Number of bytes written if the track was downloaded successfully, an error otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use soundcloud::{Checksum, Client, DownloadOptions};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let track = client.tracks().id(263801976).get().await.unwrap();
+    ///   let options = DownloadOptions::builder().verify(Checksum::Md5).build();
+    ///   let mut data = vec![];
+    ///   client.download_with_options(&track, &mut data, &options).await.unwrap();
+    /// }
+    /// ```
+    pub async fn download_with_options<W: AsyncWrite + Unpin>(
+        &self,
+        track: &Track,
+        writer: W,
+        options: &DownloadOptions,
+    ) -> Result<u64> {
+        self.read_url(download_url(track)?, writer, None, Some(options))
+            .await
+    }
+
+    /// Like [`Client::download`], but stops as soon as the `cancellation` future completes, e.g.
+    /// a deadline, a channel or the `cancelled()` future of a cancellation token.
+    ///
+    /// On cancellation, the chunk being written is completed and the `writer` is flushed before
+    /// [`Error::Cancelled`] is returned with the number of bytes written so far, so the caller
+    /// knows what has landed in the writer, e.g. to resume the download later.
+    ///
+    /// Returns:
+    ///     Number of bytes written if the track was downloaded completely, an error otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use soundcloud::{Client, Error};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let track = client.tracks().id(263801976).get().await.unwrap();
+    ///   let deadline = tokio::time::sleep(Duration::from_secs(1));
+    ///
+    ///   let mut data = vec![];
+    ///   match client.download_with_cancellation(&track, &mut data, deadline).await {
+    ///     Ok(num_bytes) => println!("downloaded {} bytes", num_bytes),
+    ///     Err(Error::Cancelled(num_bytes)) => println!("cancelled after {} bytes", num_bytes),
+    ///     Err(error) => panic!("{}", error),
+    ///   }
+    /// }
+    /// ```
+    pub async fn download_with_cancellation<W, C>(
+        &self,
+        track: &Track,
+        mut writer: W,
+        cancellation: C,
+    ) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin,
+        C: Future<Output = ()>,
+    {
+        let download_url = download_url(track)?;
+        let mut cancellation = Box::pin(cancellation);
+        let chunks = {
+            let open = self.media_chunks(download_url, None).boxed();
+            match future::select(&mut cancellation, open).await {
+                future::Either::Left(_) => return Err(Error::Cancelled(0)),
+                future::Either::Right((chunks, _)) => chunks?.0,
+            }
+        };
+        let num_bytes = write_chunks(chunks, &mut writer, Some(cancellation)).await?;
+        telemetry::record_download(num_bytes);
+
+        Ok(num_bytes)
+    }
+
+    /// Like [`Client::download`], but forwards the chunks of the file to the `sink` as they are
+    /// received, without copying them.
+    ///
+    /// The sink is flushed, but not closed, after the last chunk.
+    ///
+    /// Returns:
+    ///     Number of bytes forwarded if the track was downloaded successfully, an error otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use bytes::{Bytes, BytesMut};
+    /// use futures::channel::mpsc;
+    /// use futures::prelude::*;
+    /// use soundcloud::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let track = client.tracks().id(263801976).get().await.unwrap();
+    ///   let (sink, chunks) = mpsc::unbounded::<Bytes>();
+    ///   let sink = sink.sink_map_err(|_| std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+    ///   let (num_bytes, chunks) = future::join(
+    ///     client.download_to_sink(&track, sink),
+    ///     chunks.collect::<Vec<_>>(),
+    ///   )
+    ///   .await;
+    ///   assert_eq!(num_bytes.unwrap(), chunks.iter().map(|c| c.len() as u64).sum::<u64>());
+    /// }
+    /// ```
+    pub async fn download_to_sink<S>(&self, track: &Track, mut sink: S) -> Result<u64>
+    where
+        S: Sink<Bytes> + Unpin,
+        Error: From<S::Error>,
+    {
+        let (mut chunks, _) = self.media_chunks(download_url(track)?, None).await?;
+        let mut num_bytes = 0;
+
+        while let Some(chunk) = chunks.try_next().await? {
+            num_bytes += chunk.len() as u64;
+            sink.feed(chunk).await?;
+        }
+        sink.flush().await?;
+        telemetry::record_download(num_bytes);
+
+        Ok(num_bytes)
+    }
+
+    /// Saves all tracks of the user into the directory `dir`, along with a
+    /// [manifest](crate::archive::MANIFEST_FILE) of the saved tracks.
+    ///
+    /// Originals are downloaded where allowed and available, otherwise the stream is saved. Tracks
+    /// which can neither be downloaded nor streamed are skipped. If the archive is interrupted,
+    /// calling this again with the same `dir` only saves the missing tracks.
+    ///
+    /// Returns:
+    ///     the manifest of the archive.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use soundcloud::Client;
+    /// use soundcloud::archive::ArchiveOptions;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let options = ArchiveOptions::builder().concurrency(8).build();
+    ///   let manifest = client.archive_user(8553751, "archive", options).await.unwrap();
+    ///   println!("archived {} tracks", manifest.tracks.len());
+    /// }
+    /// ```
+    pub async fn archive_user<P: AsRef<Path>>(
+        &self,
+        user_id: usize,
+        dir: P,
+        options: ArchiveOptions,
+    ) -> Result<Manifest> {
+        archive::archive_user(self, user_id, dir.as_ref(), &options).await
+    }
+
+    /// Updates the archive in `dir` created by [`Client::archive_user`], saving only the tracks
+    /// uploaded after the newest one of the last complete run.
+    ///
+    /// Only the pages of tracks up to that one are requested. As older tracks aren't retried, an
+    /// interrupted archive should be completed with [`Client::archive_user`] first. Deleted
+    /// tracks are recorded by [`Client::archive_deletions`].
+    ///
+    /// Returns:
+    ///     the updated manifest of the archive.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use soundcloud::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let manifest = client.archive_update("archive").await.unwrap();
+    ///   println!("{} tracks archived", manifest.tracks.len());
+    /// }
+    /// ```
+    pub async fn archive_update<P: AsRef<Path>>(&self, dir: P) -> Result<Manifest> {
+        archive::archive_update(self, dir.as_ref(), &ArchiveOptions::default()).await
+    }
+
+    /// Records which tracks of the archive in `dir` have been deleted from SoundCloud or made
+    /// private, listing all tracks of the user.
+    ///
+    /// Returns:
+    ///     the updated manifest of the archive.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use soundcloud::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let manifest = client.archive_deletions("archive").await.unwrap();
+    ///   println!("{} tracks were deleted", manifest.deleted.len());
+    /// }
+    /// ```
+    pub async fn archive_deletions<P: AsRef<Path>>(&self, dir: P) -> Result<Manifest> {
+        archive::archive_deletions(self, dir.as_ref()).await
+    }
+
+    /// Uploads a track in parts, retrying each failed part on its own, see [`upload`](crate::upload).
+    ///
+    /// The returned stream reports the progress of the upload and ends with the created track.
+    /// If it fails, the upload can be resumed with the session of the last event.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use futures::TryStreamExt;
+    /// use soundcloud::Client;
+    /// use soundcloud::upload::{TrackUpload, UploadEvent};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let mut client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   client.authenticate_with_token(env!("SOUNDCLOUD_AUTH_TOKEN").to_owned());
+    ///   let file = std::fs::File::open("set.mp3").unwrap();
+    ///   let mut events = client.upload_track(TrackUpload::new("Live set", "set.mp3", file));
+    ///   while let Some(event) = events.try_next().await.unwrap() {
+    ///     match event {
+    ///       UploadEvent::PartUploaded { bytes_sent, total, .. } => {
+    ///         println!("{}/{} bytes", bytes_sent, total)
+    ///       }
+    ///       UploadEvent::Finished(handle) => println!("uploaded {}", handle.track.id),
+    ///       _ => {}
+    ///     }
+    ///   }
+    /// }
+    /// ```
+    pub fn upload_track(&self, upload: TrackUpload) -> BoxStream<'static, Result<UploadEvent>> {
+        upload::upload_track(self.clone(), upload)
+    }
+
+    /// Checks whether the original file of the track can actually be downloaded, without
+    /// transferring it.
+    ///
+    /// Returns [`Error::TrackNotDownloadable`] if the track has no download url.
+    pub async fn download_info(&self, track: &Track) -> Result<DownloadInfo> {
+        let download_url = match track.download_url {
+            Some(ref download_url) if track.downloadable => download_url,
+            _ => return Err(Error::TrackNotDownloadable),
+        };
+        let response = self.open_media(reqwest::Method::HEAD, download_url).await?;
+
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::GONE => {
+                return Ok(DownloadInfo {
+                    available: false,
+                    size: None,
+                    content_type: None,
+                    file_name: None,
+                })
+            }
+            _ => response.error_for_status_ref()?,
+        };
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value: &reqwest::header::HeaderValue| value.to_str().ok())
+        };
+        let file_name = header(reqwest::header::CONTENT_DISPOSITION).and_then(|disposition| {
+            let start = disposition.find("filename=")? + "filename=".len();
+            let name = disposition[start..]
+                .split(';')
+                .next()?
+                .trim()
+                .trim_matches('"');
+            Some(name.to_owned())
+        });
+
+        Ok(DownloadInfo {
+            available: true,
+            size: header(reqwest::header::CONTENT_LENGTH).and_then(|size| size.parse().ok()),
+            content_type: header(reqwest::header::CONTENT_TYPE).map(str::to_owned),
+            file_name,
+        })
+    }
+
+    /// Copies the data provided from reading in the `url` to the `writer`
+    /// if the track is streamable via the API.
+    ///
+    /// Without a `transform`, the chunks of the body are written as they are received, without
+    /// copying them into an intermediate buffer.
+    ///
+    /// Returns:
+    ///     number of bytes written if the resource's data was copied successfully,
+    ///     an error otherwise.
+    ///
+    /// ```
+    async fn read_url<W: AsyncWrite + Unpin>(
+        &self,
+        url: &str,
+        mut writer: W,
+        transform: Option<&dyn MediaTransform>,
+        options: Option<&DownloadOptions>,
+    ) -> Result<u64> {
+        let (chunks, verification) = self.media_chunks(url, options).await?;
+
+        let num_bytes = match transform {
+            Some(transform) => {
+                let reader = transform.transform(Box::new(chunks.into_async_read()));
+                futures::io::copy(reader, &mut writer).await?
+            }
+            None => write_chunks(chunks, &mut writer, None::<future::Pending<()>>).await?,
+        };
+        telemetry::record_download(num_bytes);
+        verify_checksum(verification)?;
 
         Ok(num_bytes)
     }
 
+    /// Opens the media at `url`, returning the chunks of its body and, if the checksum should
+    /// be verified and the CDN provides one, the state to verify it after the last chunk.
+    async fn media_chunks(
+        &self,
+        url: &str,
+        options: Option<&DownloadOptions>,
+    ) -> Result<(BoxStream<'static, io::Result<Bytes>>, Option<Verification>)> {
+        let response = self.open_media_at(url, 0).await?;
+        let response = match response.status() {
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::GONE => {
+                return Err(Error::TrackRemoved)
+            }
+            _ => response.error_for_status()?,
+        };
+        let verification = options.and_then(|options| {
+            let checksum = options.verify?;
+            let expected = checksum.expected(response.headers(), options.trust_etag)?;
+            Some((Arc::new(Mutex::new(checksum.hasher())), expected))
+        });
+        let chunk_hasher = verification.as_ref().map(|(hasher, _)| hasher.clone());
+        let chunks = self.media_body(url, response).inspect_ok(move |chunk| {
+            if let Some(ref hasher) = chunk_hasher {
+                if let Ok(mut hasher) = hasher.lock() {
+                    hasher.update(chunk);
+                }
+            }
+        });
+
+        Ok((Box::pin(chunks), verification))
+    }
+
+    /// Sends a request for the media at `url`, following the redirects to the CDN.
+    async fn open_media(&self, method: reqwest::Method, url: &str) -> Result<reqwest::Response> {
+        let url = self.parse_url(url)?;
+        self.send_media(method, url, 0).await
+    }
+
+    /// Sends a GET request for the media at `url` from the byte `offset` on.
+    ///
+    /// The signed CDN url of a previous request for the same media with the same auth token is
+    /// reused while it's valid, otherwise the redirects are followed again.
+    async fn open_media_at(&self, url: &str, offset: u64) -> Result<reqwest::Response> {
+        let token = self.auth_token.as_deref();
+        if let Some(resolved) = self.media_urls.get(token, url) {
+            let response = self
+                .send_media(reqwest::Method::GET, resolved, offset)
+                .await?;
+            if response.status() != reqwest::StatusCode::FORBIDDEN {
+                return Ok(response);
+            }
+            // The signed url expired early, so resolve a fresh one.
+            self.media_urls.remove(token, url);
+        }
+
+        let response = self
+            .send_media(reqwest::Method::GET, self.parse_url(url)?, offset)
+            .await?;
+        if response.status().is_success() {
+            self.media_urls.insert(token, url, response.url());
+        }
+
+        Ok(response)
+    }
+
+    async fn send_media(
+        &self,
+        method: reqwest::Method,
+        url: Url,
+        offset: u64,
+    ) -> Result<reqwest::Response> {
+        // Media is already compressed, so don't let the server compress it.
+        let mut request = self
+            .http_client
+            .request(method, url)
+            .header(reqwest::header::ACCEPT_ENCODING, "identity");
+
+        if offset > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+        }
+
+        self.execute_once(&*self.media_transport, "media", request.build()?)
+            .await
+    }
+
+    /// Returns the body of the media `response` for `url`.
+    ///
+    /// If the connection fails during the transfer, the transfer is resumed with a range request,
+    /// which also replaces an expired signed url.
+    fn media_body(
+        &self,
+        url: &str,
+        response: reqwest::Response,
+    ) -> BoxStream<'static, io::Result<Bytes>> {
+        let state = (
+            self.clone(),
+            url.to_owned(),
+            response.bytes_stream().boxed(),
+            0,
+            0,
+        );
+
+        Box::pin(stream::try_unfold(
+            state,
+            |(client, url, mut body, mut offset, mut attempts)| async move {
+                loop {
+                    match body.next().await {
+                        Some(Ok(chunk)) => {
+                            offset += chunk.len() as u64;
+                            return Ok(Some((chunk, (client, url, body, offset, attempts))));
+                        }
+                        Some(Err(error)) if attempts < MEDIA_RESUME_ATTEMPTS => {
+                            attempts += 1;
+                            log::warn!("resuming media transfer at byte {}: {}", offset, error);
+                            let response = client
+                                .open_media_at(&url, offset)
+                                .await
+                                .map_err(io::Error::other)?;
+                            if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+                                return Err(io::Error::other(error));
+                            }
+                            body = response.bytes_stream().boxed();
+                        }
+                        Some(Err(error)) => return Err(io::Error::other(error)),
+                        None => return Ok(None),
+                    }
+                }
+            },
+        ))
+    }
+
+    /// Returns the waveform of the `track`.
+    ///
+    /// The JSON waveform is used where available. Tracks which only have a waveform PNG fail with
+    /// its HTTP error, unless the `waveform-png` feature is enabled to decode the image instead.
+    pub async fn waveform(&self, track: &Track) -> Result<Waveform> {
+        let json_url = match track.waveform_url.strip_suffix(".png") {
+            Some(base) => format!("{}.json", base),
+            None => track.waveform_url.clone(),
+        };
+        let json = self
+            .open_media(reqwest::Method::GET, &json_url)
+            .await?
+            .error_for_status();
+
+        match json {
+            Ok(response) => Ok(response.json().await?),
+            #[cfg(feature = "waveform-png")]
+            Err(_) if track.waveform_url.ends_with(".png") => {
+                let png = self
+                    .open_media(reqwest::Method::GET, &track.waveform_url)
+                    .await?
+                    .error_for_status()?
+                    .bytes()
+                    .await?;
+                waveform_png::decode(&png)
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
     /// Resolves any soundcloud resource and returns it as a `Url`.
     pub async fn resolve(&self, url: &str) -> Result<Url> {
         let response = self.get("/resolve", Some(&[("url", url)])).await?;
@@ -272,6 +1367,187 @@ impl Client {
         }
     }
 
+    /// Resolves a soundcloud resource `url` to its id and, for private share urls, its secret
+    /// token.
+    pub(crate) async fn resolve_id(&self, url: &str) -> Result<(usize, Option<String>)> {
+        let resource_url = self.resolve(url).await?;
+        let id = resource_url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .and_then(|id| id.parse::<usize>().ok())
+            .ok_or_else(|| Error::InvalidResourceUrl(resource_url.to_string()))?;
+        let secret_token =
+            query_param(&resource_url, "secret_token").or_else(|| share_url_secret_token(url));
+
+        Ok((id, secret_token))
+    }
+
+    /// Resolves a track url, including private share urls, and returns a builder for it.
+    ///
+    /// The secret token of a private share url is kept by the builder and sent along with its
+    /// requests.
+    pub async fn resolve_track(&self, url: &str) -> Result<SingleTrackRequestBuilder<'_>> {
+        let (id, secret_token) = self.resolve_id(url).await?;
+        let mut builder = SingleTrackRequestBuilder::new(self, id);
+        builder.secret_token = secret_token;
+
+        Ok(builder)
+    }
+
+    /// Resolves a playlist url, including private share urls, and returns a builder for it.
+    ///
+    /// The secret token of a private share url is kept by the builder and sent along with its
+    /// requests.
+    pub async fn resolve_playlist(&self, url: &str) -> Result<SinglePlaylistRequestBuilder<'_>> {
+        let (id, secret_token) = self.resolve_id(url).await?;
+        let mut builder = SinglePlaylistRequestBuilder::new(self, id);
+        builder.secret_token = secret_token;
+
+        Ok(builder)
+    }
+
+    /// Returns the oEmbed payload for any soundcloud resource `url`, which can be used to embed
+    /// the player on a website.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use soundcloud::{Client, OEmbedOptions};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let options = OEmbedOptions { max_height: Some(166), ..Default::default() };
+    ///   let oembed = client.oembed("https://soundcloud.com/forss/flickermood", options).await;
+    ///
+    ///   assert!(oembed.unwrap().html.contains("<iframe"));
+    /// }
+    /// ```
+    pub async fn oembed(&self, url: &str, options: OEmbedOptions) -> Result<OEmbed> {
+        let mut oembed_url = Url::parse(crate::OEMBED_URL)?;
+        oembed_url
+            .query_pairs_mut()
+            .append_pair("url", url)
+            .extend_pairs(&options.request_params());
+
+        let request = self.http_client.get(oembed_url).build()?;
+        let response = self
+            .execute_once(&*self.transport, "api", request)
+            .await?
+            .error_for_status()?;
+        let oembed: OEmbed = self.read_json(response).await?;
+
+        Ok(oembed)
+    }
+
+    /// Fetches a single resource by its id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use soundcloud::{Client, Track};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let track = client.fetch::<Track>(262681089).await;
+    ///
+    ///   assert_eq!(track.unwrap().id, 262681089);
+    /// }
+    /// ```
+    pub async fn fetch<T: Resource>(&self, id: T::Id) -> Result<T> {
+        self.fetch_secret(id, None).await
+    }
+
+    /// Fetches a single, possibly private, resource by its id and secret token.
+    pub(crate) async fn fetch_secret<T: Resource>(
+        &self,
+        id: T::Id,
+        secret_token: Option<&str>,
+    ) -> Result<T> {
+        let path = T::path(id);
+        let params = secret_token.map(|token| [("secret_token", token)]);
+
+        self.hedged(|| async {
+            let response = self.get(&path, params).await?;
+            let resource: T = self.read_json(response).await?;
+
+            Ok(resource)
+        })
+        .await
+    }
+
+    /// Like [`Client::fetch`], but also returns the metadata of the response.
+    pub async fn fetch_with_meta<T: Resource>(&self, id: T::Id) -> Result<(T, ResponseMeta)> {
+        self.fetch_secret_with_meta(id, None).await
+    }
+
+    pub(crate) async fn fetch_secret_with_meta<T: Resource>(
+        &self,
+        id: T::Id,
+        secret_token: Option<&str>,
+    ) -> Result<(T, ResponseMeta)> {
+        let path = T::path(id);
+        let params = secret_token.map(|token| [("secret_token", token)]);
+
+        self.hedged(|| self.get_with_meta(&path, params)).await
+    }
+
+    /// Runs the `lookup`, hedged with a second run if configured in the request options.
+    ///
+    /// The result of the first successful run is returned, or the last error if both fail.
+    async fn hedged<F, Fut, T>(&self, lookup: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let delay = match self.request_options.hedge_after {
+            Some(delay) => delay,
+            None => return lookup().await,
+        };
+
+        let first = Box::pin(lookup());
+        let first = match future::select(first, Box::pin(tokio::time::sleep(delay))).await {
+            future::Either::Left((result, _)) => return result,
+            future::Either::Right((_, first)) => first,
+        };
+
+        match future::select(first, Box::pin(lookup())).await {
+            future::Either::Left((Ok(resource), _)) | future::Either::Right((Ok(resource), _)) => {
+                Ok(resource)
+            }
+            future::Either::Left((Err(_), other)) => other.await,
+            future::Either::Right((Err(_), other)) => other.await,
+        }
+    }
+
+    /// Fetches multiple resources by their ids, a few of them concurrently.
+    ///
+    /// Returns the resources in the order of their ids, or the first error which occurred.
+    pub async fn fetch_many<T, I>(&self, ids: I) -> Result<Vec<T>>
+    where
+        T: Resource,
+        I: IntoIterator<Item = T::Id>,
+    {
+        stream::iter(ids)
+            .map(|id| self.fetch::<T>(id))
+            .buffered(FETCH_MANY_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+
+    /// Fetches the details of the app with the given id, e.g. from the `created_with` field of a
+    /// track.
+    pub async fn app(&self, id: usize) -> Result<App> {
+        self.fetch::<App>(id).await
+    }
+
+    /// Returns a fetcher resolving many tracks, users or playlists with up to `limit` requests
+    /// in flight at once.
+    pub fn batch(&self, limit: usize) -> BatchFetcher {
+        BatchFetcher::new(self.clone(), limit)
+    }
+
     /// Returns a builder for a single track-by-id request.
     ///
     /// # Examples
@@ -287,26 +1563,53 @@ impl Client {
     ///   assert_eq!(track.unwrap().id, 262681089);
     /// }
     /// ```
-    pub fn track(&self, id: usize) -> SingleTrackRequestBuilder {
+    pub fn track(&self, id: usize) -> SingleTrackRequestBuilder<'_> {
         SingleTrackRequestBuilder::new(self, id)
     }
 
+    /// Searches tracks, users and playlists at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use soundcloud::{Client, SearchResult, StreamingApiExt};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let results: Vec<SearchResult> = client
+    ///     .search("lofi")
+    ///     .dedupe(true)
+    ///     .iter(Default::default())
+    ///     .take(10)
+    ///     .try_collect()
+    ///     .await
+    ///     .unwrap();
+    ///
+    ///   assert!(!results.is_empty());
+    /// }
+    /// ```
+    pub fn search<S: Into<String>>(&self, query: S) -> Search {
+        Search::new(self.clone(), query)
+    }
+
     /// Returns a builder for searching tracks with multiple criteria.
     ///
     /// # Examples
     ///
     /// ```
-    /// use soundcloud::Client;
+    /// use soundcloud::{Client, Genre};
     ///
     /// #[tokio::main]
     /// async fn main() {
     ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
-    ///   let tracks = client.tracks().genres(Some(["HipHop"])).get().await;
+    ///   let tracks = client.tracks().genres(Some([Genre::HipHopRap])).get().await;
     ///
     ///   assert!(tracks.unwrap().len() > 0);
     /// }
     /// ```
-    pub fn tracks(&self) -> TrackRequestBuilder {
+    pub fn tracks(&self) -> TrackRequestBuilder<'_> {
         TrackRequestBuilder::new(self)
     }
 
@@ -325,10 +1628,18 @@ impl Client {
     ///   assert_eq!(playlist.unwrap().id, 965640322);
     /// }
     /// ```
-    pub fn playlist(&self, id: usize) -> SinglePlaylistRequestBuilder {
+    pub fn playlist(&self, id: usize) -> SinglePlaylistRequestBuilder<'_> {
         SinglePlaylistRequestBuilder::new(self, id)
     }
 
+    /// Fetches the playlist with the given id and returns the changes of its tracks since the
+    /// `snapshot`, a previously fetched version of the playlist.
+    pub async fn playlist_changes(&self, id: u64, snapshot: &Playlist) -> Result<PlaylistDiff> {
+        let playlist = self.fetch::<Playlist>(id).await?;
+
+        Ok(snapshot.diff(&playlist))
+    }
+
     /// Returns a builder for searching playlists with multiple criteria.
     ///
     /// # Examples
@@ -344,42 +1655,454 @@ impl Client {
     ///   assert!(playlists.unwrap().len() > 0);
     /// }
     /// ```
-    pub fn playlists(&self) -> PlaylistRequestBuilder {
+    pub fn playlists(&self) -> PlaylistRequestBuilder<'_> {
         PlaylistRequestBuilder::new(self)
     }
 
+    /// Returns the chart of the given kind for a genre.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use soundcloud::{ChartKind, Client, Genre, StreamingApiExt};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let charts = client.charts(ChartKind::Top, Genre::HipHopRap);
+    ///   let entries: Vec<_> = charts.get(Default::default(), 1).try_collect().await.unwrap();
+    ///
+    ///   assert!(!entries.is_empty());
+    /// }
+    /// ```
+    pub fn charts(&self, kind: ChartKind, genre: Genre) -> Charts {
+        Charts::new(self.clone(), kind, genre)
+    }
+
+    /// Returns the station with the given urn.
+    pub fn station<S: AsRef<str>>(&self, urn: S) -> Station {
+        Station::new(self.clone(), urn)
+    }
+
+    /// Retrieve all followers of the authenticated user
+    ///
+    /// Returns:
+    ///     an instance of MyFollowers
+    pub fn my_followers(&self) -> MyFollowers {
+        MyFollowers::new(self.clone())
+    }
+
+    /// Retrieve all users followed by the authenticated user
+    ///
+    /// Returns:
+    ///     an instance of MyFollowings
+    pub fn my_followings(&self) -> MyFollowings {
+        MyFollowings::new(self.clone())
+    }
+
     /// Returns list of playlists of the authenticated user
     pub async fn my_playlists(&self) -> Result<Vec<Playlist>> {
         let params = Some(vec![("limit", "500")]);
         let res = self.get("/me/playlists", params).await?;
-        let playlists: Vec<Playlist> = res.json().await?;
+        let playlists: Vec<Playlist> = self.read_json(res).await?;
         Ok(playlists)
     }
 
+    /// Returns the personalized system playlists of the authenticated user, like The Upload,
+    /// Daily Drops and the mixes, as found on the discover page.
+    ///
+    /// The tracks of the playlists are usually only stubs, see [`SystemPlaylist::hydrate`].
+    pub async fn personalized_playlists(&self) -> Result<Vec<SystemPlaylist>> {
+        let params = Some(vec![("limit", "50")]);
+        let res = self.get("/mixed-selections", params).await?;
+        let selections: Page<Selection> = self.read_json(res).await?;
+
+        selections
+            .collection
+            .into_iter()
+            .flat_map(|selection| selection.items.collection)
+            .filter(|item| item["kind"] == "system-playlist")
+            .map(|item| Ok(serde_json::from_value(item)?))
+            .collect()
+    }
+
+    /// Updates the profile of the authenticated user.
+    ///
+    /// Returns the updated user on success, an error otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use soundcloud::{Client, ProfileUpdate};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let mut client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   client.authenticate_with_token(env!("SOUNDCLOUD_AUTH_TOKEN").to_owned());
+    ///   let update = ProfileUpdate { city: Some("Berlin".to_owned()), ..Default::default() };
+    ///   let me = client.update_me(update).await.unwrap();
+    ///
+    ///   assert_eq!(me.city.as_deref(), Some("Berlin"));
+    /// }
+    /// ```
+    pub async fn update_me(&self, update: ProfileUpdate) -> Result<User> {
+        let url = self.api_url("/me")?;
+        let request = self
+            .request(reqwest::Method::PUT, url)?
+            .multipart(update.into_form());
+        let response = self.send(request).await?;
+        let user: User = self.read_json(response).await?;
+
+        Ok(user)
+    }
+
+    /// Returns the streaming subscription of the authenticated user, which determines the
+    /// [qualities](Track::available_qualities) tracks can be streamed in.
+    pub async fn subscription(&self) -> Result<Subscription> {
+        let no_params: Option<&[(&str, &str)]> = None;
+        let response = self.get("/me", no_params).await?;
+        let me: MySubscription = self.read_json(response).await?;
+
+        Ok(match me.consumer_subscription {
+            Some(subscription) => Subscription::from_product_id(&subscription.product.id),
+            None => Subscription::Free,
+        })
+    }
+
+    /// Returns the upload quota of the authenticated user, or `None` if the API doesn't report
+    /// one.
+    pub async fn quota(&self) -> Result<Option<Quota>> {
+        let no_params: Option<&[(&str, &str)]> = None;
+        let response = self.get("/me", no_params).await?;
+        let me: MyQuota = self.read_json(response).await?;
+
+        Ok(me.quota)
+    }
+
+    /// Adds a link to the profile of the authenticated user.
+    ///
+    /// Returns the created web profile on success, an error otherwise.
+    pub async fn create_web_profile(&self, web_profile: WebProfileUpdate) -> Result<WebProfile> {
+        let url = self.api_url("/me/web-profiles")?;
+        let request = self.request(reqwest::Method::POST, url)?.json(&web_profile);
+        let response = self.send(request).await?;
+        let web_profile: WebProfile = self.read_json(response).await?;
+
+        Ok(web_profile)
+    }
+
+    /// Changes a link on the profile of the authenticated user.
+    ///
+    /// Returns the updated web profile on success, an error otherwise.
+    pub async fn update_web_profile(
+        &self,
+        id: usize,
+        update: WebProfileUpdate,
+    ) -> Result<WebProfile> {
+        let url = self.api_url(&Endpoint::new("/me/web-profiles").segment(id).to_string())?;
+        let request = self.request(reqwest::Method::PUT, url)?.json(&update);
+        let response = self.send(request).await?;
+        let web_profile: WebProfile = self.read_json(response).await?;
+
+        Ok(web_profile)
+    }
+
+    /// Removes a link from the profile of the authenticated user.
+    pub async fn delete_web_profile(&self, id: usize) -> Result<()> {
+        self.delete(&Endpoint::new("/me/web-profiles").segment(id).to_string())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Reports a play of the track for the authenticated user, the same way the web player does
+    /// once playback starts.
+    ///
+    /// This updates the playback count of the track and the user's play history, so it should
+    /// only be called when the track is actually played.
+    pub async fn report_play(&self, track: &Track) -> Result<()> {
+        let url = self.api_url("/me/play-history")?;
+        let body = serde_json::json!({
+            "track_urn": format!("soundcloud:tracks:{}", track.id),
+        });
+        let request = self.request(reqwest::Method::POST, url)?.json(&body);
+        self.send(request).await?;
+
+        Ok(())
+    }
+
     /// Returns details about the given user
-    pub fn user(&self, user_id: usize) -> SingleUserRequestBuilder {
+    pub fn user(&self, user_id: usize) -> SingleUserRequestBuilder<'_> {
         SingleUserRequestBuilder::new(self, user_id)
     }
 
+    /// Fetches a user's profile, most recent tracks and playlists and web profiles concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use soundcloud::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let snapshot = client.user_snapshot(31506117).await.unwrap();
+    ///
+    ///   assert_eq!(snapshot.user.id, 31506117);
+    /// }
+    /// ```
+    pub async fn user_snapshot(&self, user_id: usize) -> Result<UserSnapshot> {
+        let options = PageOptions::builder()
+            .page_size(USER_SNAPSHOT_LIMIT)
+            .build();
+        let tracks = Tracks::new(self.clone(), user_id);
+        let playlists = Playlists::new(self.clone(), user_id);
+        let web_profiles = WebProfiles::new(self.clone(), user_id);
+
+        let (user, tracks, playlists, web_profiles) = futures::try_join!(
+            self.fetch::<User>(user_id),
+            tracks.fetch(&options, Some(1)).try_collect(),
+            playlists.fetch(&options, Some(1)).try_collect(),
+            web_profiles.iter(Default::default()).try_collect(),
+        )?;
+
+        Ok(UserSnapshot {
+            user,
+            tracks,
+            playlists,
+            web_profiles,
+        })
+    }
+
     /// Returns a builder for searching users
-    pub fn users(&self) -> UserRequestBuilder {
+    pub fn users(&self) -> UserRequestBuilder<'_> {
         UserRequestBuilder::new(self)
     }
 
+    /// Looks up many users by their ids, with one request per 50 distinct ids.
+    ///
+    /// Returns the users in the order of their first id, skipping ids without a user, or the
+    /// first error which occurred.
+    pub async fn users_by_ids(&self, ids: &[usize]) -> Result<Vec<User>> {
+        let mut seen = HashSet::new();
+        let distinct: Vec<usize> = ids.iter().copied().filter(|id| seen.insert(*id)).collect();
+
+        let chunks: Vec<Vec<User>> = stream::iter(distinct.chunks(USERS_BY_IDS_CHUNK_SIZE))
+            .map(|chunk| async move {
+                let ids: Vec<String> = chunk.iter().map(usize::to_string).collect();
+                let params = [("ids", ids.join(","))];
+                let response = self.get("/users", Some(&params)).await?;
+                let users: Vec<User> = self.read_json(response).await?;
+                Ok::<_, Error>(users)
+            })
+            .buffered(FETCH_MANY_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        let mut users: HashMap<usize, User> = chunks
+            .into_iter()
+            .flatten()
+            .map(|user| (user.id, user))
+            .collect();
+
+        Ok(distinct.iter().filter_map(|id| users.remove(id)).collect())
+    }
+
     pub async fn likes(&self) -> Result<Vec<Track>> {
         let params = Some(vec![("limit", "500")]);
         let res = self.get("/me/likes/tracks", params).await?;
-        let likes: Vec<Track> = res.json().await?;
+        let likes: Vec<Track> = self.read_json(res).await?;
         Ok(likes)
     }
 
     /// Parses a string and returns a url with the client_id query parameter set.
     fn parse_url<S: AsRef<str>>(&self, url: S) -> Result<Url> {
         let mut url = Url::parse(url.as_ref())?;
-        url.query_pairs_mut()
-            .append_pair("client_id", &self.client_id);
+        self.append_params(&mut url);
         Ok(url)
     }
+
+    /// Appends the client_id and configured query parameters to the url, unless they are present
+    /// already, e.g. in a `next_href` returned by the API.
+    fn append_params(&self, url: &mut Url) {
+        let present: Vec<String> = url.query_pairs().map(|(key, _)| key.into_owned()).collect();
+        let mut query_pairs = url.query_pairs_mut();
+
+        if !present.iter().any(|key| key == "client_id") {
+            query_pairs.append_pair("client_id", &self.client_id.current());
+        }
+
+        for (key, value) in &self.params {
+            if !present.contains(key) {
+                query_pairs.append_pair(key, value);
+            }
+        }
+    }
+}
+
+/// Returns the value of the first query parameter with the given key.
+fn query_param(url: &Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value.into_owned())
+}
+
+/// Returns the secret token of a private share url, e.g.
+/// `https://soundcloud.com/user/track/s-AbCdE`.
+fn share_url_secret_token(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let last = url.path_segments()?.rfind(|s| !s.is_empty())?;
+
+    if last.starts_with("s-") {
+        Some(last.to_owned())
+    } else {
+        None
+    }
+}
+
+/// Replaces the value of all query parameters with the given key.
+fn set_query_param(url: &mut Url, key: &str, value: &str) {
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .into_owned()
+        .map(|(k, v)| {
+            if k == key {
+                (k, value.to_owned())
+            } else {
+                (k, v)
+            }
+        })
+        .collect();
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+}
+
+/// Writes the `chunks` to the `writer` as they are received, then flushes it.
+///
+/// If the `cancellation` future completes in between, [`Error::Cancelled`] is returned after
+/// flushing the chunks written so far.
+async fn write_chunks<W, C>(
+    mut chunks: BoxStream<'static, io::Result<Bytes>>,
+    mut writer: W,
+    mut cancellation: Option<C>,
+) -> Result<u64>
+where
+    W: AsyncWrite + Unpin,
+    C: Future<Output = ()> + Unpin,
+{
+    let mut num_bytes = 0;
+
+    loop {
+        let chunk = match cancellation {
+            Some(ref mut cancelled) => match future::select(cancelled, chunks.try_next()).await {
+                future::Either::Left(_) => {
+                    writer.flush().await?;
+                    return Err(Error::Cancelled(num_bytes));
+                }
+                future::Either::Right((chunk, _)) => chunk?,
+            },
+            None => chunks.try_next().await?,
+        };
+        match chunk {
+            Some(chunk) => {
+                writer.write_all(&chunk).await?;
+                num_bytes += chunk.len() as u64;
+            }
+            None => break,
+        }
+    }
+    writer.flush().await?;
+
+    Ok(num_bytes)
+}
+
+/// Returns the scopes required by a `WWW-Authenticate` challenge like
+/// `Bearer error="insufficient_scope", scope="non-expiring"`.
+fn insufficient_scope_challenge(challenge: &str) -> Option<Vec<String>> {
+    let param = |name: &str| {
+        let start = challenge.find(&format!("{}=", name))? + name.len() + 1;
+        let value = challenge[start..].trim_start_matches('"');
+        let end = value.find(['"', ',']).unwrap_or(value.len());
+        Some(&value[..end])
+    };
+
+    if param("error")? != "insufficient_scope" {
+        return None;
+    }
+    Some(split_scopes(param("scope").unwrap_or_default()))
+}
+
+/// Returns the scopes required by an error body like
+/// `{"error": "insufficient_scope", "scope": "non-expiring"}`.
+fn insufficient_scope_body(body: &[u8]) -> Option<Vec<String>> {
+    let body: serde_json::Value = serde_json::from_slice(body).ok()?;
+
+    if body.get("error")?.as_str()? != "insufficient_scope" {
+        return None;
+    }
+    let scope = body.get("scope").and_then(|scope| scope.as_str());
+    Some(split_scopes(scope.unwrap_or_default()))
+}
+
+/// Returns whether an error body like `{"errors": [{"error_message": "invalid client_id"}]}`
+/// rejects the client id of the request.
+fn rejects_client_id(body: &[u8]) -> bool {
+    let body: serde_json::Value = match serde_json::from_slice(body) {
+        Ok(body) => body,
+        Err(_) => return false,
+    };
+    let errors = body.get("errors").and_then(|errors| errors.as_array());
+    let messages = std::iter::once(&body).chain(errors.into_iter().flatten());
+
+    let rejected = messages
+        .flat_map(|error| ["error", "error_message", "message"].map(|key| error.get(key)))
+        .filter_map(|message| message?.as_str())
+        .any(|message| message.contains("client_id") || message.contains("client id"));
+    rejected
+}
+
+fn split_scopes(scope: &str) -> Vec<String> {
+    scope.split_whitespace().map(str::to_owned).collect()
+}
+
+/// Hasher of the media data and the checksum provided by the CDN.
+type Verification = (Arc<Mutex<Hasher>>, Vec<u8>);
+
+/// Compares the checksum of the media data with the one provided by the CDN.
+fn verify_checksum(verification: Option<Verification>) -> Result<()> {
+    if let Some((hasher, expected)) = verification {
+        let actual = match hasher.lock() {
+            Ok(mut hasher) => hasher.finalize_reset(),
+            Err(poisoned) => poisoned.into_inner().finalize_reset(),
+        };
+        if actual != expected {
+            return Err(Error::ChecksumMismatch {
+                expected: to_hex(&expected),
+                actual: to_hex(&actual),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the stream url of the `track`, if it can be streamed.
+fn stream_url(track: &Track) -> Result<&str> {
+    if track.policy == Some(Policy::Block) {
+        return Err(Error::GeoBlocked);
+    }
+    match track.stream_url {
+        Some(ref stream_url) if track.streamable => Ok(stream_url),
+        _ => Err(Error::TrackNotStreamable),
+    }
+}
+
+/// Returns the download url of the `track`, if it can be downloaded.
+fn download_url(track: &Track) -> Result<&str> {
+    match track.download_url {
+        Some(ref download_url) if track.downloadable => Ok(download_url),
+        _ => Err(Error::TrackNotDownloadable),
+    }
 }
 
 /// "unfold" paginated results of a list of soundcloud entities
@@ -0,0 +1,391 @@
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT_LANGUAGE};
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::circuit_breaker::CircuitBreaker;
+use crate::client::Client;
+use crate::client_id::{ClientIdProvider, ClientIdRotation};
+use crate::error::Result;
+use crate::media_urls::MediaUrlCache;
+use crate::pacer::Pacer;
+use crate::transport::HttpTransport;
+
+/// A mutable builder for a [`Client`] with non-default configuration.
+///
+/// # Examples
+///
+/// ```
+/// use soundcloud::Client;
+///
+/// let client = Client::builder(env!("SOUNDCLOUD_CLIENT_ID"))
+///     .app_locale("de")
+///     .accept_language("de-DE")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ClientBuilder {
+    host: String,
+    client_id: String,
+    auth_token: Option<String>,
+    app_locale: Option<String>,
+    app_version: Option<String>,
+    accept_language: Option<String>,
+    client_id_provider: Option<Arc<dyn ClientIdProvider>>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    circuit_breaker: Option<(u32, Duration)>,
+    max_response_size: Option<u64>,
+    comment_interval: Duration,
+    media_redirects: usize,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    http2: bool,
+    media_http2_prior_knowledge: bool,
+    media_http2_adaptive_window: bool,
+    media_tcp_keepalive: Option<Duration>,
+    transport: Option<Arc<dyn HttpTransport>>,
+    media_transport: Option<Arc<dyn HttpTransport>>,
+    #[cfg(any(feature = "gzip", feature = "brotli"))]
+    compression: bool,
+}
+
+impl ClientBuilder {
+    /// Creates a new builder for a client with the provided `client_id`.
+    pub fn new(client_id: &str) -> Self {
+        ClientBuilder {
+            host: crate::API_HOST.to_owned(),
+            client_id: client_id.to_owned(),
+            auth_token: None,
+            app_locale: None,
+            app_version: None,
+            accept_language: None,
+            client_id_provider: None,
+            timeout: None,
+            connect_timeout: None,
+            circuit_breaker: None,
+            max_response_size: None,
+            comment_interval: Duration::from_secs(10),
+            media_redirects: 10,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            http2: true,
+            media_http2_prior_knowledge: false,
+            media_http2_adaptive_window: false,
+            media_tcp_keepalive: None,
+            transport: None,
+            media_transport: None,
+            #[cfg(any(feature = "gzip", feature = "brotli"))]
+            compression: true,
+        }
+    }
+
+    /// Sets the API host, defaults to [`API_HOST`](crate::API_HOST).
+    pub fn host<S: AsRef<str>>(&mut self, host: S) -> &mut Self {
+        self.host = host.as_ref().to_owned();
+        self
+    }
+
+    /// Sets the OAuth token used to authenticate requests.
+    pub fn auth_token<S: AsRef<str>>(&mut self, token: S) -> &mut Self {
+        self.auth_token = Some(token.as_ref().to_owned());
+        self
+    }
+
+    /// Sets the `app_locale` parameter sent with every request, e.g. `de` or `pt_BR`.
+    ///
+    /// The API localizes metadata such as genre names and picks region-specific charts based on it.
+    pub fn app_locale<S: AsRef<str>>(&mut self, locale: S) -> &mut Self {
+        self.app_locale = Some(locale.as_ref().to_owned());
+        self
+    }
+
+    /// Sets the `app_version` parameter sent with every request.
+    pub fn app_version<S: AsRef<str>>(&mut self, version: S) -> &mut Self {
+        self.app_version = Some(version.as_ref().to_owned());
+        self
+    }
+
+    /// Sets the `Accept-Language` header sent with every request, e.g. `de-DE,de;q=0.9`.
+    pub fn accept_language<S: AsRef<str>>(&mut self, language: S) -> &mut Self {
+        self.accept_language = Some(language.as_ref().to_owned());
+        self
+    }
+
+    /// Sets a provider for fresh client ids, which is used when the API rejects the current one.
+    ///
+    /// Requests failing with `401 Unauthorized` are retried once with the new client id, so
+    /// long running jobs survive the client id being invalidated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use soundcloud::{Client, WebsiteClientIdProvider};
+    ///
+    /// let client = Client::builder(env!("SOUNDCLOUD_CLIENT_ID"))
+    ///     .client_id_provider(WebsiteClientIdProvider::new())
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn client_id_provider<P>(&mut self, provider: P) -> &mut Self
+    where
+        P: ClientIdProvider + 'static,
+    {
+        self.client_id_provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// Sets the global timeout for every request, including media downloads.
+    ///
+    /// Use [`Client::with_options`] for deadlines of individual API calls.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for establishing connections.
+    pub fn connect_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Enables a circuit breaker which opens after `failure_threshold` consecutive server errors
+    /// or timeouts.
+    ///
+    /// While the circuit is open, requests fail immediately with [`Error::CircuitOpen`] for the
+    /// `cooldown` period instead of hitting the degraded API.
+    pub fn circuit_breaker(&mut self, failure_threshold: u32, cooldown: Duration) -> &mut Self {
+        self.circuit_breaker = Some((failure_threshold, cooldown));
+        self
+    }
+
+    /// Sets the maximum number of idle connections kept open per host, which is unlimited by
+    /// default.
+    pub fn pool_max_idle_per_host(&mut self, max: usize) -> &mut Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Limits the size of API response bodies to `max_size` bytes, which is unlimited by default.
+    ///
+    /// Larger responses fail with [`Error::ResponseTooLarge`] instead of being read into memory.
+    /// Media downloads and streams aren't limited, nor are the bodies of the responses returned
+    /// by [`Client::get`](crate::Client::get), which are read by the caller.
+    pub fn max_response_size(&mut self, max_size: u64) -> &mut Self {
+        self.max_response_size = Some(max_size);
+        self
+    }
+
+    /// Sets the minimum interval between comments posted by the client and its clones, which
+    /// defaults to 10 seconds, as SoundCloud quickly starts requiring a captcha otherwise.
+    pub fn comment_interval(&mut self, interval: Duration) -> &mut Self {
+        self.comment_interval = interval;
+        self
+    }
+
+    /// Sets how long idle connections are kept open for reuse, which defaults to 90 seconds.
+    pub fn pool_idle_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets whether HTTP/2 is used if the server supports it, which is enabled by default.
+    ///
+    /// If disabled, only HTTP/1.1 is used.
+    pub fn http2(&mut self, enabled: bool) -> &mut Self {
+        self.http2 = enabled;
+        self
+    }
+
+    /// Sets the maximum number of redirects followed from a stream or download url to the media
+    /// on the CDN, which defaults to 10.
+    ///
+    /// API requests never follow redirects, as some endpoints like `/resolve` answer with one.
+    pub fn media_redirects(&mut self, max: usize) -> &mut Self {
+        self.media_redirects = max;
+        self
+    }
+
+    /// Sets whether media is requested with HTTP/2 right away, without negotiating the protocol
+    /// first, for CDNs which are known to support it.
+    ///
+    /// Ignored if HTTP/2 is [disabled](ClientBuilder::http2).
+    pub fn media_http2_prior_knowledge(&mut self, enabled: bool) -> &mut Self {
+        self.media_http2_prior_knowledge = enabled;
+        self
+    }
+
+    /// Sets whether the HTTP/2 flow control window of media downloads adapts to the measured
+    /// bandwidth and latency, which speeds up large downloads over high-latency links.
+    pub fn media_http2_adaptive_window(&mut self, enabled: bool) -> &mut Self {
+        self.media_http2_adaptive_window = enabled;
+        self
+    }
+
+    /// Sets the interval of TCP keepalive probes on media connections, so long downloads survive
+    /// idle timeouts of middleboxes.
+    pub fn media_tcp_keepalive(&mut self, interval: Duration) -> &mut Self {
+        self.media_tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// Sets the transport executing API requests, instead of the built-in `reqwest` client.
+    ///
+    /// The transport must not follow redirects, as some endpoints like `/resolve` answer with one.
+    /// The HTTP settings of this builder only apply to the built-in client.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::future::BoxFuture;
+    /// use soundcloud::{Client, HttpTransport, Result};
+    ///
+    /// struct Logging(reqwest::Client);
+    ///
+    /// impl HttpTransport for Logging {
+    ///     fn execute(&self, request: reqwest::Request) -> BoxFuture<'_, Result<reqwest::Response>> {
+    ///         println!("{} {}", request.method(), request.url());
+    ///         HttpTransport::execute(&self.0, request)
+    ///     }
+    /// }
+    ///
+    /// let http_client = reqwest::Client::builder()
+    ///     .redirect(reqwest::redirect::Policy::none())
+    ///     .build()
+    ///     .unwrap();
+    /// let client = Client::builder(env!("SOUNDCLOUD_CLIENT_ID"))
+    ///     .transport(Logging(http_client))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn transport<T>(&mut self, transport: T) -> &mut Self
+    where
+        T: HttpTransport + 'static,
+    {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Sets the transport executing media requests, instead of the built-in `reqwest` client.
+    ///
+    /// The transport must follow the redirects from stream and download urls to the CDN.
+    pub fn media_transport<T>(&mut self, transport: T) -> &mut Self
+    where
+        T: HttpTransport + 'static,
+    {
+        self.media_transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Sets whether API responses are requested compressed, which is enabled by default.
+    ///
+    /// The supported encodings depend on the enabled `gzip` and `brotli` features. Media
+    /// downloads are never compressed.
+    #[cfg(any(feature = "gzip", feature = "brotli"))]
+    pub fn compression(&mut self, enabled: bool) -> &mut Self {
+        self.compression = enabled;
+        self
+    }
+
+    /// Builds the client.
+    ///
+    /// Returns an error if the configuration is invalid or the HTTP client can't be initialized.
+    /// The client id isn't checked, see [`Client::try_new`].
+    pub fn build(&self) -> Result<Client> {
+        let mut headers = HeaderMap::new();
+
+        if let Some(ref language) = self.accept_language {
+            headers.insert(ACCEPT_LANGUAGE, HeaderValue::from_str(language)?);
+        }
+
+        let http_client = self
+            .http_client_builder()
+            .redirect(reqwest::redirect::Policy::none());
+        #[cfg(feature = "gzip")]
+        let http_client = http_client.gzip(self.compression);
+        #[cfg(feature = "brotli")]
+        let http_client = http_client.brotli(self.compression);
+        let http_client = http_client.build()?;
+
+        let mut media_client = self
+            .http_client_builder()
+            .redirect(reqwest::redirect::Policy::limited(self.media_redirects))
+            .http2_adaptive_window(self.media_http2_adaptive_window)
+            .tcp_keepalive(self.media_tcp_keepalive);
+
+        if self.http2 && self.media_http2_prior_knowledge {
+            media_client = media_client.http2_prior_knowledge();
+        }
+
+        #[cfg(feature = "gzip")]
+        let media_client = media_client.gzip(false);
+        #[cfg(feature = "brotli")]
+        let media_client = media_client.brotli(false);
+        let media_client = media_client.build()?;
+
+        let mut params = vec![];
+
+        if let Some(ref locale) = self.app_locale {
+            params.push(("app_locale".to_owned(), locale.clone()));
+        }
+
+        if let Some(ref version) = self.app_version {
+            params.push(("app_version".to_owned(), version.clone()));
+        }
+
+        Ok(Client {
+            host: self.host.clone(),
+            client_id: ClientIdRotation::new(
+                self.client_id.clone(),
+                self.client_id_provider.clone(),
+            ),
+            auth_token: self.auth_token.clone(),
+            params,
+            headers,
+            request_options: Default::default(),
+            circuit_breaker: self
+                .circuit_breaker
+                .map(|(threshold, cooldown)| Arc::new(CircuitBreaker::new(threshold, cooldown))),
+            max_response_size: self.max_response_size,
+            comment_pacer: Arc::new(Pacer::new(self.comment_interval)),
+            transport: self
+                .transport
+                .clone()
+                .unwrap_or_else(|| Arc::new(http_client.clone())),
+            media_transport: self
+                .media_transport
+                .clone()
+                .unwrap_or_else(|| Arc::new(media_client)),
+            http_client,
+            media_urls: Arc::new(MediaUrlCache::default()),
+        })
+    }
+
+    /// Returns a builder for an HTTP client with the settings shared by API and media requests.
+    fn http_client_builder(&self) -> reqwest::ClientBuilder {
+        let mut http_client = reqwest::ClientBuilder::new();
+
+        if let Some(timeout) = self.timeout {
+            http_client = http_client.timeout(timeout);
+        }
+
+        if let Some(timeout) = self.connect_timeout {
+            http_client = http_client.connect_timeout(timeout);
+        }
+
+        if let Some(max) = self.pool_max_idle_per_host {
+            http_client = http_client.pool_max_idle_per_host(max);
+        }
+
+        if let Some(timeout) = self.pool_idle_timeout {
+            http_client = http_client.pool_idle_timeout(timeout);
+        }
+
+        if !self.http2 {
+            http_client = http_client.http1_only();
+        }
+
+        http_client
+    }
+}
@@ -0,0 +1,171 @@
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use futures::future::BoxFuture;
+use futures::lock::Mutex;
+
+use crate::error::{Error, Result};
+
+/// Provides fresh client ids once the current one has been invalidated.
+///
+/// Configured with [`ClientBuilder::client_id_provider`](crate::ClientBuilder::client_id_provider),
+/// the client asks the provider for a new id whenever the API rejects a request with
+/// `401 Unauthorized` and retries the request once with the new id. Requests with an auth token
+/// only rotate the id if the response says that the client id was rejected.
+pub trait ClientIdProvider: Send + Sync {
+    /// Returns a new client id.
+    fn client_id(&self) -> BoxFuture<'_, Result<String>>;
+}
+
+impl fmt::Debug for dyn ClientIdProvider {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ClientIdProvider")
+    }
+}
+
+/// A [`ClientIdProvider`] which scrapes the client id used by the SoundCloud website.
+#[derive(Debug, Clone, Default)]
+pub struct WebsiteClientIdProvider {
+    http_client: reqwest::Client,
+}
+
+impl WebsiteClientIdProvider {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    async fn scrape(&self) -> Result<String> {
+        let html = self
+            .http_client
+            .get("https://soundcloud.com")
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        // The client id is defined in one of the application scripts, usually one of the last.
+        for script in script_urls(&html).iter().rev() {
+            let source = self
+                .http_client
+                .get(*script)
+                .send()
+                .await?
+                .error_for_status()?
+                .text()
+                .await?;
+
+            if let Some(client_id) = find_client_id(&source) {
+                return Ok(client_id);
+            }
+        }
+
+        Err(Error::ApiError(
+            "no client id found on the website".to_owned(),
+        ))
+    }
+}
+
+impl ClientIdProvider for WebsiteClientIdProvider {
+    fn client_id(&self) -> BoxFuture<'_, Result<String>> {
+        Box::pin(self.scrape())
+    }
+}
+
+fn script_urls(html: &str) -> Vec<&str> {
+    html.split("<script")
+        .skip(1)
+        .filter_map(|tag| {
+            let start = tag.find("src=\"")? + 5;
+            let end = start + tag[start..].find('"')?;
+            Some(&tag[start..end])
+        })
+        .filter(|src| src.starts_with("https://a-v2.sndcdn.com/assets/"))
+        .collect()
+}
+
+fn find_client_id(source: &str) -> Option<String> {
+    source.split("client_id").skip(1).find_map(|rest| {
+        let rest = rest.trim_start_matches([':', '=', '"']);
+        let id: String = rest
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+
+        if id.len() == 32 {
+            Some(id)
+        } else {
+            None
+        }
+    })
+}
+
+/// The current client id of a client, shared between all of its clones.
+#[derive(Clone)]
+pub(crate) struct ClientIdRotation {
+    initial: String,
+    current: Arc<RwLock<String>>,
+    provider: Option<Arc<dyn ClientIdProvider>>,
+    lock: Arc<Mutex<()>>,
+}
+
+impl ClientIdRotation {
+    pub(crate) fn new(client_id: String, provider: Option<Arc<dyn ClientIdProvider>>) -> Self {
+        ClientIdRotation {
+            initial: client_id.clone(),
+            current: Arc::new(RwLock::new(client_id)),
+            provider,
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    pub(crate) fn initial(&self) -> &str {
+        &self.initial
+    }
+
+    pub(crate) fn current(&self) -> String {
+        match self.current.read() {
+            Ok(current) => current.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    pub(crate) fn can_rotate(&self) -> bool {
+        self.provider.is_some()
+    }
+
+    /// Replaces the `rejected` client id with a new one from the provider.
+    ///
+    /// If another request rotated the id in the meantime, the already rotated id is returned.
+    pub(crate) async fn rotate(&self, rejected: &str) -> Result<String> {
+        let provider = match self.provider {
+            Some(ref provider) => provider,
+            None => return Err(Error::InvalidClientId(rejected.to_owned())),
+        };
+        let _guard = self.lock.lock().await;
+        let current = self.current();
+
+        if current != rejected {
+            return Ok(current);
+        }
+
+        let client_id = provider.client_id().await?;
+        log::info!("rotated invalidated client id");
+
+        match self.current.write() {
+            Ok(mut current) => *current = client_id.clone(),
+            Err(poisoned) => *poisoned.into_inner() = client_id.clone(),
+        }
+
+        Ok(client_id)
+    }
+}
+
+impl fmt::Debug for ClientIdRotation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ClientIdRotation")
+            .field("current", &self.current())
+            .field("provider", &self.provider)
+            .finish()
+    }
+}
@@ -0,0 +1,365 @@
+//! Bounded breadth-first crawling of the follow graph, and sharded crawling of large
+//! collections.
+
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use futures::channel::oneshot;
+use futures::prelude::*;
+use futures::stream::BoxStream;
+use serde::de::{DeserializeOwned, IgnoredAny};
+
+use crate::apis::{Followers, Followings};
+use crate::client::Client;
+use crate::error::{Error, Result};
+use crate::models::User;
+use crate::page::{Page, PageOptions};
+use crate::streaming_api::StreamingApi;
+
+/// Number of users requested per page while crawling.
+const CRAWL_PAGE_SIZE: u32 = 200;
+
+/// Which relations of a user are followed while crawling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// The users a user follows.
+    Followings,
+    /// The users following a user.
+    Followers,
+    /// Both followings and followers.
+    Both,
+}
+
+/// Something discovered while crawling.
+#[derive(Debug, Clone)]
+pub enum CrawlEvent {
+    /// A user visited for the first time, `depth` hops away from the nearest seed.
+    User { user: Box<User>, depth: usize },
+    /// `follower` follows `followed`.
+    Edge { follower: usize, followed: usize },
+}
+
+/// Crawls the follow graph breadth-first, starting from a set of seed users.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use soundcloud::Client;
+/// use soundcloud::crawler::Crawler;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+///   let events: Vec<_> = Crawler::new(client, vec![1])
+///     .max_depth(1)
+///     .max_users(10)
+///     .crawl()
+///     .try_collect()
+///     .await
+///     .unwrap();
+///
+///   assert!(!events.is_empty());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Crawler {
+    client: Client,
+    seeds: Vec<usize>,
+    direction: Direction,
+    max_depth: usize,
+    max_users: usize,
+    rate_limit: Option<Duration>,
+}
+
+impl Crawler {
+    /// Creates a crawler starting at the `seeds` user ids, which follows the followings of each
+    /// user up to a depth of 2 and visits at most 1000 users.
+    pub fn new(client: Client, seeds: Vec<usize>) -> Self {
+        Crawler {
+            client,
+            seeds,
+            direction: Direction::Followings,
+            max_depth: 2,
+            max_users: 1000,
+            rate_limit: None,
+        }
+    }
+
+    /// Sets which relations of a user are followed.
+    pub fn direction(&mut self, direction: Direction) -> &mut Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Sets the maximum number of hops away from the seeds.
+    pub fn max_depth(&mut self, max_depth: usize) -> &mut Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the maximum number of visited users, including the seeds.
+    ///
+    /// The crawl ends as soon as this many users have been visited.
+    pub fn max_users(&mut self, max_users: usize) -> &mut Self {
+        self.max_users = max_users;
+        self
+    }
+
+    /// Sets the minimum time between two requests.
+    pub fn rate_limit(&mut self, interval: Duration) -> &mut Self {
+        self.rate_limit = Some(interval);
+        self
+    }
+
+    /// Returns a stream of the visited users and the edges between them.
+    ///
+    /// The stream ends after the first error.
+    pub fn crawl(&self) -> BoxStream<'static, Result<CrawlEvent>> {
+        let state = CrawlState {
+            crawler: self.clone(),
+            seeds: self.seeds.iter().copied().collect(),
+            queue: VecDeque::new(),
+            visited: HashSet::new(),
+            pending: VecDeque::new(),
+            last_request: None,
+        };
+
+        Box::pin(stream::try_unfold(state, |mut state| async move {
+            let event = state.next().await?;
+            Ok(event.map(|event| (event, state)))
+        }))
+    }
+}
+
+struct CrawlState {
+    crawler: Crawler,
+    seeds: VecDeque<usize>,
+    queue: VecDeque<(usize, usize)>,
+    visited: HashSet<usize>,
+    pending: VecDeque<CrawlEvent>,
+    last_request: Option<Instant>,
+}
+
+impl CrawlState {
+    async fn next(&mut self) -> Result<Option<CrawlEvent>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+
+            if self.is_full() {
+                return Ok(None);
+            }
+
+            if let Some(id) = self.seeds.pop_front() {
+                if self.visited.insert(id) {
+                    self.throttle().await;
+                    let user = self.crawler.client.fetch::<User>(id).await?;
+                    self.pending.push_back(CrawlEvent::User {
+                        user: Box::new(user),
+                        depth: 0,
+                    });
+                    self.queue.push_back((id, 0));
+                }
+                continue;
+            }
+
+            match self.queue.pop_front() {
+                Some((id, depth)) if depth < self.crawler.max_depth => {
+                    self.expand(id, depth).await?
+                }
+                Some(_) => continue,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    async fn expand(&mut self, id: usize, depth: usize) -> Result<()> {
+        let client = self.crawler.client.clone();
+
+        if self.crawler.direction != Direction::Followers {
+            let path = Followings::new(client.clone(), id).url(&page_options());
+            for user in self.neighbors(&path).await? {
+                self.visit(id, user, depth + 1, true);
+            }
+        }
+
+        if self.crawler.direction != Direction::Followings {
+            let path = Followers::new(client, id).url(&page_options());
+            for user in self.neighbors(&path).await? {
+                self.visit(id, user, depth + 1, false);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fetches the users of all pages at `path`, until the crawl is full.
+    async fn neighbors(&mut self, path: &str) -> Result<Vec<User>> {
+        let client = self.crawler.client.clone();
+        self.throttle().await;
+        let mut page: Page<User> = client.get_pages(path).await?;
+        let mut users = vec![];
+
+        loop {
+            let new = page
+                .collection
+                .iter()
+                .filter(|user| !self.visited.contains(&user.id))
+                .count();
+            let full = self.visited.len() + new >= self.crawler.max_users;
+            users.append(&mut page.collection);
+
+            match page.next_href {
+                Some(ref next_href) if !full => {
+                    self.throttle().await;
+                    page = client.get_pages_url(next_href).await?;
+                }
+                _ => return Ok(users),
+            }
+        }
+    }
+
+    fn visit(&mut self, id: usize, user: User, depth: usize, following: bool) {
+        let (follower, followed) = if following {
+            (id, user.id)
+        } else {
+            (user.id, id)
+        };
+        let neighbor = user.id;
+
+        if !self.is_full() && self.visited.insert(neighbor) {
+            self.pending.push_back(CrawlEvent::User {
+                user: Box::new(user),
+                depth,
+            });
+            self.queue.push_back((neighbor, depth));
+        }
+
+        if self.visited.contains(&neighbor) {
+            self.pending
+                .push_back(CrawlEvent::Edge { follower, followed });
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.visited.len() >= self.crawler.max_users
+    }
+
+    async fn throttle(&mut self) {
+        if let Some(interval) = self.crawler.rate_limit {
+            if let Some(last_request) = self.last_request {
+                let elapsed = last_request.elapsed();
+                if elapsed < interval {
+                    tokio::time::sleep(interval - elapsed).await;
+                }
+            }
+        }
+        self.last_request = Some(Instant::now());
+    }
+}
+
+/// Streams all items of a large collection, like the followers of a popular user, fetching up to
+/// `workers` pages at the same time.
+///
+/// Pages are linked by cursors, so each worker waits for the cursor of the page before it.
+/// As soon as it has fetched its page, it hands the next cursor off to the following worker and
+/// only then decodes the items, so fetching and decoding overlap. Items are yielded in the order
+/// of the collection, and at most `workers` pages are held in memory.
+///
+/// The stream ends after the first error, whose [`resume_url`](crate::Error::resume_url) can be
+/// used to resume the collection.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use soundcloud::{Client, PageOptions, User};
+/// use soundcloud::crawler::sharded;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+///   let options = PageOptions::builder().page_size(200).build();
+///   let followers: Vec<User> = sharded(&client.user(31506117).followers(), options, 4)
+///     .take(1000)
+///     .try_collect()
+///     .await
+///     .unwrap();
+///
+///   assert!(!followers.is_empty());
+/// }
+/// ```
+pub fn sharded<A>(
+    api: &A,
+    options: PageOptions,
+    workers: usize,
+) -> BoxStream<'static, Result<A::Model>>
+where
+    A: StreamingApi + ?Sized,
+{
+    let client = api.client().clone();
+    let (first, mut cursor) = oneshot::channel();
+    let _ = first.send(Some(api.url(&options)));
+
+    // Endless stream of workers, each receiving its cursor from the one before.
+    let workers = stream::repeat_with(move || {
+        let (next, next_cursor) = oneshot::channel();
+        let cursor = std::mem::replace(&mut cursor, next_cursor);
+        fetch_shard::<A::Model>(client.clone(), cursor, next)
+    })
+    .buffered(workers.max(1));
+
+    api.adapt_stream(Box::pin(
+        workers
+            .take_while(|page| future::ready(!matches!(page, Ok(None))))
+            .scan(false, |failed, page| {
+                // End after the first error, skipping the workers canceled by it.
+                if *failed {
+                    return future::ready(None);
+                }
+                *failed = page.is_err();
+                future::ready(Some(page))
+            })
+            .map_ok(|page| stream::iter(page.unwrap_or_default()).map(Ok))
+            .try_flatten(),
+    ))
+}
+
+/// Fetches the page at the url received from `cursor`, hands its `next_href` off to `next` and
+/// decodes its items.
+///
+/// Returns `None` once the collection has ended before this page.
+async fn fetch_shard<T>(
+    client: Client,
+    cursor: oneshot::Receiver<Option<String>>,
+    next: oneshot::Sender<Option<String>>,
+) -> Result<Option<Vec<T>>>
+where
+    T: DeserializeOwned,
+{
+    let url = match cursor.await {
+        Ok(Some(url)) => url,
+        // The collection has ended, or a worker before failed.
+        _ => return Ok(None),
+    };
+
+    let body = client.get_raw_page(&url).await?;
+    let decode = || -> Result<Vec<T>> {
+        let cursor: Page<IgnoredAny> = serde_json::from_slice(&body)?;
+        let _ = next.send(cursor.next_href);
+
+        let page: Page<T> = serde_json::from_slice(&body)?;
+        Ok(page.collection)
+    };
+
+    decode().map(Some).map_err(|source| Error::Pagination {
+        page_url: url,
+        source: Box::new(source),
+    })
+}
+
+fn page_options() -> PageOptions {
+    PageOptions::builder().page_size(CRAWL_PAGE_SIZE).build()
+}
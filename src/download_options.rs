@@ -0,0 +1,147 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use md5::Md5;
+use reqwest::header::HeaderMap;
+use sha2::{Digest, Sha256};
+
+/// Options of a download, see [`Client::download_with_options`](crate::Client::download_with_options).
+#[derive(Clone, Debug, Default)]
+pub struct DownloadOptions {
+    pub(crate) verify: Option<Checksum>,
+    pub(crate) trust_etag: bool,
+}
+
+impl DownloadOptions {
+    pub fn builder() -> DownloadOptionsBuilder {
+        DownloadOptionsBuilder::new()
+    }
+}
+
+/// a mutable download options builder
+pub struct DownloadOptionsBuilder(DownloadOptions);
+
+impl DownloadOptionsBuilder {
+    fn new() -> Self {
+        DownloadOptionsBuilder(DownloadOptions::default())
+    }
+
+    /// Computes the `checksum` of the downloaded data and compares it to the checksum provided by
+    /// the CDN, if there is one.
+    pub fn verify(&mut self, checksum: Checksum) -> &mut Self {
+        self.0.verify = Some(checksum);
+        self
+    }
+
+    /// Also compares an MD5 checksum to a strong `ETag`, which most CDNs set to the MD5 of files
+    /// which weren't uploaded in parts. Defaults to `false`, as an `ETag` may be any opaque value.
+    pub fn trust_etag(&mut self, trust_etag: bool) -> &mut Self {
+        self.0.trust_etag = trust_etag;
+        self
+    }
+
+    pub fn build(&self) -> DownloadOptions {
+        self.0.clone()
+    }
+}
+
+/// Algorithm of a checksum used to verify a download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checksum {
+    /// MD5, as provided in the `Content-MD5` or `x-goog-hash` headers, or the `ETag` header if
+    /// [trusted](DownloadOptionsBuilder::trust_etag).
+    Md5,
+    /// SHA-256, as provided in the `x-amz-checksum-sha256`, `Repr-Digest` or `Digest` headers.
+    Sha256,
+}
+
+impl Checksum {
+    pub(crate) fn hasher(self) -> Hasher {
+        match self {
+            Checksum::Md5 => Hasher::Md5(Md5::new()),
+            Checksum::Sha256 => Hasher::Sha256(Sha256::new()),
+        }
+    }
+
+    /// Returns the checksum of the response body provided in the `headers`, including a strong
+    /// `ETag` if `trust_etag` is set.
+    pub(crate) fn expected(self, headers: &HeaderMap, trust_etag: bool) -> Option<Vec<u8>> {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+        match self {
+            Checksum::Md5 => header("content-md5")
+                .and_then(decode_base64)
+                .or_else(|| header("x-goog-hash").and_then(|hash| digest_field(hash, "md5")))
+                .or_else(|| header("etag").filter(|_| trust_etag).and_then(decode_etag)),
+            Checksum::Sha256 => header("x-amz-checksum-sha256")
+                .and_then(decode_base64)
+                .or_else(|| header("repr-digest").and_then(|hash| digest_field(hash, "sha-256")))
+                .or_else(|| header("digest").and_then(|hash| digest_field(hash, "sha-256"))),
+        }
+    }
+}
+
+pub(crate) enum Hasher {
+    Md5(Md5),
+    Sha256(Sha256),
+}
+
+impl Hasher {
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        match *self {
+            Hasher::Md5(ref mut hasher) => hasher.update(data),
+            Hasher::Sha256(ref mut hasher) => hasher.update(data),
+        }
+    }
+
+    /// Returns the checksum of the data so far and resets the hasher.
+    pub(crate) fn finalize_reset(&mut self) -> Vec<u8> {
+        match *self {
+            Hasher::Md5(ref mut hasher) => hasher.finalize_reset().to_vec(),
+            Hasher::Sha256(ref mut hasher) => hasher.finalize_reset().to_vec(),
+        }
+    }
+}
+
+/// Formats a checksum as lowercase hex.
+pub(crate) fn to_hex(checksum: &[u8]) -> String {
+    checksum
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn decode_base64(value: &str) -> Option<Vec<u8>> {
+    BASE64.decode(value.trim()).ok()
+}
+
+/// Finds the base64 encoded digest of `algorithm` in a list like `md5=...,crc32c=...` or
+/// `sha-256=:...:`.
+fn digest_field(value: &str, algorithm: &str) -> Option<Vec<u8>> {
+    value.split(',').find_map(|field| {
+        let (name, digest) = field.split_once('=')?;
+        if name.trim().eq_ignore_ascii_case(algorithm) {
+            decode_base64(digest.trim().trim_matches(':'))
+        } else {
+            None
+        }
+    })
+}
+
+/// Decodes an entity tag which is the hex encoded MD5 of the body, as used by S3 and most CDNs
+/// for files which weren't uploaded in parts.
+///
+/// Weak entity tags only identify equivalent representations and are never checksums.
+fn decode_etag(value: &str) -> Option<Vec<u8>> {
+    if value.starts_with("W/") {
+        return None;
+    }
+    let etag = value.trim_matches('"');
+    if etag.len() != 32 {
+        return None;
+    }
+
+    (0..etag.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(etag.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,115 @@
+//! Building urls and iframes of the embedded SoundCloud player.
+
+use crate::models::{Playlist, Track};
+use crate::query_params::QueryParams;
+
+/// The embedded player of the SoundCloud website.
+pub const PLAYER_URL: &str = "https://w.soundcloud.com/player/";
+
+/// A resource which can be played by the embedded player.
+pub trait Embeddable {
+    /// Returns the API resource URL the player loads.
+    fn embed_uri(&self) -> &str;
+}
+
+impl Embeddable for Track {
+    fn embed_uri(&self) -> &str {
+        &self.uri
+    }
+}
+
+impl Embeddable for Playlist {
+    fn embed_uri(&self) -> &str {
+        &self.uri
+    }
+}
+
+/// Options of the embedded player.
+///
+/// Options which are left as `None` use the default of the player.
+#[derive(Debug, Clone, Default)]
+pub struct PlayerOptions {
+    /// Whether the player should start playing on load.
+    pub auto_play: Option<bool>,
+    /// Primary color of the player as a hex triplet, e.g. `ff5500`.
+    pub color: Option<String>,
+    /// Whether the player shows the artwork in full size instead of the waveform.
+    pub visual: Option<bool>,
+    /// Whether the player hides related tracks after the end.
+    pub hide_related: Option<bool>,
+    /// Whether the player should display timed comments.
+    pub show_comments: Option<bool>,
+    /// Whether the player should display the uploader.
+    pub show_user: Option<bool>,
+    /// Whether the player should display reposts.
+    pub show_reposts: Option<bool>,
+    /// Index of the track of a playlist to start with.
+    pub start_track: Option<usize>,
+}
+
+impl PlayerOptions {
+    fn params(&self) -> QueryParams {
+        let color = self
+            .color
+            .as_ref()
+            .map(|color| format!("#{}", color.trim_start_matches('#')));
+        let mut params = QueryParams::new();
+        params
+            .push_opt("auto_play", self.auto_play)
+            .push_opt("color", color)
+            .push_opt("visual", self.visual)
+            .push_opt("hide_related", self.hide_related)
+            .push_opt("show_comments", self.show_comments)
+            .push_opt("show_user", self.show_user)
+            .push_opt("show_reposts", self.show_reposts)
+            .push_opt("start_track", self.start_track);
+        params
+    }
+}
+
+/// Returns the url of the embedded player for the track or playlist.
+///
+/// # Examples
+///
+/// ```
+/// use soundcloud::Track;
+/// use soundcloud::embed::{player_url, PlayerOptions};
+///
+/// let track = Track::new_for_test(1);
+/// let options = PlayerOptions {
+///     auto_play: Some(true),
+///     color: Some("ff5500".to_owned()),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     player_url(&track, &options),
+///     "https://w.soundcloud.com/player/?url=https%3A%2F%2Fapi.soundcloud.com%2Ftracks%2F1\
+///      &auto_play=true&color=%23ff5500"
+/// );
+/// ```
+pub fn player_url<E: Embeddable + ?Sized>(item: &E, options: &PlayerOptions) -> String {
+    let mut params = QueryParams::new();
+    params.push("url", item.embed_uri());
+    for (key, value) in options.params().iter() {
+        params.push(key, value);
+    }
+
+    format!("{}?{}", PLAYER_URL, params.encode())
+}
+
+/// Returns an HTML iframe of the embedded player for the track or playlist, with the height the
+/// player needs.
+pub fn player_iframe<E: Embeddable + ?Sized>(item: &E, options: &PlayerOptions) -> String {
+    let height = if options.visual == Some(true) {
+        450
+    } else {
+        166
+    };
+
+    format!(
+        r#"<iframe width="100%" height="{}" scrolling="no" frameborder="no" allow="autoplay" src="{}"></iframe>"#,
+        height,
+        player_url(item, options).replace('&', "&amp;")
+    )
+}
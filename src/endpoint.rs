@@ -0,0 +1,89 @@
+use std::fmt;
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+use crate::query_params::QueryParams;
+
+/// Characters which have to be percent-encoded in a path segment.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'\\')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// Path and query of an API endpoint, relative to the API host.
+///
+/// Path segments and query parameters are percent-encoded when the endpoint is formatted, so
+/// they may contain any characters.
+///
+/// # Examples
+///
+/// ```
+/// use soundcloud::Endpoint;
+///
+/// let endpoint = Endpoint::new("/users")
+///     .segment("dj/mix ü")
+///     .param("limit", 10);
+///
+/// assert_eq!("/users/dj%2Fmix%20%C3%BC?limit=10", endpoint.to_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    base: &'static str,
+    segments: Vec<String>,
+    params: QueryParams,
+}
+
+impl Endpoint {
+    /// Creates an endpoint for the `base` path, like `/tracks`, which is used as is.
+    pub fn new(base: &'static str) -> Self {
+        Endpoint {
+            base,
+            segments: vec![],
+            params: QueryParams::new(),
+        }
+    }
+
+    /// Appends a path segment.
+    pub fn segment<S: ToString>(mut self, segment: S) -> Self {
+        self.segments.push(segment.to_string());
+        self
+    }
+
+    /// Appends a query parameter.
+    pub fn param<V: ToString>(mut self, key: &str, value: V) -> Self {
+        self.params.push(key, value);
+        self
+    }
+
+    /// Appends all of the query `params`.
+    pub fn params(mut self, params: &QueryParams) -> Self {
+        for (key, value) in params {
+            self.params.push(key, value);
+        }
+        self
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.base)?;
+        for segment in &self.segments {
+            write!(f, "/{}", utf8_percent_encode(segment, PATH_SEGMENT))?;
+        }
+        if !self.params.is_empty() {
+            write!(f, "?{}", self.params.encode())?;
+        }
+        Ok(())
+    }
+}
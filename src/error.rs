@@ -8,15 +8,77 @@ pub type Result<T> = result::Result<T, Error>;
 #[derive(Debug)]
 pub enum Error {
     ApiError(String),
+    AuthenticationRequired(String),
+    CaptchaRequired,
+    ChecksumMismatch {
+        expected: String,
+        actual: String,
+    },
+    Cancelled(u64),
+    CircuitOpen(std::time::Duration),
     JsonError(serde_json::Error),
     HttpError(reqwest::Error),
     HttpHeaderError(reqwest::header::ToStrError),
     HttpInvalidHeaderError(reqwest::header::InvalidHeaderValue),
+    InsufficientScope {
+        required: Vec<String>,
+    },
+    InvalidClientId(String),
     InvalidFilter(String),
+    InvalidGenre(String),
+    InvalidPartSize(u64),
+    InvalidResourceUrl(String),
+    InvalidUrn(String),
+    InvalidWaveform(String),
+    GeoBlocked,
     Io(io::Error),
+    ItemDecode {
+        json: serde_json::Value,
+        source: serde_json::Error,
+    },
+    Pagination {
+        page_url: String,
+        source: Box<Error>,
+    },
+    ProcessingFailed(u64),
+    ProcessingTimedOut(u64),
+    QuotaExceeded(std::time::Duration),
+    ResponseTooLarge(u64),
+    SubscriptionRequired(crate::models::Quality),
     TrackNotDownloadable,
     TrackNotStreamable,
+    TrackRemoved,
     UrlParseError(url::ParseError),
+    ZipError(async_zip::error::ZipError),
+}
+
+impl Error {
+    /// Returns true if the error is likely transient, like a network error, a server error or
+    /// a rate limit, so the request can be retried.
+    pub fn is_retryable(&self) -> bool {
+        match *self {
+            Error::HttpError(ref error) => match error.status() {
+                Some(status) => {
+                    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                }
+                None => !error.is_decode() && !error.is_builder(),
+            },
+            Error::CircuitOpen(_) => true,
+            Error::Pagination { ref source, .. } => source.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// Returns the url of the page which failed to load, if this is a [`Error::Pagination`].
+    ///
+    /// All items before this page have been yielded, so the collection can be resumed from this
+    /// url with [`Client::stream_collection`](crate::Client::stream_collection).
+    pub fn resume_url(&self) -> Option<&str> {
+        match *self {
+            Error::Pagination { ref page_url, .. } => Some(page_url),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -27,11 +89,73 @@ impl fmt::Display for Error {
             Error::HttpHeaderError(ref error) => write!(f, "HTTP error: {}", error),
             Error::HttpInvalidHeaderError(ref error) => write!(f, "HTTP error: {}", error),
             Error::ApiError(ref error) => write!(f, "SoundCloud error: {}", error),
+            Error::AuthenticationRequired(ref endpoint) => {
+                write!(f, "Authentication required for {}", endpoint)
+            }
+            Error::CaptchaRequired => write!(
+                f,
+                "SoundCloud requires solving a captcha on the website before posting again"
+            ),
+            Error::Cancelled(num_bytes) => {
+                write!(f, "Cancelled after writing {} bytes", num_bytes)
+            }
+            Error::CircuitOpen(ref retry_after) => write!(
+                f,
+                "Too many failed requests, retry in {}s",
+                retry_after.as_secs()
+            ),
+            Error::ChecksumMismatch {
+                ref expected,
+                ref actual,
+            } => write!(
+                f,
+                "Checksum mismatch: expected {}, computed {}",
+                expected, actual
+            ),
+            Error::GeoBlocked => write!(f, "The track is not available in this region"),
             Error::Io(ref error) => write!(f, "IO error: {}", error),
+            Error::ItemDecode { ref source, .. } => write!(f, "Item decoding error: {}", source),
+            Error::Pagination {
+                ref page_url,
+                ref source,
+            } => write!(f, "Failed to fetch page {}: {}", page_url, source),
+            Error::ProcessingFailed(id) => write!(f, "Processing of track {} failed", id),
+            Error::ProcessingTimedOut(id) => {
+                write!(f, "Timed out waiting for track {} to be processed", id)
+            }
+            Error::QuotaExceeded(ref remaining) => {
+                write!(f, "Upload quota exceeded, {}s left", remaining.as_secs())
+            }
+            Error::ResponseTooLarge(limit) => {
+                write!(f, "Response body exceeds the limit of {} bytes", limit)
+            }
+            Error::InsufficientScope { ref required } => write!(
+                f,
+                "The auth token lacks the required scopes: {}",
+                required.join(" ")
+            ),
+            Error::InvalidClientId(ref id) => write!(f, "Invalid client id: {:?}", id),
             Error::InvalidFilter(_) => write!(f, "Invalid filter"),
+            Error::InvalidGenre(ref genre) => write!(f, "Invalid genre: {}", genre),
+            Error::InvalidPartSize(part_size) => write!(
+                f,
+                "Part size of {} bytes is below the minimum of {} bytes",
+                part_size,
+                crate::upload::MIN_PART_SIZE
+            ),
+            Error::InvalidResourceUrl(ref url) => write!(f, "Invalid resource URL: {}", url),
+            Error::InvalidUrn(ref urn) => write!(f, "Invalid URN: {}", urn),
+            Error::InvalidWaveform(ref reason) => write!(f, "Invalid waveform image: {}", reason),
+            Error::SubscriptionRequired(ref quality) => write!(
+                f,
+                "A subscription is required to stream in {:?} quality",
+                quality
+            ),
             Error::TrackNotStreamable => write!(f, "The track is not available for streaming"),
             Error::TrackNotDownloadable => write!(f, "The track is not available for download"),
+            Error::TrackRemoved => write!(f, "The track has been removed"),
             Error::UrlParseError(ref error) => write!(f, "URL parsing error: {}", error),
+            Error::ZipError(ref error) => write!(f, "Zip error: {}", error),
         }
     }
 }
@@ -42,6 +166,9 @@ impl error::Error for Error {
             Error::JsonError(ref error) => Some(error),
             Error::HttpError(ref error) => Some(error),
             Error::Io(ref error) => Some(error),
+            Error::ItemDecode { ref source, .. } => Some(source),
+            Error::Pagination { ref source, .. } => Some(source.as_ref()),
+            Error::ZipError(ref error) => Some(error),
             _ => None,
         }
     }
@@ -77,6 +204,12 @@ impl From<reqwest::header::InvalidHeaderValue> for Error {
     }
 }
 
+impl From<async_zip::error::ZipError> for Error {
+    fn from(error: async_zip::error::ZipError) -> Error {
+        Error::ZipError(error)
+    }
+}
+
 impl From<url::ParseError> for Error {
     fn from(error: url::ParseError) -> Error {
         Error::UrlParseError(error)
@@ -0,0 +1,70 @@
+//! Exporting tracks into archives.
+
+use async_zip::base::write::ZipFileWriter;
+use async_zip::{Compression, ZipEntryBuilder};
+use futures::io::AsyncWrite;
+
+use crate::client::Client;
+use crate::error::Result;
+use crate::models::Track;
+
+/// Streams the audio of each of the `tracks` into a zip archive written to the `writer`, along
+/// with a JSON file of the track's metadata.
+///
+/// The files are named after the position and the title of the track, e.g. `01 - Title.mp3` and
+/// `01 - Title.json`. They're stored without compression, as the audio is already compressed.
+///
+/// Returns:
+///     the `writer` after the archive has been completed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use soundcloud::Client;
+/// use soundcloud::export::zip_tracks;
+/// use tokio::fs::File;
+/// use tokio_util::compat::TokioAsyncWriteCompatExt;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+///   let playlist = client.playlist(965640322).get().await.unwrap();
+///   let tracks = playlist.tracks.unwrap_or_default();
+///   let outfile = File::create("playlist.zip").await.unwrap().compat_write();
+///   zip_tracks(&client, &tracks, outfile).await.unwrap();
+/// }
+/// ```
+pub async fn zip_tracks<W: AsyncWrite + Unpin>(
+    client: &Client,
+    tracks: &[Track],
+    writer: W,
+) -> Result<W> {
+    let mut zip = ZipFileWriter::new(writer);
+
+    for (index, track) in tracks.iter().enumerate() {
+        let name = format!("{:02} - {}", index + 1, file_name(&track.title));
+
+        let entry = ZipEntryBuilder::new(format!("{}.mp3", name).into(), Compression::Stored);
+        let mut audio = zip.write_entry_stream(entry).await?;
+        client.stream(track, &mut audio).await?;
+        audio.close().await?;
+
+        let metadata = serde_json::to_vec_pretty(track)?;
+        let entry = ZipEntryBuilder::new(format!("{}.json", name).into(), Compression::Stored);
+        zip.write_entry_whole(entry, &metadata).await?;
+    }
+
+    Ok(zip.close().await?)
+}
+
+/// Replaces the characters of `title` which aren't allowed in file names on common platforms.
+pub(crate) fn file_name(title: &str) -> String {
+    title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
@@ -0,0 +1,56 @@
+//! Formatting durations and counts the way the SoundCloud website displays them.
+
+use std::time::Duration;
+
+/// Formats a `duration` as `m:ss`, or as `h:mm:ss` from one hour on.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use soundcloud::fmt::format_duration;
+///
+/// assert_eq!("3:07", format_duration(Duration::from_secs(187)));
+/// assert_eq!("2:00:05", format_duration(Duration::from_secs(7205)));
+/// ```
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// Formats a `count` of plays, likes or followers in short form, like `1.2M` or `845K`.
+///
+/// Counts are truncated rather than rounded, so a count is never shown as more than it is.
+///
+/// # Examples
+///
+/// ```
+/// use soundcloud::fmt::format_count;
+///
+/// assert_eq!("999", format_count(999));
+/// assert_eq!("12.3K", format_count(12_345));
+/// assert_eq!("1.2M", format_count(1_234_567));
+/// ```
+pub fn format_count(count: u64) -> String {
+    const UNITS: [(u64, &str); 3] = [(1_000_000_000, "B"), (1_000_000, "M"), (1_000, "K")];
+
+    for &(size, suffix) in UNITS.iter() {
+        if count >= size {
+            let whole = count / size;
+            let tenths = count % size / (size / 10);
+            return if whole >= 100 || tenths == 0 {
+                format!("{}{}", whole, suffix)
+            } else {
+                format!("{}.{}{}", whole, tenths, suffix)
+            };
+        }
+    }
+
+    count.to_string()
+}
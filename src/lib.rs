@@ -4,19 +4,68 @@
 //! about tracks and users.
 
 pub use crate::apis::*;
+pub use crate::batch::{BatchFetcher, BatchResult};
 pub use crate::client::Client;
+pub use crate::client_builder::ClientBuilder;
+pub use crate::client_id::{ClientIdProvider, WebsiteClientIdProvider};
+pub use crate::download_options::{Checksum, DownloadOptions, DownloadOptionsBuilder};
+pub use crate::endpoint::Endpoint;
 pub use crate::error::{Error, Result};
 pub use crate::models::App;
 pub use crate::models::*;
-pub use crate::page::PageOptions;
+pub use crate::page::{Page, PageOptions};
+pub use crate::query_params::QueryParams;
+pub use crate::request_options::{RequestOptions, RequestOptionsBuilder};
+pub use crate::resource::Resource;
+pub use crate::response_meta::ResponseMeta;
+pub use crate::retry::{RetryPolicy, RetryPolicyBuilder};
+pub use crate::session::SessionManager;
 pub use crate::streaming_api::StreamingApiExt;
+pub use crate::transform::{MediaReader, MediaTransform, PassThrough};
+pub use crate::transport::HttpTransport;
 
 /// The static host address for the API.
 pub const API_HOST: &str = "https://api-v2.soundcloud.com";
 
+/// The oEmbed endpoint of the SoundCloud website.
+pub const OEMBED_URL: &str = "https://soundcloud.com/oembed";
+
+/// The endpoint revoking OAuth tokens, see [`Client::sign_out`].
+pub const SIGN_OUT_URL: &str = "https://secure.soundcloud.com/sign-out";
+
+pub mod analytics;
 mod apis;
+pub mod archive;
+mod batch;
+mod circuit_breaker;
 mod client;
+mod client_builder;
+mod client_id;
+pub mod crawler;
+mod download_options;
+pub mod embed;
+mod endpoint;
 pub mod error;
+pub mod export;
+pub mod fmt;
+mod media_urls;
 mod models;
+mod pacer;
 mod page;
+mod page_decoder;
+mod query_params;
+mod request_options;
+mod resource;
+mod response_meta;
+mod retry;
+#[cfg(feature = "tower")]
+mod service;
+mod session;
 mod streaming_api;
+mod telemetry;
+mod transform;
+mod transport;
+pub mod upload;
+pub mod watch;
+#[cfg(feature = "waveform-png")]
+mod waveform_png;
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use url::Url;
+
+/// Time before the expiry of a signed url from which on it's no longer used, so transfers don't
+/// start right before the url expires.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+/// Number of urls kept at most, after which the one expiring first is dropped.
+const CAPACITY: usize = 1024;
+
+/// Stream or download url of a track, and the auth token it was requested with.
+///
+/// The CDN url a stream url redirects to depends on the account, e.g. a preview for free accounts
+/// or a private track only its owner can access, so it's only reused with the same token.
+type Key = (Option<String>, String);
+
+/// The signed CDN urls which the stream and download urls of tracks redirected to, kept until
+/// they expire or make room for newer ones.
+#[derive(Debug, Default)]
+pub(crate) struct MediaUrlCache {
+    urls: Mutex<HashMap<Key, (Url, SystemTime)>>,
+}
+
+impl MediaUrlCache {
+    /// Returns the signed url `url` redirected to when requested with the auth `token`, if it's
+    /// still valid.
+    pub(crate) fn get(&self, token: Option<&str>, url: &str) -> Option<Url> {
+        let key = key(token, url);
+        let mut urls = self.urls();
+
+        match urls.get(&key) {
+            Some((resolved, expiry)) if SystemTime::now() + EXPIRY_MARGIN < *expiry => {
+                Some(resolved.clone())
+            }
+            Some(_) => {
+                urls.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Remembers that `url` redirected to `resolved` when requested with the auth `token`, if
+    /// it's a signed url with an expiry.
+    ///
+    /// Expired urls are dropped, and the one expiring first if the cache is full.
+    pub(crate) fn insert(&self, token: Option<&str>, url: &str, resolved: &Url) {
+        let expiry = match expiry(resolved) {
+            Some(expiry) => expiry,
+            None => return,
+        };
+        let key = key(token, url);
+        let mut urls = self.urls();
+
+        let now = SystemTime::now();
+        urls.retain(|_, (_, expiry)| now + EXPIRY_MARGIN < *expiry);
+        if urls.len() >= CAPACITY && !urls.contains_key(&key) {
+            let first = urls
+                .iter()
+                .min_by_key(|(_, (_, expiry))| *expiry)
+                .map(|(key, _)| key.clone());
+            if let Some(first) = first {
+                urls.remove(&first);
+            }
+        }
+        urls.insert(key, (resolved.clone(), expiry));
+    }
+
+    pub(crate) fn remove(&self, token: Option<&str>, url: &str) {
+        self.urls().remove(&key(token, url));
+    }
+
+    fn urls(&self) -> MutexGuard<'_, HashMap<Key, (Url, SystemTime)>> {
+        match self.urls.lock() {
+            Ok(urls) => urls,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+fn key(token: Option<&str>, url: &str) -> Key {
+    (token.map(str::to_owned), url.to_owned())
+}
+
+/// Returns the expiry of a signed url, given as unix timestamp in its `Expires` parameter.
+fn expiry(url: &Url) -> Option<SystemTime> {
+    let (_, expires) = url
+        .query_pairs()
+        .find(|(key, _)| key.eq_ignore_ascii_case("expires"))?;
+    let seconds = expires.parse().ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(seconds))
+}
@@ -1,16 +1,54 @@
+use std::hash::{Hash, Hasher};
+
 use serde::{Deserialize, Serialize};
 
 /// Registered client application.
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
 pub struct App {
     /// Integer ID.
     pub id: usize,
+    /// Name of the app.
+    #[serde(default)]
+    pub name: Option<String>,
     /// API resource URL.
-    pub uri: String,
+    #[serde(default)]
+    pub uri: Option<String>,
     /// URL to the SoundCloud.com page
-    pub permalink_url: String,
+    #[serde(default)]
+    pub permalink_url: Option<String>,
     /// URL to an external site.
-    pub external_url: String,
+    #[serde(default)]
+    pub external_url: Option<String>,
     /// Username of the app creator.
+    #[serde(default)]
     pub creator: Option<String>,
 }
+
+impl App {
+    /// Constructs an app with the given id and placeholder values for all other fields.
+    pub fn new_for_test(id: usize) -> App {
+        App {
+            id,
+            name: None,
+            uri: Some(format!("https://api.soundcloud.com/apps/{}", id)),
+            permalink_url: None,
+            external_url: None,
+            creator: None,
+        }
+    }
+}
+
+impl PartialEq for App {
+    fn eq(&self, other: &App) -> bool {
+        other.id == self.id
+    }
+}
+
+impl Eq for App {}
+
+impl Hash for App {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
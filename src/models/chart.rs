@@ -0,0 +1,19 @@
+use crate::models::Track;
+use serde::{Deserialize, Serialize};
+
+/// Entry of a genre chart.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct ChartEntry {
+    /// Chart score of the track.
+    pub score: f64,
+    /// The charted track.
+    pub track: Track,
+}
+
+impl ChartEntry {
+    /// Constructs a chart entry for the given track.
+    pub fn new_for_test(score: f64, track: Track) -> ChartEntry {
+        ChartEntry { score, track }
+    }
+}
@@ -1,8 +1,13 @@
+use std::hash::{Hash, Hasher};
+
+use crate::apis::{SingleTrackRequestBuilder, SingleUserRequestBuilder};
+use crate::client::Client;
 use crate::models::User;
 use serde::{Deserialize, Serialize};
 
 /// User comment.
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
 pub struct Comment {
     /// Integer ID.
     pub id: usize,
@@ -21,3 +26,43 @@ pub struct Comment {
     /// The track ID of the related track.
     pub track_id: usize,
 }
+
+impl Comment {
+    /// Constructs a comment with the given id and placeholder values for all other fields.
+    pub fn new_for_test(id: usize) -> Comment {
+        Comment {
+            id,
+            uri: format!("https://api.soundcloud.com/comments/{}", id),
+            created_at: String::new(),
+            body: String::new(),
+            timestamp: None,
+            user_id: 0,
+            user: User::new_for_test(0),
+            track_id: 0,
+        }
+    }
+
+    /// Returns a request builder for the track this comment was posted on.
+    pub fn track<'a>(&self, client: &'a Client) -> SingleTrackRequestBuilder<'a> {
+        client.track(self.track_id)
+    }
+
+    /// Returns a request builder for the user who posted this comment.
+    pub fn user<'a>(&self, client: &'a Client) -> SingleUserRequestBuilder<'a> {
+        client.user(self.user_id)
+    }
+}
+
+impl PartialEq for Comment {
+    fn eq(&self, other: &Comment) -> bool {
+        other.id == self.id
+    }
+}
+
+impl Eq for Comment {}
+
+impl Hash for Comment {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
@@ -0,0 +1,43 @@
+use std::path::Path;
+
+/// Availability of the original file of a track, as probed by
+/// [`Client::download_info`](crate::Client::download_info).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DownloadInfo {
+    /// If the file can actually be retrieved.
+    pub available: bool,
+    /// Size of the file in bytes, if announced by the server.
+    pub size: Option<u64>,
+    /// MIME type of the file, e.g. `audio/wav`.
+    pub content_type: Option<String>,
+    /// File name suggested by the server.
+    pub file_name: Option<String>,
+}
+
+impl DownloadInfo {
+    /// Returns the file format as a lowercase file extension, e.g. `wav`, based on the file name
+    /// or, if it has no extension, the MIME type.
+    pub fn format(&self) -> Option<String> {
+        let extension = self
+            .file_name
+            .as_deref()
+            .and_then(|name| Path::new(name).extension())
+            .and_then(|extension| extension.to_str());
+
+        if let Some(extension) = extension {
+            return Some(extension.to_ascii_lowercase());
+        }
+
+        let format = match self.content_type.as_deref()? {
+            "audio/mpeg" | "audio/mp3" => "mp3",
+            "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+            "audio/flac" | "audio/x-flac" => "flac",
+            "audio/aiff" | "audio/x-aiff" => "aiff",
+            "audio/ogg" => "ogg",
+            "audio/mp4" | "audio/x-m4a" | "audio/aac" => "m4a",
+            _ => return None,
+        };
+        Some(format.to_owned())
+    }
+}
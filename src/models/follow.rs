@@ -0,0 +1,24 @@
+use serde::Deserialize;
+
+use crate::models::User;
+
+/// A user in a followers or followings list, along with the time the follow happened.
+#[derive(Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct Follow {
+    /// The follower or followed user.
+    #[serde(flatten)]
+    pub user: User,
+    /// Time of the follow, as an unparsed string.
+    ///
+    /// Only included by the API for authenticated requests.
+    #[serde(default)]
+    pub followed_at: Option<String>,
+}
+
+impl Follow {
+    /// Constructs a follow of the given user.
+    pub fn new_for_test(user: User, followed_at: Option<String>) -> Follow {
+        Follow { user, followed_at }
+    }
+}
@@ -0,0 +1,233 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// Canonical SoundCloud genres.
+///
+/// Each genre has a URN (used by the charts API, e.g. `soundcloud:genres:hiphoprap`) and a plain
+/// display name (used by the search filters, e.g. `Hip-hop & Rap`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Genre {
+    AllMusic,
+    AllAudio,
+    AlternativeRock,
+    Ambient,
+    Classical,
+    Country,
+    DanceEdm,
+    Dancehall,
+    DeepHouse,
+    Disco,
+    DrumBass,
+    Dubstep,
+    Electronic,
+    FolkSingerSongwriter,
+    HipHopRap,
+    House,
+    Indie,
+    JazzBlues,
+    Latin,
+    Metal,
+    Piano,
+    Pop,
+    RbSoul,
+    Reggae,
+    Reggaeton,
+    Rock,
+    Soundtrack,
+    Techno,
+    Trance,
+    Trap,
+    TripHop,
+    World,
+    Audiobooks,
+    Business,
+    Comedy,
+    Entertainment,
+    Learning,
+    NewsPolitics,
+    ReligionSpirituality,
+    Science,
+    Sports,
+    Storytelling,
+    Technology,
+}
+
+impl Genre {
+    /// All known genres.
+    pub const ALL: &'static [Genre] = &[
+        Genre::AllMusic,
+        Genre::AllAudio,
+        Genre::AlternativeRock,
+        Genre::Ambient,
+        Genre::Classical,
+        Genre::Country,
+        Genre::DanceEdm,
+        Genre::Dancehall,
+        Genre::DeepHouse,
+        Genre::Disco,
+        Genre::DrumBass,
+        Genre::Dubstep,
+        Genre::Electronic,
+        Genre::FolkSingerSongwriter,
+        Genre::HipHopRap,
+        Genre::House,
+        Genre::Indie,
+        Genre::JazzBlues,
+        Genre::Latin,
+        Genre::Metal,
+        Genre::Piano,
+        Genre::Pop,
+        Genre::RbSoul,
+        Genre::Reggae,
+        Genre::Reggaeton,
+        Genre::Rock,
+        Genre::Soundtrack,
+        Genre::Techno,
+        Genre::Trance,
+        Genre::Trap,
+        Genre::TripHop,
+        Genre::World,
+        Genre::Audiobooks,
+        Genre::Business,
+        Genre::Comedy,
+        Genre::Entertainment,
+        Genre::Learning,
+        Genre::NewsPolitics,
+        Genre::ReligionSpirituality,
+        Genre::Science,
+        Genre::Sports,
+        Genre::Storytelling,
+        Genre::Technology,
+    ];
+
+    /// The identifier used in the genre URN.
+    pub fn id(&self) -> &'static str {
+        match *self {
+            Genre::AllMusic => "all-music",
+            Genre::AllAudio => "all-audio",
+            Genre::AlternativeRock => "alternativerock",
+            Genre::Ambient => "ambient",
+            Genre::Classical => "classical",
+            Genre::Country => "country",
+            Genre::DanceEdm => "danceedm",
+            Genre::Dancehall => "dancehall",
+            Genre::DeepHouse => "deephouse",
+            Genre::Disco => "disco",
+            Genre::DrumBass => "drumbass",
+            Genre::Dubstep => "dubstep",
+            Genre::Electronic => "electronic",
+            Genre::FolkSingerSongwriter => "folksingersongwriter",
+            Genre::HipHopRap => "hiphoprap",
+            Genre::House => "house",
+            Genre::Indie => "indie",
+            Genre::JazzBlues => "jazzblues",
+            Genre::Latin => "latin",
+            Genre::Metal => "metal",
+            Genre::Piano => "piano",
+            Genre::Pop => "pop",
+            Genre::RbSoul => "rbsoul",
+            Genre::Reggae => "reggae",
+            Genre::Reggaeton => "reggaeton",
+            Genre::Rock => "rock",
+            Genre::Soundtrack => "soundtrack",
+            Genre::Techno => "techno",
+            Genre::Trance => "trance",
+            Genre::Trap => "trap",
+            Genre::TripHop => "triphop",
+            Genre::World => "world",
+            Genre::Audiobooks => "audiobooks",
+            Genre::Business => "business",
+            Genre::Comedy => "comedy",
+            Genre::Entertainment => "entertainment",
+            Genre::Learning => "learning",
+            Genre::NewsPolitics => "newspolitics",
+            Genre::ReligionSpirituality => "religionspirituality",
+            Genre::Science => "science",
+            Genre::Sports => "sports",
+            Genre::Storytelling => "storytelling",
+            Genre::Technology => "technology",
+        }
+    }
+
+    /// The genre URN, as accepted by the charts API.
+    pub fn urn(&self) -> String {
+        format!("soundcloud:genres:{}", self.id())
+    }
+
+    /// The plain genre name, as accepted by the search filters.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Genre::AllMusic => "All music genres",
+            Genre::AllAudio => "All audio genres",
+            Genre::AlternativeRock => "Alternative Rock",
+            Genre::Ambient => "Ambient",
+            Genre::Classical => "Classical",
+            Genre::Country => "Country",
+            Genre::DanceEdm => "Dance & EDM",
+            Genre::Dancehall => "Dancehall",
+            Genre::DeepHouse => "Deep House",
+            Genre::Disco => "Disco",
+            Genre::DrumBass => "Drum & Bass",
+            Genre::Dubstep => "Dubstep",
+            Genre::Electronic => "Electronic",
+            Genre::FolkSingerSongwriter => "Folk & Singer-Songwriter",
+            Genre::HipHopRap => "Hip-hop & Rap",
+            Genre::House => "House",
+            Genre::Indie => "Indie",
+            Genre::JazzBlues => "Jazz & Blues",
+            Genre::Latin => "Latin",
+            Genre::Metal => "Metal",
+            Genre::Piano => "Piano",
+            Genre::Pop => "Pop",
+            Genre::RbSoul => "R&B & Soul",
+            Genre::Reggae => "Reggae",
+            Genre::Reggaeton => "Reggaeton",
+            Genre::Rock => "Rock",
+            Genre::Soundtrack => "Soundtrack",
+            Genre::Techno => "Techno",
+            Genre::Trance => "Trance",
+            Genre::Trap => "Trap",
+            Genre::TripHop => "Triphop",
+            Genre::World => "World",
+            Genre::Audiobooks => "Audiobooks",
+            Genre::Business => "Business",
+            Genre::Comedy => "Comedy",
+            Genre::Entertainment => "Entertainment",
+            Genre::Learning => "Learning",
+            Genre::NewsPolitics => "News & Politics",
+            Genre::ReligionSpirituality => "Religion & Spirituality",
+            Genre::Science => "Science",
+            Genre::Sports => "Sports",
+            Genre::Storytelling => "Storytelling",
+            Genre::Technology => "Technology",
+        }
+    }
+}
+
+impl FromStr for Genre {
+    type Err = Error;
+
+    /// Parses a genre from its URN, URN identifier or plain name.
+    fn from_str(s: &str) -> Result<Genre> {
+        let id = s.strip_prefix("soundcloud:genres:").unwrap_or(s);
+        Genre::ALL
+            .iter()
+            .find(|genre| genre.id() == id || genre.name().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| Error::InvalidGenre(s.to_string()))
+    }
+}
+
+impl fmt::Display for Genre {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl AsRef<str> for Genre {
+    fn as_ref(&self) -> &str {
+        self.name()
+    }
+}
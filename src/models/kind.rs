@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{App, Comment, Playlist, Track, User, WebProfile};
+
+/// Discriminator of the resources returned by the API, as found in their `kind` field.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum Kind {
+    Track,
+    User,
+    Playlist,
+    SystemPlaylist,
+    Comment,
+    WebProfile,
+    App,
+    Like,
+    TrackRepost,
+    PlaylistRepost,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Resources which have a [`Kind`].
+pub trait HasKind {
+    fn kind(&self) -> Kind;
+}
+
+impl HasKind for Track {
+    fn kind(&self) -> Kind {
+        Kind::Track
+    }
+}
+
+impl HasKind for User {
+    fn kind(&self) -> Kind {
+        Kind::User
+    }
+}
+
+impl HasKind for Playlist {
+    fn kind(&self) -> Kind {
+        self.kind
+    }
+}
+
+impl HasKind for Comment {
+    fn kind(&self) -> Kind {
+        Kind::Comment
+    }
+}
+
+impl HasKind for WebProfile {
+    fn kind(&self) -> Kind {
+        self.kind
+    }
+}
+
+impl HasKind for App {
+    fn kind(&self) -> Kind {
+        Kind::App
+    }
+}
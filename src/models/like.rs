@@ -0,0 +1,45 @@
+use serde::Deserialize;
+
+use crate::models::{Playlist, User};
+
+/// Like of a playlist or album.
+#[derive(Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct PlaylistLike {
+    /// Time of the like, as an unparsed string.
+    pub created_at: String,
+    /// The liked playlist.
+    pub playlist: Playlist,
+}
+
+impl PlaylistLike {
+    /// Constructs a like of the given playlist.
+    pub fn new_for_test(playlist: Playlist) -> PlaylistLike {
+        PlaylistLike {
+            created_at: String::new(),
+            playlist,
+        }
+    }
+}
+
+/// Like of a track, see
+/// [`TrackLikers::iter_with_timestamps`](crate::TrackLikers::iter_with_timestamps).
+#[derive(Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct TrackLiker {
+    /// Time of the like, as an unparsed string, if provided by the API.
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// The user who liked the track.
+    pub user: User,
+}
+
+impl TrackLiker {
+    /// Constructs a like by the given user.
+    pub fn new_for_test(user: User) -> TrackLiker {
+        TrackLiker {
+            created_at: None,
+            user,
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+/// The encodings a track can be streamed in.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct Media {
+    #[serde(default)]
+    pub transcodings: Vec<Transcoding>,
+}
+
+/// An encoding a track can be streamed in.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct Transcoding {
+    /// API resource URL, which resolves to the url of the stream.
+    pub url: String,
+    /// Codec and bitrate, e.g. `mp3_0_0` or `aac_256k`.
+    pub preset: String,
+    /// If only a preview of the track is available in this encoding.
+    #[serde(default)]
+    pub snipped: bool,
+    /// Quality tier.
+    pub quality: Quality,
+    /// Streaming protocol and container.
+    pub format: TranscodingFormat,
+}
+
+/// Url of a stream, as the API url of a [`Transcoding`] resolves to.
+#[derive(Deserialize, Debug)]
+pub(crate) struct StreamLocation {
+    pub(crate) url: String,
+}
+
+/// Streaming protocol and container of a [`Transcoding`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TranscodingFormat {
+    /// Protocol, e.g. `progressive` or `hls`.
+    pub protocol: String,
+    /// MIME type, e.g. `audio/mpeg`.
+    pub mime_type: String,
+}
+
+/// Quality tier of a stream.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Quality {
+    /// Standard quality, available to everyone.
+    #[serde(rename = "sq")]
+    Standard,
+    /// High quality, available with a Go+ subscription.
+    #[serde(rename = "hq")]
+    High,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Streaming subscription of the authenticated user, see
+/// [`Client::subscription`](crate::Client::subscription).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subscription {
+    /// No subscription.
+    Free,
+    /// SoundCloud Go, which removes ads.
+    Go,
+    /// SoundCloud Go+, which also unlocks high quality streams.
+    GoPlus,
+}
+
+impl Subscription {
+    /// Returns the subscription with the product id of the API, e.g. `consumer-high-tier`.
+    pub(crate) fn from_product_id(id: &str) -> Self {
+        match id {
+            "consumer-high-tier" => Subscription::GoPlus,
+            "consumer-mid-tier" => Subscription::Go,
+            _ => Subscription::Free,
+        }
+    }
+
+    /// Returns whether high quality streams can be played with this subscription.
+    pub fn allows(&self, quality: Quality) -> bool {
+        match quality {
+            Quality::High => *self == Subscription::GoPlus,
+            _ => true,
+        }
+    }
+}
+
+/// Consumer subscription as included in the authenticated user.
+#[derive(Deserialize, Debug)]
+pub(crate) struct MySubscription {
+    #[serde(default)]
+    pub(crate) consumer_subscription: Option<SubscriptionProduct>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct SubscriptionProduct {
+    pub(crate) product: ProductId,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ProductId {
+    pub(crate) id: String,
+}
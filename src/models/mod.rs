@@ -1,13 +1,50 @@
+//! The resources of the API.
+//!
+//! The structs are `#[non_exhaustive]`, so their `new_for_test` constructors are the only way to
+//! build them outside of this crate, e.g. in the tests of applications.
+
 pub use self::app::*;
+pub use self::chart::*;
 pub use self::comment::*;
+pub use self::download_info::*;
+pub use self::follow::*;
+pub use self::genre::*;
+pub use self::kind::*;
+pub use self::like::*;
+pub use self::media::*;
+pub use self::oembed::*;
 pub use self::playlist::*;
+pub use self::quota::*;
+pub use self::repost::*;
+pub use self::search_result::*;
+pub use self::secret_token::*;
+pub use self::system_playlist::*;
 pub use self::track::*;
+pub use self::urn::*;
 pub use self::user::*;
+pub use self::user_snapshot::*;
+pub use self::waveform::*;
 pub use self::web_profile::*;
 
 mod app;
+mod chart;
 mod comment;
+mod download_info;
+mod follow;
+mod genre;
+mod kind;
+mod like;
+mod media;
+mod oembed;
 mod playlist;
+mod quota;
+mod repost;
+mod search_result;
+mod secret_token;
+mod system_playlist;
 mod track;
+mod urn;
 mod user;
+mod user_snapshot;
+mod waveform;
 mod web_profile;
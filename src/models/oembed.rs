@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// Size of an embedded player, either in pixels or relative to its container (e.g. `100%`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum EmbedSize {
+    Pixels(u64),
+    Relative(String),
+}
+
+/// oEmbed payload of a SoundCloud resource.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct OEmbed {
+    /// oEmbed version.
+    pub version: f64,
+    /// Resource type, usually `rich`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Name of the provider.
+    pub provider_name: String,
+    /// URL to the provider.
+    pub provider_url: String,
+    /// Title of the resource.
+    pub title: Option<String>,
+    /// Description of the resource.
+    pub description: Option<String>,
+    /// Name of the resource owner.
+    pub author_name: Option<String>,
+    /// URL to the resource owner.
+    pub author_url: Option<String>,
+    /// URL to a thumbnail image.
+    pub thumbnail_url: Option<String>,
+    /// Width of the thumbnail image.
+    pub thumbnail_width: Option<u64>,
+    /// Height of the thumbnail image.
+    pub thumbnail_height: Option<u64>,
+    /// HTML snippet of the embedded player.
+    pub html: String,
+    /// Width of the embedded player.
+    pub width: EmbedSize,
+    /// Height of the embedded player.
+    pub height: EmbedSize,
+}
+
+impl OEmbed {
+    /// Constructs an oEmbed payload with the given html and placeholder values for all other fields.
+    pub fn new_for_test(html: &str) -> OEmbed {
+        OEmbed {
+            version: 1.0,
+            kind: "rich".to_string(),
+            provider_name: "SoundCloud".to_string(),
+            provider_url: "https://soundcloud.com".to_string(),
+            title: None,
+            description: None,
+            author_name: None,
+            author_url: None,
+            thumbnail_url: None,
+            thumbnail_width: None,
+            thumbnail_height: None,
+            html: html.to_string(),
+            width: EmbedSize::Relative("100%".to_string()),
+            height: EmbedSize::Pixels(400),
+        }
+    }
+}
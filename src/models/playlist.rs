@@ -1,28 +1,26 @@
-use crate::models::{Track, User};
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use crate::apis::SingleUserRequestBuilder;
+use crate::client::Client;
+use crate::models::{Kind, Track, User};
 use serde::Deserialize;
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum PlaylistType {
     Single,
     Album,
     Ep,
     Compilation,
+    #[default]
     #[serde(other)]
     Playlist,
 }
 
-impl Default for PlaylistType {
-    fn default() -> Self {
-        PlaylistType::Playlist
-    }
-}
-
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
-pub enum PlaylistKind {
-    #[serde(rename = "playlist")]
-    Playlist,
-}
+#[deprecated(note = "use `Kind` instead")]
+pub type PlaylistKind = Kind;
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 pub enum PlaylistSharing {
@@ -33,6 +31,7 @@ pub enum PlaylistSharing {
 }
 
 #[derive(Debug, Clone, Deserialize)]
+#[non_exhaustive]
 pub struct Playlist {
     pub duration: u64,
     pub release_day: Option<i32>,
@@ -44,7 +43,7 @@ pub struct Playlist {
     pub uri: String,
     pub track_count: u64,
     pub user_id: u64,
-    pub kind: PlaylistKind,
+    pub kind: Kind,
     pub title: String,
     pub id: u64,
     #[serde(default)]
@@ -52,3 +51,152 @@ pub struct Playlist {
     pub user: User,
     pub artwork_url: Option<String>,
 }
+
+impl Playlist {
+    /// Constructs a playlist with the given id and placeholder values for all other fields.
+    pub fn new_for_test(id: u64) -> Playlist {
+        Playlist {
+            duration: 0,
+            release_day: None,
+            permalink_url: String::new(),
+            permalink: String::new(),
+            playlist_type: None,
+            purchase_url: None,
+            description: None,
+            uri: format!("https://api.soundcloud.com/playlists/{}", id),
+            track_count: 0,
+            user_id: 0,
+            kind: Kind::Playlist,
+            title: String::new(),
+            id,
+            tracks: None,
+            user: User::new_for_test(0),
+            artwork_url: None,
+        }
+    }
+
+    /// Returns the total duration of the tracks.
+    pub fn length(&self) -> Duration {
+        Duration::from_millis(self.duration)
+    }
+
+    /// Returns the ids of the tracks, in playlist order.
+    pub fn track_ids(&self) -> Vec<u64> {
+        self.tracks.iter().flatten().map(|track| track.id).collect()
+    }
+
+    /// Returns a request builder for the user who owns this playlist.
+    pub fn owner<'a>(&self, client: &'a Client) -> SingleUserRequestBuilder<'a> {
+        client.user(self.user_id as usize)
+    }
+
+    /// Returns the changes of the tracks from `self` to `other`.
+    ///
+    /// Only considers the tracks included in both playlists.
+    pub fn diff(&self, other: &Playlist) -> PlaylistDiff {
+        let old = self.track_ids();
+        let new = other.track_ids();
+        let old_set: HashSet<u64> = old.iter().copied().collect();
+        let new_set: HashSet<u64> = new.iter().copied().collect();
+
+        let added = new
+            .iter()
+            .filter(|id| !old_set.contains(id))
+            .copied()
+            .collect();
+        let removed = old
+            .iter()
+            .filter(|id| !new_set.contains(id))
+            .copied()
+            .collect();
+
+        // The tracks kept in both versions which are in their old order stay, all others moved
+        let old_positions: HashMap<u64, usize> = old
+            .into_iter()
+            .filter(|id| new_set.contains(id))
+            .enumerate()
+            .map(|(position, id)| (id, position))
+            .collect();
+        let kept_new: Vec<u64> = new.into_iter().filter(|id| old_set.contains(id)).collect();
+        let positions: Vec<usize> = kept_new.iter().map(|id| old_positions[id]).collect();
+        let stayed: HashSet<usize> = longest_increasing_subsequence(&positions)
+            .into_iter()
+            .collect();
+        let moved = kept_new
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !stayed.contains(index))
+            .map(|(_, id)| id)
+            .collect();
+
+        PlaylistDiff {
+            added,
+            removed,
+            moved,
+        }
+    }
+}
+
+/// Changes of the tracks between two versions of a playlist.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlaylistDiff {
+    /// Ids of the added tracks, in playlist order.
+    pub added: Vec<u64>,
+    /// Ids of the removed tracks, in their previous order.
+    pub removed: Vec<u64>,
+    /// Ids of the fewest tracks which have to be moved to get from the old to the new order, in
+    /// playlist order.
+    pub moved: Vec<u64>,
+}
+
+impl PlaylistDiff {
+    /// Returns true if the tracks didn't change.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty()
+    }
+}
+
+impl PartialEq for Playlist {
+    fn eq(&self, other: &Playlist) -> bool {
+        other.id == self.id
+    }
+}
+
+impl Eq for Playlist {}
+
+impl Hash for Playlist {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// Returns the indices of a longest strictly increasing subsequence of `values`.
+fn longest_increasing_subsequence(values: &[usize]) -> Vec<usize> {
+    // Index of the last value of the lowest ending subsequence of each length
+    let mut tails: Vec<usize> = vec![];
+    let mut previous = vec![None; values.len()];
+
+    for (index, &value) in values.iter().enumerate() {
+        let length = tails.partition_point(|&tail| values[tail] < value);
+        if length > 0 {
+            previous[index] = Some(tails[length - 1]);
+        }
+        if length == tails.len() {
+            tails.push(index);
+        } else {
+            tails[length] = index;
+        }
+    }
+
+    let mut indices = vec![];
+    let mut current = tails.last().copied();
+    while let Some(index) = current {
+        indices.push(index);
+        current = previous[index];
+    }
+    indices.reverse();
+    indices
+}
+
+/// Set of unique playlists, compared by id.
+pub type PlaylistSet = HashSet<Playlist>;
@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Upload quota of the authenticated user, see [`Client::quota`](crate::Client::quota).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Quota {
+    /// Whether the user can upload without limit.
+    #[serde(default)]
+    pub unlimited_upload_quota: bool,
+    /// Length of the audio uploaded so far, in seconds.
+    #[serde(default)]
+    pub upload_seconds_used: u64,
+    /// Length of the audio which can still be uploaded, in seconds.
+    #[serde(default)]
+    pub upload_seconds_left: Option<u64>,
+}
+
+impl Quota {
+    /// Constructs a quota with the given number of seconds left.
+    pub fn new_for_test(upload_seconds_left: u64) -> Quota {
+        Quota {
+            unlimited_upload_quota: false,
+            upload_seconds_used: 0,
+            upload_seconds_left: Some(upload_seconds_left),
+        }
+    }
+
+    /// Returns the length of the audio which can still be uploaded, or `None` if unlimited or
+    /// unknown.
+    pub fn remaining(&self) -> Option<Duration> {
+        if self.unlimited_upload_quota {
+            return None;
+        }
+        self.upload_seconds_left.map(Duration::from_secs)
+    }
+
+    /// Returns whether audio of the given `length` can be uploaded.
+    ///
+    /// If the remaining quota is unknown, the upload is allowed and left to the API to reject.
+    pub fn allows(&self, length: Duration) -> bool {
+        match self.remaining() {
+            Some(remaining) => length <= remaining,
+            None => true,
+        }
+    }
+}
+
+/// Upload quota as included in the authenticated user.
+#[derive(Deserialize, Debug)]
+pub(crate) struct MyQuota {
+    #[serde(default)]
+    pub(crate) quota: Option<Quota>,
+}
@@ -0,0 +1,40 @@
+use serde::Deserialize;
+
+use crate::models::{Playlist, Track, User};
+
+/// Repost of a track or playlist, see [`UserReposts`](crate::UserReposts).
+#[derive(Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct Repost {
+    /// Time of the repost, as an unparsed string.
+    pub created_at: String,
+    /// Caption added by the reposting user.
+    #[serde(default)]
+    pub caption: Option<String>,
+    /// The reposting user.
+    #[serde(default)]
+    pub user: Option<User>,
+    /// The reposted track or playlist.
+    #[serde(flatten)]
+    pub item: RepostedItem,
+}
+
+/// Track or playlist of a [`Repost`].
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum RepostedItem {
+    Track(Box<Track>),
+    Playlist(Box<Playlist>),
+}
+
+impl Repost {
+    /// Constructs a repost of the given item without caption.
+    pub fn new_for_test(item: RepostedItem) -> Repost {
+        Repost {
+            created_at: String::new(),
+            caption: None,
+            user: None,
+            item,
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use serde::de::{self, Deserialize, Deserializer};
+
+use crate::models::{Playlist, Track, Urn, User};
+
+/// Result of a search across all kinds of resources, see [`Client::search`](crate::Client::search).
+#[derive(Debug, Clone)]
+pub enum SearchResult {
+    Track(Box<Track>),
+    User(Box<User>),
+    Playlist(Box<Playlist>),
+    /// A result of another kind, as raw JSON.
+    Other(serde_json::Value),
+}
+
+impl SearchResult {
+    /// Returns the URN of the result, if it's a track, user or playlist.
+    pub fn urn(&self) -> Option<Urn> {
+        match *self {
+            SearchResult::Track(ref track) => Some(Urn::track(track.id)),
+            SearchResult::User(ref user) => Some(Urn::user(user.id)),
+            SearchResult::Playlist(ref playlist) => Some(Urn::playlist(playlist.id)),
+            SearchResult::Other(_) => None,
+        }
+    }
+
+    /// Returns the URNs of the tracks included in the result, if it's a playlist.
+    pub fn track_urns(&self) -> Vec<Urn> {
+        match *self {
+            SearchResult::Playlist(ref playlist) => {
+                playlist.track_ids().into_iter().map(Urn::track).collect()
+            }
+            _ => vec![],
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SearchResult {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let kind = value.get("kind").and_then(|kind| kind.as_str());
+
+        let result = match kind {
+            Some("track") => serde_json::from_value(value).map(SearchResult::Track),
+            Some("user") => serde_json::from_value(value).map(SearchResult::User),
+            Some("playlist") => serde_json::from_value(value).map(SearchResult::Playlist),
+            _ => Ok(SearchResult::Other(value)),
+        };
+        result.map_err(de::Error::custom)
+    }
+}
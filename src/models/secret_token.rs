@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::Track;
+
+/// Secret token of a private track, which grants access to anyone who knows it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SecretToken {
+    /// The token, e.g. `s-AbCdE`.
+    pub token: String,
+    /// API resource URL of the token.
+    pub uri: Option<String>,
+}
+
+impl SecretToken {
+    /// Constructs a secret token with the given token.
+    pub fn new_for_test<S: Into<String>>(token: S) -> SecretToken {
+        SecretToken {
+            token: token.into(),
+            uri: None,
+        }
+    }
+
+    /// Returns the private share link of the `track` with this token.
+    pub fn share_url(&self, track: &Track) -> String {
+        format!(
+            "{}/{}",
+            track.permalink_url.trim_end_matches('/'),
+            self.token
+        )
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::error::Result;
+use crate::models::{HasKind, Kind, Track};
+use crate::page::Page;
+
+/// Playlist generated by SoundCloud for the authenticated user, like The Upload, Daily Drops or
+/// one of the mixes.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct SystemPlaylist {
+    /// URN, e.g. `soundcloud:system-playlists:the-upload:1234`.
+    pub urn: String,
+    /// Title.
+    pub title: String,
+    /// Shortened title, as shown on the discover page.
+    pub short_title: Option<String>,
+    /// Description.
+    pub description: Option<String>,
+    /// Shortened description, as shown on the discover page.
+    pub short_description: Option<String>,
+    /// URL to the SoundCloud.com page.
+    pub permalink_url: Option<String>,
+    /// URL to a JPEG image.
+    pub artwork_url: Option<String>,
+    /// Time of the last update, as an unparsed string.
+    pub last_updated: Option<String>,
+    /// The tracks, which are only stubs until the playlist has been
+    /// [hydrated](SystemPlaylist::hydrate).
+    #[serde(default)]
+    pub tracks: Vec<SystemPlaylistTrack>,
+}
+
+/// Track of a [`SystemPlaylist`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum SystemPlaylistTrack {
+    /// The complete track.
+    Full(Box<Track>),
+    /// Only the id of the track.
+    Stub { id: u64 },
+}
+
+impl SystemPlaylistTrack {
+    pub fn id(&self) -> u64 {
+        match *self {
+            SystemPlaylistTrack::Full(ref track) => track.id,
+            SystemPlaylistTrack::Stub { id } => id,
+        }
+    }
+}
+
+impl SystemPlaylist {
+    /// Constructs a system playlist with the given urn and placeholder values for all other
+    /// fields.
+    pub fn new_for_test<S: Into<String>>(urn: S) -> SystemPlaylist {
+        SystemPlaylist {
+            urn: urn.into(),
+            title: String::new(),
+            short_title: None,
+            short_description: None,
+            description: None,
+            permalink_url: None,
+            artwork_url: None,
+            last_updated: None,
+            tracks: vec![],
+        }
+    }
+
+    /// Returns the ids of the tracks.
+    pub fn track_ids(&self) -> Vec<u64> {
+        self.tracks.iter().map(SystemPlaylistTrack::id).collect()
+    }
+
+    /// Returns whether all tracks are complete.
+    pub fn is_hydrated(&self) -> bool {
+        self.tracks
+            .iter()
+            .all(|track| matches!(track, SystemPlaylistTrack::Full(_)))
+    }
+
+    /// Replaces all track stubs with the complete tracks.
+    pub async fn hydrate(&mut self, client: &Client) -> Result<()> {
+        let stubs: Vec<u64> = self
+            .tracks
+            .iter()
+            .filter_map(|track| match *track {
+                SystemPlaylistTrack::Stub { id } => Some(id),
+                SystemPlaylistTrack::Full(_) => None,
+            })
+            .collect();
+        let tracks: HashMap<u64, Track> = client
+            .fetch_many::<Track, _>(stubs)
+            .await?
+            .into_iter()
+            .map(|track| (track.id, track))
+            .collect();
+
+        for track in self.tracks.iter_mut() {
+            if let Some(full) = tracks.get(&track.id()) {
+                *track = SystemPlaylistTrack::Full(Box::new(full.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl HasKind for SystemPlaylist {
+    fn kind(&self) -> Kind {
+        Kind::SystemPlaylist
+    }
+}
+
+/// Module of the discover page.
+#[derive(Deserialize, Debug)]
+pub(crate) struct Selection {
+    pub(crate) items: Page<serde_json::Value>,
+}
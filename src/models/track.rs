@@ -1,8 +1,97 @@
-use crate::models::{App, User};
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use crate::apis::{SingleUserRequestBuilder, Station};
+use crate::client::Client;
+use crate::error::{Error, Result};
+use crate::models::{App, Media, Quality, Subscription, Transcoding, Urn, User};
 use serde::{Deserialize, Serialize};
 
+/// Availability policy of a track in the region of the request.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Policy {
+    /// The track can be played in full.
+    Allow,
+    /// The track can be played in full and is monetized.
+    Monetize,
+    /// Only a preview snippet of the track can be played.
+    Snip,
+    /// The track is blocked in this region.
+    Block,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Length of the preview snippet streamed for tracks with the [`Policy::Snip`] policy.
+pub const PREVIEW_LENGTH: Duration = Duration::from_secs(30);
+
+/// How much of a track is streamed, see [`Client::stream_with_playback`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Playback {
+    /// The full track.
+    Full,
+    /// Only a preview snippet of [`PREVIEW_LENGTH`].
+    PreviewOnly,
+}
+
+/// Sharing status of a track.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Sharing {
+    Public,
+    Private,
+}
+
+impl Sharing {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Sharing::Public => "public",
+            Sharing::Private => "private",
+        }
+    }
+}
+
+/// Processing state of an uploaded track.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackState {
+    /// The uploaded file is still being transcoded.
+    Processing,
+    /// The track has been transcoded and can be played.
+    Finished,
+    /// The uploaded file couldn't be transcoded.
+    Failed,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Who is allowed to embed the player of a track.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddableBy {
+    /// Anyone.
+    All,
+    /// Only the owner of the track.
+    Me,
+    /// No one.
+    None,
+}
+
+impl EmbeddableBy {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            EmbeddableBy::All => "all",
+            EmbeddableBy::Me => "me",
+            EmbeddableBy::None => "none",
+        }
+    }
+}
+
 /// Uploaded track.
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
 pub struct Track {
     /// Integer ID.
     pub id: u64,
@@ -17,15 +106,25 @@ pub struct Track {
     /// API resource URL.
     pub uri: String,
     /// Sharing status.
-    pub sharing: String,
+    pub sharing: Sharing,
+    /// Processing state of the uploaded file.
+    #[serde(default)]
+    pub state: Option<TrackState>,
+    /// Who is allowed to embed the player.
+    #[serde(default)]
+    pub embeddable_by: Option<EmbeddableBy>,
     /// External purchase link.
     pub purchase_url: Option<String>,
     /// URL to a JPEG image.
     pub artwork_url: Option<String>,
     /// HTML description.
     pub description: Option<String>,
-    /// Duration in milliseconds.
+    /// Duration in milliseconds, see [`Track::length`].
     pub duration: u64,
+    /// Duration of the full track in milliseconds, if only a preview of it can be played, see
+    /// [`Track::full_length`].
+    #[serde(default)]
+    pub full_duration: Option<u64>,
     /// Genre.
     pub genre: Option<String>,
     /// List of tags.
@@ -40,6 +139,14 @@ pub struct Track {
     pub release_month: Option<u64>,
     /// Year of the release.
     pub release_year: Option<u64>,
+    /// Release date, as `YYYY-MM-DD`.
+    #[serde(default)]
+    pub release_date: Option<String>,
+    /// Time at which the track is scheduled to be made public, as an unparsed string.
+    ///
+    /// Only returned to the owner of the track.
+    #[serde(default)]
+    pub publish_at: Option<String>,
     /// If the track is available for stream via the API.
     pub streamable: bool,
     /// If the track is available for download.
@@ -70,6 +177,12 @@ pub struct Track {
     pub playback_count: Option<u64>,
     /// Number of times favorited.
     pub favoritings_count: Option<u64>,
+    /// Number of likes.
+    #[serde(default)]
+    pub likes_count: Option<u64>,
+    /// Number of reposts.
+    #[serde(default)]
+    pub reposts_count: Option<u64>,
     /// Application the track was uploaded with.
     pub created_with: Option<App>,
     /// Binary data of the audio file. Only for uploading.
@@ -78,6 +191,196 @@ pub struct Track {
     pub artwork_data: Option<Vec<u8>>,
     /// User favorite.
     pub user_favorite: Option<bool>,
+    /// Availability policy in the region of the request.
+    #[serde(default)]
+    pub policy: Option<Policy>,
+    /// URN of the station seeded by this track.
+    #[serde(default)]
+    pub station_urn: Option<String>,
+    /// The encodings the track can be streamed in, see [`Track::available_qualities`].
+    #[serde(default)]
+    pub media: Option<Media>,
+}
+
+impl Track {
+    /// Constructs a track with the given id and placeholder values for all other fields.
+    pub fn new_for_test(id: u64) -> Track {
+        Track {
+            id,
+            created_at: String::new(),
+            user: User::new_for_test(0),
+            title: String::new(),
+            permalink_url: String::new(),
+            uri: format!("https://api.soundcloud.com/tracks/{}", id),
+            sharing: Sharing::Public,
+            state: None,
+            embeddable_by: None,
+            purchase_url: None,
+            artwork_url: None,
+            description: None,
+            duration: 0,
+            full_duration: None,
+            genre: None,
+            tags: None,
+            label_name: None,
+            release: None,
+            release_day: None,
+            release_month: None,
+            release_date: None,
+            publish_at: None,
+            release_year: None,
+            streamable: true,
+            downloadable: false,
+            purchase_title: None,
+            license: String::new(),
+            waveform_url: String::new(),
+            download_url: None,
+            stream_url: None,
+            bpm: None,
+            commentable: true,
+            isrc: None,
+            key_signature: None,
+            comment_count: None,
+            download_count: None,
+            playback_count: None,
+            favoritings_count: None,
+            likes_count: None,
+            reposts_count: None,
+            created_with: None,
+            asset_data: None,
+            artwork_data: None,
+            user_favorite: None,
+            policy: None,
+            station_urn: None,
+            media: None,
+        }
+    }
+
+    /// Returns the playable duration.
+    pub fn length(&self) -> Duration {
+        Duration::from_millis(self.duration)
+    }
+
+    /// Returns the duration of the full track, which is longer than [`Track::length`] if only a
+    /// preview can be played.
+    pub fn full_length(&self) -> Duration {
+        Duration::from_millis(self.full_duration.unwrap_or(self.duration))
+    }
+
+    /// Returns the playable duration formatted as `m:ss`, or as `h:mm:ss` for long tracks.
+    pub fn duration_hms(&self) -> String {
+        crate::fmt::format_duration(self.length())
+    }
+
+    /// Returns how much of the track is streamed.
+    pub fn playback(&self) -> Playback {
+        let snipped = self.transcodings().any(|transcoding| transcoding.snipped);
+
+        if self.policy == Some(Policy::Snip) || snipped {
+            Playback::PreviewOnly
+        } else {
+            Playback::Full
+        }
+    }
+
+    /// Returns the duration of the audio actually streamed, which is only the
+    /// [preview](PREVIEW_LENGTH) for tracks with the [`Policy::Snip`] policy, whatever `duration`
+    /// says.
+    pub fn effective_stream_duration(&self) -> Duration {
+        match self.playback() {
+            Playback::PreviewOnly => self.length().min(PREVIEW_LENGTH),
+            Playback::Full => self.length(),
+        }
+    }
+
+    /// Returns the qualities the track can be streamed in with the `subscription`, from the
+    /// lowest to the highest.
+    pub fn available_qualities(&self, subscription: Subscription) -> Vec<Quality> {
+        let mut qualities: Vec<Quality> = self
+            .transcodings()
+            .map(|transcoding| transcoding.quality)
+            .filter(|quality| *quality != Quality::Unknown && subscription.allows(*quality))
+            .collect();
+        qualities.sort();
+        qualities.dedup();
+        qualities
+    }
+
+    /// Returns the encoding of the `quality` the track can be streamed in with the
+    /// `subscription`, preferring progressive streams.
+    ///
+    /// Returns [`Error::SubscriptionRequired`] if the track is available in the quality, but not
+    /// with the subscription.
+    pub fn transcoding(
+        &self,
+        quality: Quality,
+        subscription: Subscription,
+    ) -> Result<&Transcoding> {
+        let mut transcodings: Vec<&Transcoding> = self
+            .transcodings()
+            .filter(|transcoding| transcoding.quality == quality)
+            .collect();
+        transcodings.sort_by_key(|transcoding| transcoding.format.protocol != "progressive");
+
+        match transcodings.first() {
+            Some(_) if !subscription.allows(quality) => Err(Error::SubscriptionRequired(quality)),
+            Some(transcoding) => Ok(transcoding),
+            None => Err(Error::TrackNotStreamable),
+        }
+    }
+
+    fn transcodings(&self) -> impl Iterator<Item = &Transcoding> {
+        self.media
+            .iter()
+            .flat_map(|media| media.transcodings.iter())
+    }
+
+    /// Returns the station of tracks similar to this track.
+    pub fn station(&self, client: &Client) -> Station {
+        let urn = match self.station_urn {
+            Some(ref urn) => urn.clone(),
+            None => Urn::track_station(self.id).to_string(),
+        };
+        Station::new(client.clone(), urn)
+    }
+
+    /// Returns a request builder for the user who uploaded this track.
+    pub fn user_builder<'a>(&self, client: &'a Client) -> SingleUserRequestBuilder<'a> {
+        client.user(self.user.id)
+    }
+}
+
+/// Engagement counters of a track.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TrackEngagement {
+    /// Number of playbacks.
+    #[serde(default)]
+    pub playback_count: Option<u64>,
+    /// Number of likes.
+    #[serde(default)]
+    pub likes_count: Option<u64>,
+    /// Number of reposts.
+    #[serde(default)]
+    pub reposts_count: Option<u64>,
+    /// Number of comments.
+    #[serde(default)]
+    pub comment_count: Option<u64>,
+    /// Number of downloads.
+    #[serde(default)]
+    pub download_count: Option<u64>,
+}
+
+impl From<&Track> for TrackEngagement {
+    fn from(track: &Track) -> Self {
+        TrackEngagement {
+            playback_count: track.playback_count,
+            likes_count: track.likes_count,
+            reposts_count: track.reposts_count,
+            comment_count: track.comment_count,
+            download_count: track.download_count,
+        }
+    }
 }
 
 impl PartialEq for Track {
@@ -85,3 +388,14 @@ impl PartialEq for Track {
         other.id == self.id
     }
 }
+
+impl Eq for Track {}
+
+impl Hash for Track {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// Set of unique tracks, compared by id.
+pub type TrackSet = HashSet<Track>;
@@ -0,0 +1,109 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::models::Genre;
+
+/// A SoundCloud URN, like `soundcloud:genres:hiphoprap` or
+/// `soundcloud:system-playlists:track-stations:1234`.
+///
+/// URNs can be passed wherever the API expects one as a string, e.g. to
+/// [`Client::station`](crate::Client::station).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Urn(String);
+
+impl Urn {
+    /// The URN of a genre, from its identifier, e.g. `hiphoprap`.
+    ///
+    /// Returns [`Error::InvalidGenre`] if the identifier contains anything but lowercase letters,
+    /// digits and hyphens.
+    pub fn genre(id: &str) -> Result<Urn> {
+        let valid = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+
+        if valid {
+            Ok(Urn(format!("soundcloud:genres:{}", id)))
+        } else {
+            Err(Error::InvalidGenre(id.to_owned()))
+        }
+    }
+
+    /// The URN of the track with the given id.
+    pub fn track(track_id: u64) -> Urn {
+        Urn(format!("soundcloud:tracks:{}", track_id))
+    }
+
+    /// The URN of the user with the given id.
+    pub fn user(user_id: usize) -> Urn {
+        Urn(format!("soundcloud:users:{}", user_id))
+    }
+
+    /// The URN of the playlist with the given id.
+    pub fn playlist(playlist_id: u64) -> Urn {
+        Urn(format!("soundcloud:playlists:{}", playlist_id))
+    }
+
+    /// The URN of the station of tracks similar to the track with the given id.
+    pub fn track_station(track_id: u64) -> Urn {
+        Urn(format!(
+            "soundcloud:system-playlists:track-stations:{}",
+            track_id
+        ))
+    }
+
+    /// The URN of the station of tracks by and similar to the user with the given id.
+    pub fn artist_station(user_id: usize) -> Urn {
+        Urn(format!(
+            "soundcloud:system-playlists:artist-stations:{}",
+            user_id
+        ))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for Urn {
+    type Err = Error;
+
+    /// Parses a URN, which has to start with `soundcloud:` followed by at least two non-empty
+    /// segments separated by colons.
+    fn from_str(s: &str) -> Result<Urn> {
+        let segments: Vec<&str> = s.split(':').collect();
+        let valid = segments.len() >= 3
+            && segments[0] == "soundcloud"
+            && segments.iter().all(|segment| {
+                !segment.is_empty()
+                    && segment
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            });
+
+        if valid {
+            Ok(Urn(s.to_owned()))
+        } else {
+            Err(Error::InvalidUrn(s.to_owned()))
+        }
+    }
+}
+
+impl From<Genre> for Urn {
+    fn from(genre: Genre) -> Urn {
+        Urn(genre.urn())
+    }
+}
+
+impl fmt::Display for Urn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for Urn {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
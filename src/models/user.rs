@@ -1,7 +1,15 @@
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
 use serde::{Deserialize, Serialize};
 
+use crate::apis::Station;
+use crate::client::Client;
+use crate::models::Urn;
+
 /// Registered user.
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
 pub struct User {
     /// Integer ID.
     pub id: usize,
@@ -46,5 +54,63 @@ pub struct User {
     pub followings_count: Option<usize>,
     /// Number of favorited public tracks.
     pub public_favorites_count: Option<usize>,
+    /// URN of the station seeded by this user's tracks.
+    #[serde(default)]
+    pub station_urn: Option<String>,
     // pub avatar_data …
 }
+
+impl User {
+    /// Constructs a user with the given id and placeholder values for all other fields.
+    pub fn new_for_test(id: usize) -> User {
+        User {
+            id,
+            permalink: String::new(),
+            username: String::new(),
+            uri: format!("https://api.soundcloud.com/users/{}", id),
+            permalink_url: String::new(),
+            avatar_url: String::new(),
+            country: None,
+            full_name: None,
+            city: None,
+            description: None,
+            discogs_name: None,
+            myspace_name: None,
+            website: None,
+            website_title: None,
+            online: None,
+            track_count: None,
+            playlist_count: None,
+            followers_count: None,
+            followings_count: None,
+            public_favorites_count: None,
+            station_urn: None,
+        }
+    }
+
+    /// Returns the station of tracks by and similar to this user.
+    pub fn station(&self, client: &Client) -> Station {
+        let urn = match self.station_urn {
+            Some(ref urn) => urn.clone(),
+            None => Urn::artist_station(self.id).to_string(),
+        };
+        Station::new(client.clone(), urn)
+    }
+}
+
+impl PartialEq for User {
+    fn eq(&self, other: &User) -> bool {
+        other.id == self.id
+    }
+}
+
+impl Eq for User {}
+
+impl Hash for User {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// Set of unique users, compared by id.
+pub type UserSet = HashSet<User>;
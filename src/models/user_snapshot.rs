@@ -0,0 +1,28 @@
+use crate::models::{Playlist, Track, User, WebProfile};
+
+/// A user's profile along with the first of their tracks and playlists, as needed to render a
+/// profile page.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct UserSnapshot {
+    /// The user, including their follower and following counts.
+    pub user: User,
+    /// The most recent tracks of the user.
+    pub tracks: Vec<Track>,
+    /// The most recent playlists of the user.
+    pub playlists: Vec<Playlist>,
+    /// The links on the user's profile.
+    pub web_profiles: Vec<WebProfile>,
+}
+
+impl UserSnapshot {
+    /// Number of followers.
+    pub fn followers_count(&self) -> Option<usize> {
+        self.user.followers_count
+    }
+
+    /// Number of followed users.
+    pub fn followings_count(&self) -> Option<usize> {
+        self.user.followings_count
+    }
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// Amplitudes of the audio of a track, as drawn by the SoundCloud player.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Waveform {
+    /// Number of samples.
+    pub width: u32,
+    /// Largest possible amplitude of a sample.
+    pub height: u32,
+    /// Amplitude of each sample, from 0 to `height`.
+    pub samples: Vec<u32>,
+}
+
+impl Waveform {
+    /// Returns the amplitudes scaled to the range from 0 to 1.
+    pub fn normalized(&self) -> Vec<f32> {
+        let height = self.height.max(1) as f32;
+        self.samples
+            .iter()
+            .map(|&sample| sample as f32 / height)
+            .collect()
+    }
+}
@@ -1,18 +1,152 @@
-use serde::Deserialize;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
-pub enum WebProfileKind {
-    #[serde(rename = "web-profile")]
-    WebProfile,
+use serde::{Deserialize, Serialize};
+
+use crate::models::Kind;
+
+#[deprecated(note = "use `Kind` instead")]
+pub type WebProfileKind = Kind;
+
+/// Service a web profile links to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum Service {
+    Instagram,
+    /// Twitter, now X.
+    Twitter,
+    Bandcamp,
+    Spotify,
+    YouTube,
+    /// A personal website.
+    Personal,
+    /// Any other service, by the name the API uses for it.
+    ///
+    /// Names which differ from the known services only in case, or newer names like `x`, are
+    /// kept here as received, so that they are written back unchanged.
+    Other(String),
+}
+
+impl Service {
+    /// Returns the name the API uses for the service.
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Service::Instagram => "instagram",
+            Service::Twitter => "twitter",
+            Service::Bandcamp => "bandcamp",
+            Service::Spotify => "spotify",
+            Service::YouTube => "youtube",
+            Service::Personal => "personal",
+            Service::Other(ref name) => name,
+        }
+    }
+
+    /// Returns the url of the profile of `username` on the service, if it can be derived.
+    fn profile_url(&self, username: &str) -> Option<String> {
+        match *self {
+            Service::Instagram => Some(format!("https://www.instagram.com/{}", username)),
+            Service::Twitter => Some(format!("https://x.com/{}", username)),
+            Service::Bandcamp => Some(format!("https://{}.bandcamp.com", username)),
+            Service::YouTube => Some(format!("https://www.youtube.com/@{}", username)),
+            Service::Other(ref name) if name.eq_ignore_ascii_case("x") => {
+                Some(format!("https://x.com/{}", username))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for Service {
+    fn from(name: String) -> Service {
+        match name.as_str() {
+            "instagram" => Service::Instagram,
+            "twitter" => Service::Twitter,
+            "bandcamp" => Service::Bandcamp,
+            "spotify" => Service::Spotify,
+            "youtube" => Service::YouTube,
+            "personal" => Service::Personal,
+            _ => Service::Other(name),
+        }
+    }
+}
+
+impl From<Service> for String {
+    fn from(service: Service) -> String {
+        service.as_str().to_owned()
+    }
+}
+
+impl fmt::Display for Service {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
+#[non_exhaustive]
 pub struct WebProfile {
-    pub kind: WebProfileKind,
+    pub kind: Kind,
     pub id: usize,
-    pub service: String,
+    pub service: Service,
     pub title: String,
     pub url: String,
     pub username: Option<String>,
     pub created_at: String,
 }
+
+impl WebProfile {
+    /// Constructs a web profile with the given id and placeholder values for all other fields.
+    pub fn new_for_test(id: usize) -> WebProfile {
+        WebProfile {
+            kind: Kind::WebProfile,
+            id,
+            service: Service::Personal,
+            title: String::new(),
+            url: String::new(),
+            username: None,
+            created_at: String::new(),
+        }
+    }
+
+    /// Returns the link as an absolute url.
+    ///
+    /// Links saved without a scheme, like `example.com`, get `https://`, and links without a url
+    /// are derived from the username on services like Instagram.
+    pub fn normalized_url(&self) -> String {
+        let url = self.url.trim();
+        if url.is_empty() {
+            let profile_url = self
+                .username
+                .as_deref()
+                .and_then(|username| self.service.profile_url(username));
+            return profile_url.unwrap_or_default();
+        }
+
+        let scheme = url.find("://").map(|end| &url[..end]);
+        let has_scheme = scheme.is_some_and(|scheme| {
+            !scheme.is_empty()
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        });
+        if has_scheme {
+            url.to_owned()
+        } else {
+            format!("https://{}", url.trim_start_matches("//"))
+        }
+    }
+}
+
+impl PartialEq for WebProfile {
+    fn eq(&self, other: &WebProfile) -> bool {
+        other.id == self.id
+    }
+}
+
+impl Eq for WebProfile {}
+
+impl Hash for WebProfile {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
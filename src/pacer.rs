@@ -0,0 +1,37 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Spaces out operations by a minimum interval, shared between all clones of a client.
+#[derive(Debug)]
+pub(crate) struct Pacer {
+    interval: Duration,
+    /// When the latest operation was scheduled.
+    last: Mutex<Option<Instant>>,
+}
+
+impl Pacer {
+    pub(crate) fn new(interval: Duration) -> Self {
+        Pacer {
+            interval,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Waits until `interval` has passed since the previous operation.
+    ///
+    /// Concurrent callers are scheduled one after another.
+    pub(crate) async fn wait(&self) {
+        let scheduled = {
+            let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let scheduled = match *last {
+                Some(last) => (last + self.interval).max(now),
+                None => now,
+            };
+            *last = Some(scheduled);
+            scheduled
+        };
+
+        tokio::time::sleep_until(scheduled.into()).await;
+    }
+}
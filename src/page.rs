@@ -7,6 +7,9 @@ use crate::error::Result;
 
 const DEFAULT_PAGE_SIZE: u32 = 15;
 
+/// Largest page size accepted by most endpoints, larger sizes are silently capped.
+pub(crate) const MAX_PAGE_SIZE: u32 = 200;
+
 pub struct PageOptions {
     params: HashMap<&'static str, String>,
 }
@@ -34,6 +37,47 @@ impl PageOptions {
     }
 }
 
+impl PageOptions {
+    /// The requested number of items per page, if set.
+    pub fn page_size(&self) -> Option<u32> {
+        self.params.get("page_size")?.parse().ok()
+    }
+
+    /// Returns the options with the page size reduced to at most `max`.
+    pub(crate) fn clamp_page_size(&self, max: u32) -> PageOptions {
+        let mut params = self.params.clone();
+        if self.page_size().is_some_and(|page_size| page_size > max) {
+            params.insert("page_size", max.to_string());
+        }
+        PageOptions { params }
+    }
+
+    /// Appends the options to the query of `url`, unless they are present already, e.g. in a
+    /// `next_href` returned by the API.
+    pub(crate) fn apply(&self, url: &str) -> String {
+        let query = url.split_once('?').map_or("", |(_, query)| query);
+        let present: Vec<String> = form_urlencoded::parse(query.as_bytes())
+            .map(|(key, _)| key.into_owned())
+            .collect();
+        let params: Vec<(&str, &String)> = self
+            .params
+            .iter()
+            .filter(|(key, _)| !present.iter().any(|present| present == *key))
+            .map(|(key, value)| (*key, value))
+            .collect();
+
+        if params.is_empty() {
+            return url.to_owned();
+        }
+
+        let params = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(params)
+            .finish();
+        let separator = if url.contains('?') { '&' } else { '?' };
+        format!("{}{}{}", url, separator, params)
+    }
+}
+
 impl Default for PageOptions {
     fn default() -> Self {
         Self::builder().page_size(DEFAULT_PAGE_SIZE).build()
@@ -70,14 +114,18 @@ pub struct Page<T> {
     pub collection: Vec<T>,
     /// The url to the next page of results
     pub next_href: Option<String>,
+    /// The total number of results in the whole collection, if provided by the endpoint
+    #[serde(default)]
+    pub total_results: Option<u64>,
 }
 
 impl<T> Page<T> {
     pub fn next_query(&self) -> Result<Option<HashMap<String, String>>> {
-        if self.next_href.is_none() {
-            return Ok(None);
-        }
-        let url = Url::parse(self.next_href.as_ref().unwrap())?;
+        let next_href = match self.next_href {
+            Some(ref next_href) => next_href,
+            None => return Ok(None),
+        };
+        let url = Url::parse(next_href)?;
         let next_query: HashMap<String, String> = url.query_pairs().into_owned().collect();
 
         match next_query.is_empty() {
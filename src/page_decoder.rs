@@ -0,0 +1,160 @@
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeOwned, IgnoredAny};
+
+use crate::error::Result;
+use crate::page::Page;
+
+/// Where the decoder is within the page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Position {
+    /// Before the `collection` array.
+    Before,
+    /// Within the `collection` array.
+    Items,
+    /// After the `collection` array.
+    After,
+}
+
+/// Incremental decoder of a [`Page`], which yields the items of the `collection` as soon as
+/// their JSON is complete instead of waiting for the whole page.
+///
+/// The remaining fields of the page are kept, with an empty collection, and decoded by
+/// [`PageDecoder::finish`].
+pub(crate) struct PageDecoder<T> {
+    position: Position,
+    /// Nesting depth of the current byte, 1 within the page object.
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    /// The last string seen directly within the page object, i.e. the current key.
+    key: Vec<u8>,
+    /// JSON of the item being read.
+    item: Vec<u8>,
+    /// JSON of the page without its items.
+    rest: Vec<u8>,
+    model: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> PageDecoder<T> {
+    pub(crate) fn new() -> Self {
+        PageDecoder {
+            position: Position::Before,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            key: vec![],
+            item: vec![],
+            rest: vec![],
+            model: PhantomData,
+        }
+    }
+
+    /// Consumes the next `chunk` of the page, returning the items completed by it.
+    pub(crate) fn feed(&mut self, chunk: &[u8]) -> Result<Vec<T>> {
+        let mut items = vec![];
+
+        for &byte in chunk {
+            match self.position {
+                Position::Items => {
+                    if let Some(item) = self.feed_item(byte)? {
+                        items.push(item);
+                    }
+                }
+                Position::Before | Position::After => self.feed_rest(byte),
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Decodes the page without its items, once all chunks have been consumed.
+    pub(crate) fn finish(self) -> Result<Page<IgnoredAny>> {
+        Ok(serde_json::from_slice(&self.rest)?)
+    }
+
+    fn feed_rest(&mut self, byte: u8) {
+        self.rest.push(byte);
+
+        if self.in_string {
+            if self.depth == 1 {
+                self.key.push(byte);
+            }
+            if self.skip_string(byte) && self.depth == 1 {
+                self.key.pop();
+            }
+            return;
+        }
+
+        match byte {
+            b'"' => {
+                self.in_string = true;
+                if self.depth == 1 {
+                    self.key.clear();
+                }
+            }
+            b'{' | b'[' => {
+                self.depth += 1;
+                if byte == b'['
+                    && self.depth == 2
+                    && self.position == Position::Before
+                    && self.key == b"collection"
+                {
+                    self.position = Position::Items;
+                }
+            }
+            b'}' | b']' => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    fn feed_item(&mut self, byte: u8) -> Result<Option<T>> {
+        if self.in_string {
+            self.item.push(byte);
+            self.skip_string(byte);
+            return Ok(None);
+        }
+
+        match byte {
+            b',' if self.depth == 2 => return self.finish_item(),
+            b']' if self.depth == 2 => {
+                let item = self.finish_item()?;
+                self.depth = 1;
+                self.position = Position::After;
+                self.rest.push(byte);
+                return Ok(item);
+            }
+            b'"' => self.in_string = true,
+            b'{' | b'[' => self.depth += 1,
+            b'}' | b']' => self.depth -= 1,
+            _ if byte.is_ascii_whitespace() && self.item.is_empty() => return Ok(None),
+            _ => {}
+        }
+        self.item.push(byte);
+
+        Ok(None)
+    }
+
+    /// Tracks the end of a string, returning whether `byte` closed it.
+    fn skip_string(&mut self, byte: u8) -> bool {
+        if self.escaped {
+            self.escaped = false;
+        } else if byte == b'\\' {
+            self.escaped = true;
+        } else if byte == b'"' {
+            self.in_string = false;
+            return true;
+        }
+        false
+    }
+
+    fn finish_item(&mut self) -> Result<Option<T>> {
+        if self.item.is_empty() {
+            return Ok(None);
+        }
+        let item = serde_json::from_slice(&self.item)?;
+        self.item.clear();
+
+        Ok(Some(item))
+    }
+}
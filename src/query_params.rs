@@ -0,0 +1,78 @@
+use std::slice;
+
+use url::form_urlencoded;
+
+/// Query parameters of an API request, in insertion order.
+///
+/// Values are kept unencoded and only encoded when the parameters are serialized or appended
+/// to a url.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryParams {
+    pairs: Vec<(String, String)>,
+}
+
+impl QueryParams {
+    /// Creates an empty set of query parameters.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Appends a parameter.
+    pub fn push<V: ToString>(&mut self, key: &str, value: V) -> &mut Self {
+        self.pairs.push((key.to_owned(), value.to_string()));
+        self
+    }
+
+    /// Appends a parameter if `value` is set.
+    pub fn push_opt<V: ToString>(&mut self, key: &str, value: Option<V>) -> &mut Self {
+        if let Some(value) = value {
+            self.push(key, value);
+        }
+        self
+    }
+
+    /// Appends a comma separated list of values if `values` is set.
+    pub fn push_list<I>(&mut self, key: &str, values: Option<I>) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: ToString,
+    {
+        if let Some(values) = values {
+            let values: Vec<String> = values.into_iter().map(|v| v.to_string()).collect();
+            self.push(key, values.join(","));
+        }
+        self
+    }
+
+    /// Returns the value of the first parameter named `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, (String, String)> {
+        self.pairs.iter()
+    }
+
+    /// Serializes the parameters as an `application/x-www-form-urlencoded` query string.
+    pub fn encode(&self) -> String {
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&self.pairs)
+            .finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a QueryParams {
+    type Item = &'a (String, String);
+    type IntoIter = slice::Iter<'a, (String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
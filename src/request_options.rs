@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+/// Options applied to every request of a client, see [`Client::with_options`](crate::Client::with_options).
+#[derive(Clone, Debug, Default)]
+pub struct RequestOptions {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) hedge_after: Option<Duration>,
+}
+
+impl RequestOptions {
+    pub fn builder() -> RequestOptionsBuilder {
+        RequestOptionsBuilder::new()
+    }
+}
+
+/// a mutable request options builder
+pub struct RequestOptionsBuilder(RequestOptions);
+
+impl RequestOptionsBuilder {
+    fn new() -> Self {
+        RequestOptionsBuilder(RequestOptions::default())
+    }
+
+    /// Sets the deadline for each request, from sending it until the response body has been read.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.0.timeout = Some(timeout);
+        self
+    }
+
+    /// Enables hedging of single-resource lookups, like [`Client::fetch`](crate::Client::fetch):
+    /// if a lookup hasn't completed after `delay`, a second identical request is sent and
+    /// whichever succeeds first is used.
+    ///
+    /// This cuts tail latencies for interactive use at the cost of additional requests.
+    pub fn hedge_after(&mut self, delay: Duration) -> &mut Self {
+        self.0.hedge_after = Some(delay);
+        self
+    }
+
+    pub fn build(&self) -> RequestOptions {
+        self.0.clone()
+    }
+}
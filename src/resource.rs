@@ -0,0 +1,77 @@
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+
+use crate::endpoint::Endpoint;
+use crate::models::{App, HasKind, Kind, Playlist, Track, User};
+
+/// A resource which can be fetched by its id, see [`Client::fetch`](crate::Client::fetch).
+pub trait Resource: DeserializeOwned + HasKind + Send + 'static {
+    /// Type of the resource's id.
+    type Id: fmt::Display + Copy + Send;
+
+    /// The kind of the resource.
+    const KIND: Kind;
+
+    /// Returns the id of the resource.
+    fn id(&self) -> Self::Id;
+
+    /// Returns the API path of the resource with the given id.
+    fn path(id: Self::Id) -> String;
+}
+
+impl Resource for Track {
+    type Id = u64;
+
+    const KIND: Kind = Kind::Track;
+
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn path(id: u64) -> String {
+        Endpoint::new("/tracks").segment(id).to_string()
+    }
+}
+
+impl Resource for User {
+    type Id = usize;
+
+    const KIND: Kind = Kind::User;
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn path(id: usize) -> String {
+        Endpoint::new("/users").segment(id).to_string()
+    }
+}
+
+impl Resource for Playlist {
+    type Id = u64;
+
+    const KIND: Kind = Kind::Playlist;
+
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn path(id: u64) -> String {
+        Endpoint::new("/playlists").segment(id).to_string()
+    }
+}
+
+impl Resource for App {
+    type Id = usize;
+
+    const KIND: Kind = Kind::App;
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn path(id: usize) -> String {
+        Endpoint::new("/apps").segment(id).to_string()
+    }
+}
@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use url::Url;
+
+/// Metadata of an API response, returned alongside the typed result by the `*_with_meta`
+/// methods.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ResponseMeta {
+    /// HTTP status code.
+    pub status: StatusCode,
+    /// Response headers, e.g. for `ETag` or `Cache-Control` based caching.
+    pub headers: HeaderMap,
+    /// Time from sending the request until the response headers were received.
+    pub duration: Duration,
+    /// Final URL of the response.
+    pub url: Url,
+}
+
+impl ResponseMeta {
+    pub(crate) fn new(response: &reqwest::Response, duration: Duration) -> Self {
+        ResponseMeta {
+            status: response.status(),
+            headers: response.headers().clone(),
+            duration,
+            url: response.url().clone(),
+        }
+    }
+}
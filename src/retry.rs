@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+/// How failed page requests are retried, see
+/// [`StreamingApiExt::iter_resilient`](crate::StreamingApiExt::iter_resilient).
+///
+/// Each failed request is retried after a backoff, which starts at the initial backoff and
+/// doubles with every further attempt up to the maximum backoff.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub(crate) max_retries: u32,
+    pub(crate) initial_backoff: Duration,
+    pub(crate) max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn builder() -> RetryPolicyBuilder {
+        RetryPolicyBuilder::new()
+    }
+
+    /// Returns the backoff before the retry following `attempt` failed attempts.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+/// a mutable retry policy builder
+pub struct RetryPolicyBuilder(RetryPolicy);
+
+impl RetryPolicyBuilder {
+    fn new() -> Self {
+        RetryPolicyBuilder(RetryPolicy::default())
+    }
+
+    /// Sets how often a single request is retried before the error is returned, defaults to 5.
+    pub fn max_retries(&mut self, max_retries: u32) -> &mut Self {
+        self.0.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the backoff before the first retry, defaults to one second.
+    pub fn initial_backoff(&mut self, backoff: Duration) -> &mut Self {
+        self.0.initial_backoff = backoff;
+        self
+    }
+
+    /// Sets the maximum backoff between two retries, defaults to one minute.
+    pub fn max_backoff(&mut self, backoff: Duration) -> &mut Self {
+        self.0.max_backoff = backoff;
+        self
+    }
+
+    pub fn build(&self) -> RetryPolicy {
+        self.0.clone()
+    }
+}
@@ -0,0 +1,52 @@
+use std::task::{Context, Poll};
+
+use futures::future::BoxFuture;
+use tower_service::Service;
+
+use crate::client::Client;
+use crate::error::{Error, Result};
+
+impl Client {
+    /// Builds a request to the API endpoint at `path`, with the `client_id` parameter and the
+    /// authorization header set, to be sent through the [`Service`] implementation.
+    pub fn api_request(&self, method: reqwest::Method, path: &str) -> Result<reqwest::Request> {
+        let url = self.api_url(path)?;
+        Ok(self.request(method, url)?.build()?)
+    }
+}
+
+/// Sends API requests like [`Client::get`], so tower middleware like retries, rate limits or load
+/// shedding can be layered around them.
+///
+/// The circuit breaker and the client id rotation of the client apply, and responses with a
+/// non-success status are returned as errors.
+///
+/// # Examples
+///
+/// ```
+/// use soundcloud::Client;
+/// use tower_service::Service;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let mut client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+///   let request = client.api_request(reqwest::Method::GET, "/tracks/262681089").unwrap();
+///   let response = client.call(request).await;
+///
+///   assert!(response.is_ok());
+/// }
+/// ```
+impl Service<reqwest::Request> for Client {
+    type Response = reqwest::Response;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<reqwest::Response>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: reqwest::Request) -> Self::Future {
+        let client = self.clone();
+        Box::pin(async move { client.send_request(request).await })
+    }
+}
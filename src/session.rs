@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::client::Client;
+use crate::error::Result;
+use crate::media_urls::MediaUrlCache;
+use crate::models::User;
+
+/// Holds authenticated clients for several accounts, keyed by the id of their user.
+///
+/// The clients are clones of the base client with their own auth token and their own cache of
+/// signed media urls. They share its configuration, its HTTP connection pools, its client id
+/// rotation, its circuit breaker and the pacing of comments, so a failing API opens the circuit
+/// and posted comments are paced across all accounts.
+///
+/// # Examples
+///
+/// ```no_run
+/// use soundcloud::{Client, SessionManager};
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+///   let mut sessions = SessionManager::new(client);
+///   let artist = sessions.sign_in("token of the artist").await.unwrap();
+///   let label = sessions.sign_in("token of the label").await.unwrap();
+///
+///   let client = sessions.with_account(artist.id).unwrap();
+///   let playlists = client.my_playlists().await.unwrap();
+///   println!("{} has {} playlists", artist.username, playlists.len());
+///
+///   sessions.sign_out(label.id).await.unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SessionManager {
+    base: Client,
+    clients: BTreeMap<usize, Client>,
+}
+
+impl SessionManager {
+    /// Creates a manager without accounts, whose clients are derived from `base`.
+    pub fn new(base: Client) -> Self {
+        SessionManager {
+            base,
+            clients: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the account the `token` belongs to, replacing a previous session of it.
+    ///
+    /// Returns:
+    ///     the user of the account.
+    pub async fn sign_in<S: Into<String>>(&mut self, token: S) -> Result<User> {
+        let mut client = self.base.clone();
+        client.authenticate_with_token(token.into());
+        client.media_urls = Arc::new(MediaUrlCache::default());
+        let no_params: Option<&[(&str, &str)]> = None;
+        let response = client.get("/me", no_params).await?;
+        let user: User = client.read_json(response).await?;
+
+        self.clients.insert(user.id, client);
+        Ok(user)
+    }
+
+    /// Returns the client authenticated as the user with the id `account`, if it has been added.
+    pub fn with_account(&self, account: usize) -> Option<&Client> {
+        self.clients.get(&account)
+    }
+
+    /// Returns the ids of the users of all accounts, in ascending order.
+    pub fn accounts(&self) -> impl Iterator<Item = usize> + '_ {
+        self.clients.keys().copied()
+    }
+
+    /// Removes the account of the user with the id `account` without revoking its token.
+    ///
+    /// Returns:
+    ///     the client of the account, if it had been added.
+    pub fn remove(&mut self, account: usize) -> Option<Client> {
+        self.clients.remove(&account)
+    }
+
+    /// Removes the account of the user with the id `account` and revokes its token, see
+    /// [`Client::sign_out`].
+    pub async fn sign_out(&mut self, account: usize) -> Result<()> {
+        match self.clients.remove(&account) {
+            Some(mut client) => client.sign_out().await,
+            None => Ok(()),
+        }
+    }
+}
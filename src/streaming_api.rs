@@ -1,40 +1,348 @@
-use crate::error::Result;
+use std::collections::VecDeque;
+
+use crate::client::Client;
+use crate::error::{Error, Result};
+use crate::page::{Page, MAX_PAGE_SIZE};
+use crate::page_decoder::PageDecoder;
+use crate::retry::RetryPolicy;
 use crate::PageOptions;
+use futures::future::BoxFuture;
+use futures::stream;
 use futures::stream::BoxStream;
-use serde::de::DeserializeOwned;
+use futures::stream::StreamExt;
+use futures::{TryFutureExt, TryStreamExt};
+use serde::de::{DeserializeOwned, IgnoredAny};
 
 pub trait StreamingApiExt: StreamingApi {
     /// Return a stream of all [`StreamingApi::Model`].
-    fn iter(&self, options: PageOptions) -> BoxStream<Result<Self::Model>> {
+    fn iter(&self, options: PageOptions) -> BoxStream<'_, Result<Self::Model>> {
         self.fetch(&options, None)
     }
 
     /// Return a stream of [`StreamingApi::Model`] limited to the first num_pages pages
-    fn get(&self, options: PageOptions, num_pages: u64) -> BoxStream<Result<Self::Model>> {
+    fn get(&self, options: PageOptions, num_pages: u64) -> BoxStream<'_, Result<Self::Model>> {
         self.fetch(&options, Some(num_pages))
     }
+
+    /// Return a stream of all [`StreamingApi::Model`] after the first `num_pages` pages.
+    ///
+    /// The skipped pages still have to be requested to follow their cursors, but their items
+    /// aren't deserialized. Together with [`get`](StreamingApiExt::get) this splits a
+    /// collection into page ranges, e.g. to let several workers crawl it in parallel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use soundcloud::{Client, PageOptions, StreamingApiExt, User};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+    ///   let options = PageOptions::builder().page_size(50).build();
+    ///   let followers = client.user(31506117).followers();
+    ///   let second_half: Vec<User> = followers
+    ///     .skip_pages(options, 2)
+    ///     .take(100)
+    ///     .try_collect()
+    ///     .await
+    ///     .unwrap();
+    ///
+    ///   assert!(!second_half.is_empty());
+    /// }
+    /// ```
+    fn skip_pages(&self, options: PageOptions, num_pages: u64) -> BoxStream<'_, Result<Self::Model>>
+    where
+        Self: Sync,
+    {
+        let url = self.url(&options);
+
+        Box::pin(
+            stream::once(async move {
+                let next = skip_pages(self.client(), url, num_pages).await?;
+                let items: BoxStream<'_, Result<Self::Model>> = match next {
+                    Some(next) => self.get_stream(&next, None),
+                    None => stream::empty().boxed(),
+                };
+                Result::Ok(items)
+            })
+            .try_flatten(),
+        )
+    }
+
+    /// Return a stream of all [`StreamingApi::Model`] starting at the page `next_href`, as
+    /// returned by a previous request of the same collection.
+    fn start_from(&self, next_href: &str) -> BoxStream<'_, Result<Self::Model>> {
+        self.get_stream(next_href, None)
+    }
+
+    /// Return a stream of all [`StreamingApi::Model`] which doesn't end when an item can't be
+    /// deserialized.
+    ///
+    /// Items which fail to deserialize are yielded as [`Error::ItemDecode`](crate::Error::ItemDecode)
+    /// containing the raw JSON of the item, after which the stream continues with the next item.
+    /// Failing to fetch a page still ends the stream.
+    fn iter_lenient(&self, options: PageOptions) -> BoxStream<'_, Result<Self::Model>> {
+        self.adapt_stream(self.client().get_stream_lenient(&self.url(&options), None))
+    }
+
+    /// Return a stream of all [`StreamingApi::Model`] which decodes each page incrementally.
+    ///
+    /// Items are yielded as soon as they have been received, instead of after the whole page,
+    /// so large pages start flowing sooner and aren't held in memory at once. If a page fails
+    /// midway, the items received before are still yielded, so resuming from the
+    /// [`resume_url`](crate::Error::resume_url) of the error repeats them.
+    ///
+    /// The [maximum response size](crate::ClientBuilder::max_response_size) is checked against
+    /// the bytes received of each page, without holding the page in memory.
+    fn iter_streaming(&self, options: PageOptions) -> BoxStream<'_, Result<Self::Model>> {
+        let client = self.client().clone();
+        let state = (
+            Some(self.url(&options)),
+            None::<(String, reqwest::Response, PageDecoder<Self::Model>, u64)>,
+            VecDeque::new(),
+        );
+
+        self.adapt_stream(Box::pin(stream::try_unfold(
+            state,
+            move |(mut next, mut page, mut items)| {
+                let client = client.clone();
+                async move {
+                    loop {
+                        if let Some(item) = items.pop_front() {
+                            return Ok(Some((item, (next, page, items))));
+                        }
+
+                        if let Some((page_url, mut response, mut decoder, mut received)) =
+                            page.take()
+                        {
+                            let decoded = async {
+                                match response.chunk().await? {
+                                    Some(chunk) => {
+                                        received += chunk.len() as u64;
+                                        client.check_body_size(received)?;
+                                        items.extend(decoder.feed(&chunk)?);
+                                        Ok(Some(decoder))
+                                    }
+                                    None => {
+                                        next = decoder.finish()?.next_href;
+                                        Ok(None)
+                                    }
+                                }
+                            };
+                            match decoded.await {
+                                Ok(Some(decoder)) => {
+                                    page = Some((page_url, response, decoder, received))
+                                }
+                                Ok(None) => {}
+                                Err(source) => {
+                                    return Err(Error::Pagination {
+                                        page_url,
+                                        source: Box::new(source),
+                                    })
+                                }
+                            }
+                            continue;
+                        }
+
+                        let url = match next.take() {
+                            Some(url) => url,
+                            None => return Ok(None),
+                        };
+                        let page_url = client.page_url(&url);
+                        let response = async {
+                            let response = client.get_page_response(&url).await?;
+                            client.check_body_size(response.content_length().unwrap_or(0))?;
+                            Ok(response)
+                        };
+                        match response.await {
+                            Ok(response) => {
+                                page = Some((page_url, response, PageDecoder::new(), 0))
+                            }
+                            Err(source) => {
+                                return Err(Error::Pagination {
+                                    page_url,
+                                    source: Box::new(source),
+                                })
+                            }
+                        }
+                    }
+                }
+            },
+        )))
+    }
+
+    /// Return a stream of all [`StreamingApi::Model`] which retries failed page requests
+    /// according to the `retry_policy`.
+    ///
+    /// After a retry the stream resumes with the page which failed, so no item is skipped or
+    /// repeated. Errors which aren't [retryable](crate::Error::is_retryable), or which persist
+    /// after all retries, still end the stream.
+    fn iter_resilient(
+        &self,
+        options: PageOptions,
+        retry_policy: RetryPolicy,
+    ) -> BoxStream<'_, Result<Self::Model>> {
+        let client = self.client().clone();
+        let state = (Some(self.url(&options)), VecDeque::new(), true);
+
+        self.adapt_stream(Box::pin(stream::try_unfold(
+            state,
+            move |(mut next, mut items, mut first)| {
+                let client = client.clone();
+                let retry_policy = retry_policy.clone();
+                async move {
+                    loop {
+                        if let Some(item) = items.pop_front() {
+                            return Ok(Some((item, (next, items, first))));
+                        }
+
+                        let url = match next.take() {
+                            Some(url) => url,
+                            None => return Ok(None),
+                        };
+                        let page: Page<Self::Model> =
+                            get_page_with_retries(&client, &url, first, &retry_policy).await?;
+                        first = false;
+                        next = page.next_href;
+                        items.extend(page.collection);
+                    }
+                }
+            },
+        )))
+    }
+
+    /// Return the number of items per page actually requested with the `options`, as endpoints
+    /// cap the page size.
+    ///
+    /// Larger page sizes are still honored by [`get`](StreamingApiExt::get), which fetches the
+    /// requested number of items in multiple smaller pages.
+    fn effective_page_size(&self, options: &PageOptions) -> Option<u32> {
+        options
+            .page_size()
+            .map(|page_size| page_size.min(self.max_page_size()))
+    }
+
+    /// Return the total number of [`StreamingApi::Model`] in the collection, if the endpoint
+    /// provides it.
+    ///
+    /// Only fetches the first page, so this is much cheaper than counting the items of the stream.
+    fn total(&self, options: &PageOptions) -> BoxFuture<'_, Result<Option<u64>>> {
+        Box::pin(
+            self.client()
+                .get_pages::<serde_json::Value>(&self.url(options))
+                .map_ok(|page| page.total_results),
+        )
+    }
 }
 
 impl<T: ?Sized> StreamingApiExt for T where T: StreamingApi {}
 
 pub trait StreamingApi {
-    type Model: DeserializeOwned;
+    type Model: DeserializeOwned + 'static + Send;
 
     fn path(&self) -> String;
 
-    fn get_stream(&self, url: &str, pages: Option<u64>) -> BoxStream<Result<Self::Model>>;
+    fn client(&self) -> &Client;
 
+    fn get_stream(&self, url: &str, pages: Option<u64>) -> BoxStream<'_, Result<Self::Model>> {
+        self.adapt_stream(self.client().get_stream(url, pages))
+    }
+
+    /// Applies the options of the API which are evaluated on the client, like filters, to a
+    /// `stream` of its items.
+    ///
+    /// All streams of [`StreamingApiExt`] and [`crawler::sharded`](crate::crawler::sharded) pass
+    /// through it.
+    fn adapt_stream<'a>(
+        &self,
+        stream: BoxStream<'a, Result<Self::Model>>,
+    ) -> BoxStream<'a, Result<Self::Model>> {
+        stream
+    }
+
+    /// The largest page size accepted by the endpoint.
+    fn max_page_size(&self) -> u32 {
+        MAX_PAGE_SIZE
+    }
+
+    fn url(&self, options: &PageOptions) -> String {
+        options
+            .clamp_page_size(self.max_page_size())
+            .apply(&self.path())
+    }
+
+    /// Fetches `num_pages` pages of the page size requested in the `options`.
+    ///
+    /// If the page size exceeds the [`max_page_size`](StreamingApi::max_page_size), the
+    /// requested number of items is fetched in as many smaller pages as needed.
     fn fetch(
         &self,
         options: &PageOptions,
         num_pages: Option<u64>,
-    ) -> BoxStream<Result<Self::Model>> {
-        let url = self.path();
-        let url = if let Some(params) = options.serialize() {
-            format!("{}?{}", url, params)
+    ) -> BoxStream<'_, Result<Self::Model>> {
+        let url = self.url(options);
+
+        match (
+            options.page_size(),
+            self.effective_page_size(options),
+            num_pages,
+        ) {
+            (Some(page_size), Some(effective), Some(num_pages)) if page_size > effective => {
+                let limit = u64::from(page_size) * num_pages;
+                let pages = limit.div_ceil(u64::from(effective));
+                Box::pin(self.get_stream(&url, Some(pages)).take(limit as usize))
+            }
+            _ => self.get_stream(&url, num_pages),
+        }
+    }
+}
+
+/// Follows the cursors of the first `num_pages` pages at `url`, returning the url of the page
+/// after them, or `None` if the collection ends before.
+async fn skip_pages(client: &Client, url: String, num_pages: u64) -> Result<Option<String>> {
+    let mut next = Some(url);
+
+    for _ in 0..num_pages {
+        let url = match next {
+            Some(url) => url,
+            None => return Ok(None),
+        };
+        let page: Page<IgnoredAny> = client.get_first_page(&url).await?;
+        next = page.next_href;
+    }
+
+    Ok(next)
+}
+
+/// Fetches a page, retrying failed requests according to the `retry_policy`.
+///
+/// The first page is given as path, all further pages as the absolute `next_href`.
+async fn get_page_with_retries<T>(
+    client: &Client,
+    url: &str,
+    first: bool,
+    retry_policy: &RetryPolicy,
+) -> Result<Page<T>>
+where
+    T: DeserializeOwned + 'static + Send,
+{
+    let mut attempt = 0;
+
+    loop {
+        let result = if first {
+            client.get_pages(url).await
         } else {
-            url
+            client.get_pages_url(url).await
         };
-        self.get_stream(&url, num_pages)
+
+        match result {
+            Err(ref error) if error.is_retryable() && attempt < retry_policy.max_retries => {
+                attempt += 1;
+                log::warn!("retrying page request after error: {}", error);
+                tokio::time::sleep(retry_policy.backoff(attempt)).await;
+            }
+            result => return result,
+        }
     }
 }
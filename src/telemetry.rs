@@ -0,0 +1,63 @@
+//! Metrics emitted through the `metrics` facade if the `metrics` feature is enabled, and no-ops
+//! otherwise.
+//!
+//! * `soundcloud_requests_total`: HTTP requests, labeled with `kind` (`api` or `media`).
+//! * `soundcloud_request_errors_total`: failed HTTP requests, labeled with `kind` and `status`,
+//!   which is the status code or `network` if there was no response.
+//! * `soundcloud_request_duration_seconds`: histogram of the time until the response headers
+//!   arrived, labeled with `kind`.
+//! * `soundcloud_downloaded_bytes_total`: bytes of media written by streams and downloads.
+
+use std::time::Duration;
+
+#[cfg(feature = "metrics")]
+use crate::error::Error;
+use crate::error::Result;
+
+/// Records a finished HTTP request of `kind`, which took `duration`.
+#[cfg(feature = "metrics")]
+pub(crate) fn record_request(
+    kind: &'static str,
+    result: &Result<reqwest::Response>,
+    duration: Duration,
+) {
+    let status = match *result {
+        Ok(ref response) => Some(response.status()),
+        Err(Error::HttpError(ref error)) => error.status(),
+        Err(_) => None,
+    };
+
+    metrics::counter!("soundcloud_requests_total", "kind" => kind).increment(1);
+    metrics::histogram!("soundcloud_request_duration_seconds", "kind" => kind)
+        .record(duration.as_secs_f64());
+
+    let error_status = match status {
+        Some(status) if status.is_client_error() || status.is_server_error() => {
+            Some(status.as_u16().to_string())
+        }
+        Some(_) => None,
+        None => Some("network".to_owned()),
+    };
+
+    if let Some(status) = error_status {
+        metrics::counter!("soundcloud_request_errors_total", "kind" => kind, "status" => status)
+            .increment(1);
+    }
+}
+
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn record_request(
+    _kind: &'static str,
+    _result: &Result<reqwest::Response>,
+    _duration: Duration,
+) {
+}
+
+/// Records `bytes` of media written by a stream or download.
+#[cfg(feature = "metrics")]
+pub(crate) fn record_download(bytes: u64) {
+    metrics::counter!("soundcloud_downloaded_bytes_total").increment(bytes);
+}
+
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn record_download(_bytes: u64) {}
@@ -0,0 +1,24 @@
+use futures::io::AsyncRead;
+
+/// A reader of media data.
+pub type MediaReader = Box<dyn AsyncRead + Send + Unpin>;
+
+/// A transformation of media data on its way from the API to the writer, e.g. remuxing it into
+/// another container format with an external tool.
+///
+/// See [`Client::stream_with`](crate::Client::stream_with) and
+/// [`Client::download_with`](crate::Client::download_with).
+pub trait MediaTransform: Send + Sync {
+    /// Returns a reader of the transformed `input`.
+    fn transform(&self, input: MediaReader) -> MediaReader;
+}
+
+/// A [`MediaTransform`] which passes the media data through unchanged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PassThrough;
+
+impl MediaTransform for PassThrough {
+    fn transform(&self, input: MediaReader) -> MediaReader {
+        input
+    }
+}
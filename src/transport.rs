@@ -0,0 +1,29 @@
+use std::fmt;
+
+use futures::future::BoxFuture;
+
+use crate::error::Result;
+
+/// Executes the HTTP requests of a [`Client`](crate::Client).
+///
+/// Configured with [`ClientBuilder::transport`](crate::ClientBuilder::transport) and
+/// [`ClientBuilder::media_transport`](crate::ClientBuilder::media_transport), a transport
+/// replaces the default `reqwest` client, e.g. with another HTTP stack or a test double.
+///
+/// Responses for other HTTP stacks can be constructed from an `http::Response`.
+pub trait HttpTransport: Send + Sync {
+    /// Executes the request and returns the response, whatever its status.
+    fn execute(&self, request: reqwest::Request) -> BoxFuture<'_, Result<reqwest::Response>>;
+}
+
+impl fmt::Debug for dyn HttpTransport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("HttpTransport")
+    }
+}
+
+impl HttpTransport for reqwest::Client {
+    fn execute(&self, request: reqwest::Request) -> BoxFuture<'_, Result<reqwest::Response>> {
+        Box::pin(async move { Ok(reqwest::Client::execute(self, request).await?) })
+    }
+}
@@ -0,0 +1,453 @@
+//! Uploading tracks in parts, which are retried individually and can be resumed.
+//!
+//! An upload starts by checking the quota of the user with `GET /me`, if the API reports one.
+//! It is then prepared with `POST /uploads/track-upload-policy`, which returns a signed url
+//! for each part of the file. After all parts have been uploaded to these urls, the upload is
+//! completed with `POST /uploads/{uid}/complete` and the track is created with `POST /tracks`.
+
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::prelude::*;
+use futures::stream::BoxStream;
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::endpoint::Endpoint;
+use crate::error::{Error, Result};
+use crate::models::{Sharing, Track, TrackState};
+use crate::retry::RetryPolicy;
+
+/// Size of the parts a file is uploaded in, unless set otherwise.
+pub const DEFAULT_PART_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Smallest size of a part accepted by the API, except for the last one.
+pub const MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+
+/// How often the state of an uploaded track is polled, unless set otherwise.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A track to be uploaded, see [`Client::upload_track`](crate::Client::upload_track).
+#[derive(Debug, Clone)]
+pub struct TrackUpload {
+    title: String,
+    file_name: String,
+    source: PartSource,
+    sharing: Sharing,
+    part_size: u64,
+    retry_policy: RetryPolicy,
+    length: Option<Duration>,
+    session: Option<UploadSession>,
+}
+
+impl TrackUpload {
+    /// Creates an upload of the audio file `file_name`, which is published as a public track
+    /// with the `title`.
+    ///
+    /// The contents are read from the `reader`, e.g. a [`File`](std::fs::File) or an
+    /// [`io::Cursor`] over data in memory, one part at a time.
+    pub fn new<T, F, R>(title: T, file_name: F, reader: R) -> Self
+    where
+        T: Into<String>,
+        F: Into<String>,
+        R: Read + Seek + Send + 'static,
+    {
+        TrackUpload {
+            title: title.into(),
+            file_name: file_name.into(),
+            source: PartSource(Arc::new(Mutex::new(reader))),
+            sharing: Sharing::Public,
+            part_size: DEFAULT_PART_SIZE,
+            retry_policy: RetryPolicy::default(),
+            length: None,
+            session: None,
+        }
+    }
+
+    /// Sets the sharing status of the track.
+    pub fn sharing(&mut self, sharing: Sharing) -> &mut Self {
+        self.sharing = sharing;
+        self
+    }
+
+    /// Sets the size of the parts, which defaults to [`DEFAULT_PART_SIZE`].
+    ///
+    /// The upload fails with [`Error::InvalidPartSize`] if the file is split into parts smaller
+    /// than [`MIN_PART_SIZE`], which the API rejects for all but the last part.
+    pub fn part_size(&mut self, part_size: u64) -> &mut Self {
+        self.part_size = part_size;
+        self
+    }
+
+    /// Sets how failed uploads of a part are retried.
+    pub fn retry_policy(&mut self, retry_policy: RetryPolicy) -> &mut Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the length of the audio, which is checked against the upload quota of the user
+    /// before uploading.
+    ///
+    /// Without it, the upload only fails early if no quota is left at all.
+    pub fn length(&mut self, length: Duration) -> &mut Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// Resumes the interrupted upload of the same file described by `session`, as reported by
+    /// [`UploadEvent::Prepared`] and updated with every [`UploadEvent::PartUploaded`].
+    ///
+    /// Parts which have already been uploaded are skipped.
+    pub fn resume(&mut self, session: UploadSession) -> &mut Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Returns the contents of the `part`.
+    fn part_data(&self, session: &UploadSession, part: &UploadPart) -> Result<Bytes> {
+        let start = (u64::from(part.part_number) - 1) * session.part_size;
+        Ok(self.source.read(start, session.part_size)?)
+    }
+}
+
+/// Seekable reader of the file, shared by the clones of an upload.
+#[derive(Clone)]
+struct PartSource(Arc<Mutex<dyn ReadSeek>>);
+
+trait ReadSeek: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
+impl PartSource {
+    /// Returns the size of the file.
+    fn len(&self) -> io::Result<u64> {
+        self.lock().seek(SeekFrom::End(0))
+    }
+
+    /// Reads up to `len` bytes from the offset `start`.
+    fn read(&self, start: u64, len: u64) -> io::Result<Bytes> {
+        let mut reader = self.lock();
+        reader.seek(SeekFrom::Start(start))?;
+        let mut data = vec![];
+        (&mut *reader).take(len).read_to_end(&mut data)?;
+        Ok(data.into())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, dyn ReadSeek + 'static> {
+        match self.0.lock() {
+            Ok(reader) => reader,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl fmt::Debug for PartSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("PartSource")
+    }
+}
+
+/// State of an upload, which can be stored to [resume](TrackUpload::resume) it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct UploadSession {
+    /// Id of the upload.
+    pub uid: String,
+    /// Size of all parts but the last.
+    pub part_size: u64,
+    /// The parts of the file.
+    pub parts: Vec<UploadPart>,
+}
+
+impl UploadSession {
+    /// Returns the number of bytes of the parts uploaded so far.
+    pub fn bytes_sent(&self, total: u64) -> u64 {
+        self.parts
+            .iter()
+            .filter(|part| part.etag.is_some())
+            .map(|part| {
+                let start = (u64::from(part.part_number) - 1) * self.part_size;
+                (start + self.part_size).min(total).saturating_sub(start)
+            })
+            .sum()
+    }
+}
+
+/// Part of an [`UploadSession`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct UploadPart {
+    /// Number of the part, starting at 1.
+    pub part_number: u32,
+    /// Signed url the part is uploaded to.
+    pub url: String,
+    /// Entity tag returned for the uploaded part, if it has been uploaded.
+    #[serde(default)]
+    pub etag: Option<String>,
+}
+
+/// Progress of an upload, see [`Client::upload_track`](crate::Client::upload_track).
+#[derive(Debug, Clone)]
+pub enum UploadEvent {
+    /// The upload has been prepared, or is resumed.
+    Prepared(UploadSession),
+    /// A part has been uploaded.
+    PartUploaded {
+        part_number: u32,
+        /// Number of bytes of all parts uploaded so far.
+        bytes_sent: u64,
+        /// Size of the file.
+        total: u64,
+        /// The session including the part, to resume the upload from if it fails later.
+        session: UploadSession,
+    },
+    /// All parts have been uploaded and the track has been created.
+    Finished(Box<UploadHandle>),
+}
+
+/// A track created by an upload.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct UploadHandle {
+    /// Id of the upload.
+    pub uid: String,
+    /// The created track, which may still be processed.
+    pub track: Track,
+    client: Client,
+    poll_interval: Duration,
+}
+
+impl UploadHandle {
+    /// Sets how often [`UploadHandle::wait_until_ready`] polls the state of the track, which
+    /// defaults to [`DEFAULT_POLL_INTERVAL`].
+    pub fn poll_interval(&mut self, poll_interval: Duration) -> &mut Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Waits until the uploaded file has been processed and the track can be played.
+    ///
+    /// Fails with [`Error::ProcessingFailed`] if the file couldn't be processed, or with
+    /// [`Error::ProcessingTimedOut`] if it hasn't been processed within `timeout`.
+    ///
+    /// Returns:
+    ///     the processed track.
+    pub async fn wait_until_ready(&self, timeout: Duration) -> Result<Track> {
+        let id = self.track.id;
+        let poll = async {
+            loop {
+                let track: Track = self.client.fetch(id).await?;
+                match track.state {
+                    Some(TrackState::Finished) => return Ok(track),
+                    Some(TrackState::Failed) => return Err(Error::ProcessingFailed(id)),
+                    Some(TrackState::Processing) => {}
+                    // Without a state, the track is ready once it can be streamed.
+                    Some(TrackState::Unknown) | None if track.streamable => return Ok(track),
+                    Some(TrackState::Unknown) | None => {}
+                }
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        };
+
+        match tokio::time::timeout(timeout, poll).await {
+            Ok(result) => result,
+            Err(_) => Err(Error::ProcessingTimedOut(id)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct UploadPolicy {
+    uid: String,
+    parts: Vec<UploadPart>,
+}
+
+/// Where an upload is at.
+enum Step {
+    Prepare,
+    Parts(UploadSession),
+    Done,
+}
+
+pub(crate) fn upload_track(
+    client: Client,
+    upload: TrackUpload,
+) -> BoxStream<'static, Result<UploadEvent>> {
+    let step = match upload.session.clone() {
+        Some(session) => Step::Parts(session),
+        None => Step::Prepare,
+    };
+    let resumed = upload.session.clone();
+
+    let events = stream::try_unfold(
+        (client, upload, step),
+        |(client, upload, step)| async move {
+            match step {
+                Step::Prepare => {
+                    let session = prepare(&client, &upload).await?;
+                    let event = UploadEvent::Prepared(session.clone());
+                    Ok(Some((event, (client, upload, Step::Parts(session)))))
+                }
+                Step::Parts(mut session) => {
+                    let next = session.parts.iter().position(|part| part.etag.is_none());
+                    match next {
+                        Some(index) => {
+                            let etag = upload_part(&client, &upload, &session, index).await?;
+                            session.parts[index].etag = Some(etag);
+                            let total = upload.source.len()?;
+                            let event = UploadEvent::PartUploaded {
+                                part_number: session.parts[index].part_number,
+                                bytes_sent: session.bytes_sent(total),
+                                total,
+                                session: session.clone(),
+                            };
+                            Ok(Some((event, (client, upload, Step::Parts(session)))))
+                        }
+                        None => {
+                            let handle = finish(&client, &upload, session).await?;
+                            let event = UploadEvent::Finished(Box::new(handle));
+                            Ok(Some((event, (client, upload, Step::Done))))
+                        }
+                    }
+                }
+                Step::Done => Ok(None),
+            }
+        },
+    );
+
+    match resumed {
+        Some(session) => {
+            Box::pin(stream::once(future::ok(UploadEvent::Prepared(session))).chain(events))
+        }
+        None => Box::pin(events),
+    }
+}
+
+/// Checks the part size and the upload quota, if known, and requests the signed urls of the
+/// parts.
+async fn prepare(client: &Client, upload: &TrackUpload) -> Result<UploadSession> {
+    let size = upload.source.len()?;
+    if size > upload.part_size && upload.part_size < MIN_PART_SIZE {
+        return Err(Error::InvalidPartSize(upload.part_size));
+    }
+
+    let length = upload.length.unwrap_or(Duration::from_secs(1));
+    if let Some(quota) = client.quota().await? {
+        if !quota.allows(length) {
+            return Err(Error::QuotaExceeded(quota.remaining().unwrap_or_default()));
+        }
+    }
+
+    let url = client.api_url("/uploads/track-upload-policy")?;
+    let request = client
+        .request(reqwest::Method::POST, url)?
+        .json(&serde_json::json!({
+            "filename": upload.file_name,
+            "filesize": size,
+            "part_size": upload.part_size,
+        }));
+    let response = client.send(request).await?;
+    let policy: UploadPolicy = client.read_json(response).await?;
+
+    Ok(UploadSession {
+        uid: policy.uid,
+        part_size: upload.part_size,
+        parts: policy.parts,
+    })
+}
+
+/// Uploads the part at `index`, retrying according to the retry policy of the upload.
+///
+/// Returns:
+///     the entity tag of the uploaded part.
+async fn upload_part(
+    client: &Client,
+    upload: &TrackUpload,
+    session: &UploadSession,
+    index: usize,
+) -> Result<String> {
+    let part = &session.parts[index];
+    let data = upload.part_data(session, part)?;
+    let mut attempt = 0;
+
+    loop {
+        let request = client
+            .http_client
+            .put(&part.url)
+            .body(data.clone())
+            .build()?;
+        let result = client
+            .execute_once(&*client.media_transport, "upload", request)
+            .await
+            .and_then(|response| Ok(response.error_for_status()?));
+
+        match result {
+            Ok(response) => {
+                let etag = response
+                    .headers()
+                    .get(reqwest::header::ETAG)
+                    .and_then(|etag| etag.to_str().ok());
+                return match etag {
+                    Some(etag) => Ok(etag.to_owned()),
+                    None => Err(Error::ApiError(format!(
+                        "no ETag for part {} of upload {}",
+                        part.part_number, session.uid
+                    ))),
+                };
+            }
+            Err(ref error) if error.is_retryable() && attempt < upload.retry_policy.max_retries => {
+                attempt += 1;
+                log::warn!("retrying part {} after error: {}", part.part_number, error);
+                tokio::time::sleep(upload.retry_policy.backoff(attempt)).await;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Completes the upload and creates the track.
+async fn finish(
+    client: &Client,
+    upload: &TrackUpload,
+    session: UploadSession,
+) -> Result<UploadHandle> {
+    let parts: Vec<_> = session
+        .parts
+        .iter()
+        .map(|part| serde_json::json!({ "part_number": part.part_number, "etag": part.etag }))
+        .collect();
+    let url = client.api_url(
+        &Endpoint::new("/uploads")
+            .segment(&session.uid)
+            .segment("complete")
+            .to_string(),
+    )?;
+    let request = client
+        .request(reqwest::Method::POST, url)?
+        .json(&serde_json::json!({ "parts": parts }));
+    client.send(request).await?;
+
+    let url = client.api_url("/tracks")?;
+    let request = client
+        .request(reqwest::Method::POST, url)?
+        .json(&serde_json::json!({
+            "track": {
+                "title": upload.title,
+                "sharing": upload.sharing.as_str(),
+                "original_filename": upload.file_name,
+                "uid": session.uid,
+            }
+        }));
+    let response = client.send(request).await?;
+    let track: Track = client.read_json(response).await?;
+
+    Ok(UploadHandle {
+        uid: session.uid,
+        track,
+        client: client.clone(),
+        poll_interval: DEFAULT_POLL_INTERVAL,
+    })
+}
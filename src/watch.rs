@@ -0,0 +1,259 @@
+//! Streams of changes, produced by polling the API.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use futures::prelude::*;
+use futures::stream::BoxStream;
+
+use crate::apis::{ChartKind, Charts};
+use crate::client::Client;
+use crate::error::{Error, Result};
+use crate::models::{Genre, Policy, Sharing, Track};
+use crate::page::PageOptions;
+use crate::streaming_api::StreamingApiExt;
+
+/// Number of chart entries compared by [`chart_monitor`].
+const CHART_MONITOR_SIZE: u32 = 50;
+
+/// Change of a chart between two polls. Ranks start at 1.
+#[derive(Debug, Clone)]
+pub enum ChartDelta {
+    /// The track entered the chart.
+    Entered { track: Track, rank: usize },
+    /// The track changed its rank.
+    Moved {
+        track: Track,
+        from: usize,
+        to: usize,
+    },
+    /// The track dropped out of the chart.
+    Left { track: Track, rank: usize },
+}
+
+/// Returns an endless stream of the changes to the trending chart of `genre`, polled every
+/// `interval`.
+///
+/// The first poll only establishes the baseline, so changes are reported from the second poll
+/// on. The stream ends after the first failed poll.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use futures::prelude::*;
+/// use soundcloud::{Client, Genre};
+/// use soundcloud::watch::{chart_monitor, ChartDelta};
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+///   let mut deltas = chart_monitor(client, Genre::HipHopRap, Duration::from_secs(3600));
+///
+///   while let Some(delta) = deltas.try_next().await.unwrap() {
+///     if let ChartDelta::Entered { track, rank } = delta {
+///       println!("#{} {}", rank, track.title);
+///     }
+///   }
+/// }
+/// ```
+pub fn chart_monitor(
+    client: Client,
+    genre: Genre,
+    interval: Duration,
+) -> BoxStream<'static, Result<ChartDelta>> {
+    let charts = Charts::new(client, ChartKind::Trending, genre);
+    let state = (charts, None::<Vec<Track>>, VecDeque::new());
+
+    Box::pin(stream::try_unfold(
+        state,
+        move |(charts, mut previous, mut pending)| async move {
+            loop {
+                if let Some(delta) = pending.pop_front() {
+                    return Ok(Some((delta, (charts, previous, pending))));
+                }
+
+                if previous.is_some() {
+                    tokio::time::sleep(interval).await;
+                }
+
+                let options = PageOptions::builder().page_size(CHART_MONITOR_SIZE).build();
+                let current: Vec<Track> = charts
+                    .get(options, 1)
+                    .map_ok(|entry| entry.track)
+                    .try_collect()
+                    .await?;
+
+                if let Some(ref previous) = previous {
+                    pending.extend(diff(previous, &current));
+                }
+                previous = Some(current);
+            }
+        },
+    ))
+}
+
+/// Whether a track can be retrieved, as seen by the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Availability {
+    /// The track can be retrieved.
+    Available,
+    /// The track is blocked in the region of the client.
+    GeoBlocked,
+    /// The track has been made private.
+    Private,
+    /// The track has been deleted.
+    Deleted,
+}
+
+/// Change of the availability of a track between two polls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AvailabilityChange {
+    pub track_id: u64,
+    pub from: Availability,
+    pub to: Availability,
+}
+
+/// Returns an endless stream of the changes to the availability of the tracks with the
+/// `track_ids`, polled every `interval`.
+///
+/// The first poll only establishes the baseline, so changes are reported from the second poll
+/// on. The stream ends after the first poll failing for other reasons than the track being
+/// unavailable.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use futures::prelude::*;
+/// use soundcloud::Client;
+/// use soundcloud::watch::{availability, Availability};
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Client::new(env!("SOUNDCLOUD_CLIENT_ID"));
+///   let mut changes = availability(client, vec![263801976], Duration::from_secs(3600));
+///
+///   while let Some(change) = changes.try_next().await.unwrap() {
+///     if change.to != Availability::Available {
+///       println!("track {} is {:?}", change.track_id, change.to);
+///     }
+///   }
+/// }
+/// ```
+pub fn availability(
+    client: Client,
+    track_ids: Vec<u64>,
+    interval: Duration,
+) -> BoxStream<'static, Result<AvailabilityChange>> {
+    let state = (
+        client,
+        track_ids,
+        None::<Vec<Availability>>,
+        VecDeque::new(),
+    );
+
+    Box::pin(stream::try_unfold(
+        state,
+        move |(client, track_ids, mut previous, mut pending)| async move {
+            loop {
+                if let Some(change) = pending.pop_front() {
+                    return Ok(Some((change, (client, track_ids, previous, pending))));
+                }
+
+                if previous.is_some() {
+                    tokio::time::sleep(interval).await;
+                }
+
+                let mut current = Vec::with_capacity(track_ids.len());
+                for &id in track_ids.iter() {
+                    current.push(classify(client.fetch::<Track>(id).await)?);
+                }
+
+                if let Some(ref previous) = previous {
+                    pending.extend(
+                        track_ids
+                            .iter()
+                            .zip(previous.iter().zip(current.iter()))
+                            .filter(|(_, (from, to))| from != to)
+                            .map(|(&track_id, (&from, &to))| AvailabilityChange {
+                                track_id,
+                                from,
+                                to,
+                            }),
+                    );
+                }
+                previous = Some(current);
+            }
+        },
+    ))
+}
+
+/// Returns the availability of a track given the result of fetching it.
+fn classify(result: Result<Track>) -> Result<Availability> {
+    match result {
+        Ok(ref track) if track.policy == Some(Policy::Block) => Ok(Availability::GeoBlocked),
+        Ok(ref track) if track.sharing == Sharing::Private => Ok(Availability::Private),
+        Ok(_) => Ok(Availability::Available),
+        Err(Error::HttpError(ref error)) => match error.status() {
+            Some(reqwest::StatusCode::NOT_FOUND) | Some(reqwest::StatusCode::GONE) => {
+                Ok(Availability::Deleted)
+            }
+            Some(reqwest::StatusCode::FORBIDDEN) | Some(reqwest::StatusCode::UNAUTHORIZED) => {
+                Ok(Availability::Private)
+            }
+            _ => result.map(|_| Availability::Available),
+        },
+        Err(Error::InsufficientScope { .. }) | Err(Error::AuthenticationRequired(_)) => {
+            Ok(Availability::Private)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Returns the changes from the `previous` to the `current` ranking.
+fn diff(previous: &[Track], current: &[Track]) -> Vec<ChartDelta> {
+    let previous_ranks: HashMap<u64, usize> = previous
+        .iter()
+        .enumerate()
+        .map(|(index, track)| (track.id, index + 1))
+        .collect();
+    let current_ranks: HashMap<u64, usize> = current
+        .iter()
+        .enumerate()
+        .map(|(index, track)| (track.id, index + 1))
+        .collect();
+
+    let mut deltas: Vec<ChartDelta> = current
+        .iter()
+        .enumerate()
+        .filter_map(|(index, track)| {
+            let rank = index + 1;
+            match previous_ranks.get(&track.id) {
+                None => Some(ChartDelta::Entered {
+                    track: track.clone(),
+                    rank,
+                }),
+                Some(&from) if from != rank => Some(ChartDelta::Moved {
+                    track: track.clone(),
+                    from,
+                    to: rank,
+                }),
+                Some(_) => None,
+            }
+        })
+        .collect();
+
+    deltas.extend(
+        previous
+            .iter()
+            .enumerate()
+            .filter(|(_, track)| !current_ranks.contains_key(&track.id))
+            .map(|(index, track)| ChartDelta::Left {
+                track: track.clone(),
+                rank: index + 1,
+            }),
+    );
+
+    deltas
+}
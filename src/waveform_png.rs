@@ -0,0 +1,64 @@
+use crate::error::{Error, Result};
+use crate::models::Waveform;
+
+/// Largest waveform image accepted, in pixels.
+///
+/// SoundCloud's waveform images are 1800x280 pixels.
+const MAX_PIXELS: u64 = 4_000_000;
+
+/// Converts a waveform PNG into a [`Waveform`] with a sample per column of the image.
+///
+/// The waveform is drawn by the transparent pixels of the image, or by the dark pixels of an
+/// image without transparency, so the amplitude of a sample is the number of such pixels in its
+/// column.
+pub(crate) fn decode(data: &[u8]) -> Result<Waveform> {
+    let mut decoder = png::Decoder::new(data);
+    // Expand palettes, transparency and small bit depths into 8 bit channels.
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().map_err(invalid)?;
+
+    let info = reader.info();
+    let (width, height) = (info.width, info.height);
+    if u64::from(width) * u64::from(height) > MAX_PIXELS {
+        return Err(Error::InvalidWaveform(format!(
+            "image of {}x{} pixels is too large",
+            width, height
+        )));
+    }
+
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut pixels).map_err(invalid)?;
+    let channels = frame.color_type.samples();
+    let stride = width as usize * channels;
+    if frame.bit_depth != png::BitDepth::Eight || pixels.len() < stride * height as usize {
+        return Err(Error::InvalidWaveform("unexpected pixel format".to_owned()));
+    }
+
+    let is_wave = |x: u32, y: u32| {
+        let offset = y as usize * stride + x as usize * channels;
+        let pixel = &pixels[offset..offset + channels];
+        match frame.color_type {
+            png::ColorType::GrayscaleAlpha => pixel[1] < 128,
+            png::ColorType::Rgba => pixel[3] < 128,
+            png::ColorType::Rgb => luminance(pixel) < 128,
+            _ => pixel[0] < 128,
+        }
+    };
+    let samples = (0..width)
+        .map(|x| (0..height).filter(|&y| is_wave(x, y)).count() as u32)
+        .collect();
+
+    Ok(Waveform {
+        width,
+        height,
+        samples,
+    })
+}
+
+fn invalid(error: png::DecodingError) -> Error {
+    Error::InvalidWaveform(error.to_string())
+}
+
+fn luminance(rgb: &[u8]) -> u8 {
+    ((u32::from(rgb[0]) * 299 + u32::from(rgb[1]) * 587 + u32::from(rgb[2]) * 114) / 1000) as u8
+}
@@ -0,0 +1,2551 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+
+use soundcloud::*;
+
+mod common;
+
+#[tokio::test]
+async fn test_circuit_breaker_opens_after_failures() {
+    let host = common::serve(vec![(500, "{}"), (500, "{}"), (200, "{}")]).await;
+    let client = Client::builder("clientid")
+        .host(host)
+        .circuit_breaker(2, Duration::from_secs(60))
+        .build()
+        .unwrap();
+    let no_params: Option<&[(&str, &str)]> = None;
+
+    assert!(matches!(
+        client.get("/me", no_params).await,
+        Err(Error::HttpError(_))
+    ));
+    assert!(matches!(
+        client.get("/me", no_params).await,
+        Err(Error::HttpError(_))
+    ));
+    assert!(matches!(
+        client.get("/me", no_params).await,
+        Err(Error::CircuitOpen(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_batch_collects_errors() {
+    let host = common::serve(vec![(404, "{}"), (404, "{}")]).await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let result = client.batch(1).users(vec![1, 2]).await;
+
+    assert!(!result.is_complete());
+    assert!(result.items.is_empty());
+    assert_eq!(
+        vec![1, 2],
+        result.errors.iter().map(|(id, _)| *id).collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn test_total_results() {
+    let host = common::serve(vec![(
+        200,
+        r#"{"collection":[],"next_href":null,"total_results":12345}"#,
+    )])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let total = client
+        .user(1)
+        .followers()
+        .total(&Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(Some(12345), total);
+}
+
+#[tokio::test]
+async fn test_resolve_private_share_url() {
+    let host = common::serve_with_headers(vec![
+        (
+            302,
+            vec![("Location", "https://api.soundcloud.com/tracks/123")],
+            "{}",
+        ),
+        (
+            302,
+            vec![(
+                "Location",
+                "https://api.soundcloud.com/playlists/456?secret_token=s-XyZ",
+            )],
+            "{}",
+        ),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+
+    let track = client
+        .resolve_track("https://soundcloud.com/forss/flickermood/s-AbCdE")
+        .await
+        .unwrap();
+    assert_eq!(123, track.id);
+    assert_eq!(Some("s-AbCdE"), track.secret_token.as_deref());
+
+    let playlist = client
+        .resolve_playlist("https://soundcloud.com/forss/sets/soulhack")
+        .await
+        .unwrap();
+    assert_eq!(456, playlist.id);
+    assert_eq!(Some("s-XyZ"), playlist.secret_token.as_deref());
+}
+
+#[tokio::test]
+async fn test_personalized_playlists() {
+    let host = common::serve(vec![(
+        200,
+        r#"{"collection":[{"items":{"collection":[
+            {"kind":"system-playlist","urn":"soundcloud:system-playlists:the-upload:1","title":"The Upload","tracks":[{"id":1},{"id":2}]},
+            {"kind":"playlist","id":3}
+        ]}}]}"#,
+    )])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let playlists = client.personalized_playlists().await.unwrap();
+
+    assert_eq!(1, playlists.len());
+    assert_eq!("The Upload", playlists[0].title);
+    assert_eq!(vec![1, 2], playlists[0].track_ids());
+    assert!(!playlists[0].is_hydrated());
+}
+
+#[tokio::test]
+async fn test_common_likers() {
+    let host = common::serve(vec![
+        (200, common::users_page(&[1, 2])),
+        (200, common::users_page(&[2, 3])),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let users = soundcloud::analytics::common_likers(&client, 1, 2, 100)
+        .await
+        .unwrap();
+
+    assert_eq!(vec![2], users.iter().map(|u| u.id).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_crawler_visits_followings() {
+    use futures::TryStreamExt;
+    use soundcloud::crawler::{CrawlEvent, Crawler};
+
+    let host = common::serve(vec![
+        (200, common::user(1)),
+        (200, common::users_page(&[2, 3])),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let events: Vec<CrawlEvent> = Crawler::new(client, vec![1])
+        .max_depth(1)
+        .crawl()
+        .try_collect()
+        .await
+        .unwrap();
+
+    let users: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            CrawlEvent::User { user, depth } => Some((user.id, *depth)),
+            CrawlEvent::Edge { .. } => None,
+        })
+        .collect();
+    let edges: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            CrawlEvent::Edge { follower, followed } => Some((*follower, *followed)),
+            CrawlEvent::User { .. } => None,
+        })
+        .collect();
+    assert_eq!(vec![(1, 0), (2, 1), (3, 1)], users);
+    assert_eq!(vec![(1, 2), (1, 3)], edges);
+}
+
+#[tokio::test]
+async fn test_comments_since_watermark() {
+    use futures::TryStreamExt;
+
+    let comment = |id: usize, created_at: &str| {
+        format!(
+            r#"{{"id":{0},"uri":"https://api.soundcloud.com/comments/{0}","created_at":"{1}","body":"","timestamp":null,"user_id":1,"user":{2},"track_id":1}}"#,
+            id,
+            created_at,
+            common::user(1)
+        )
+    };
+    let page = format!(
+        r#"{{"collection":[{},{},{}],"next_href":null}}"#,
+        comment(3, "2020-03-01T00:00:00Z"),
+        comment(2, "2020-02-01T00:00:00Z"),
+        comment(1, "2020-01-01T00:00:00Z"),
+    );
+    let page: &'static str = Box::leak(page.into_boxed_str());
+    let host = common::serve(vec![(200, page); 5]).await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let comments = client
+        .track(1)
+        .comments()
+        .order(CommentOrder::Newest)
+        .since("2020-02-01T00:00:00Z");
+
+    // The watermark applies to every kind of stream.
+    let streams = vec![
+        comments.iter(Default::default()),
+        comments.iter_lenient(Default::default()),
+        comments.iter_streaming(Default::default()),
+        comments.iter_resilient(Default::default(), RetryPolicy::default()),
+        soundcloud::crawler::sharded(&comments, Default::default(), 2),
+    ];
+    for stream in streams {
+        let comments: Vec<Comment> = stream.try_collect().await.unwrap();
+        assert_eq!(vec![Comment::new_for_test(3)], comments);
+    }
+}
+
+#[tokio::test]
+async fn test_create_web_profile() {
+    let host = common::serve(vec![(
+        201,
+        r#"{"kind":"web-profile","id":7,"service":"personal","title":"Website","url":"https://example.com","username":null,"created_at":"2020-01-01T00:00:00Z"}"#,
+    )])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let update = WebProfileUpdate {
+        url: Some("https://example.com".to_owned()),
+        title: Some("Website".to_owned()),
+    };
+    let web_profile = client.create_web_profile(update).await.unwrap();
+
+    assert_eq!(7, web_profile.id);
+    assert_eq!("https://example.com", web_profile.url);
+}
+
+#[tokio::test]
+async fn test_get_with_meta() {
+    let host =
+        common::serve_with_headers(vec![(200, vec![("ETag", "\"abc\"")], common::user(1))]).await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let (user, meta) = client.user(1).get_with_meta().await.unwrap();
+
+    assert_eq!(1, user.id);
+    assert_eq!(200, meta.status.as_u16());
+    assert_eq!("\"abc\"", meta.headers["etag"]);
+    assert_eq!("/users/1", meta.url.path());
+}
+
+#[tokio::test]
+async fn test_stream_collection() {
+    use futures::TryStreamExt;
+
+    let host = common::serve(vec![(
+        200,
+        r#"{"collection":[{"id":1},{"id":2}],"next_href":null}"#,
+    )])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let items: Vec<serde_json::Value> = client
+        .stream_collection("/unmodeled", Default::default())
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(2, items.len());
+    assert_eq!(2, items[1]["id"]);
+}
+
+#[tokio::test]
+async fn test_iter_resilient_retries_failed_pages() {
+    use futures::TryStreamExt;
+
+    let host = common::serve(vec![
+        (503, "{}"),
+        (429, "{}"),
+        (200, common::users_page(&[1, 2])),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let retry_policy = RetryPolicy::builder()
+        .initial_backoff(Duration::from_millis(1))
+        .build();
+    let users: Vec<User> = client
+        .user(1)
+        .followers()
+        .iter_resilient(Default::default(), retry_policy)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(vec![1, 2], users.iter().map(|u| u.id).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_chart_monitor() {
+    use futures::{StreamExt, TryStreamExt};
+    use soundcloud::watch::{chart_monitor, ChartDelta};
+
+    let chart = |ids: &[u64]| {
+        let entries: Vec<String> = ids
+            .iter()
+            .map(|id| format!(r#"{{"score":1.0,"track":{}}}"#, common::track(*id)))
+            .collect();
+        let page = format!(
+            r#"{{"collection":[{}],"next_href":null}}"#,
+            entries.join(",")
+        );
+        &*Box::leak(page.into_boxed_str())
+    };
+    let host = common::serve(vec![(200, chart(&[1, 2, 3])), (200, chart(&[2, 4, 1]))]).await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let deltas: Vec<ChartDelta> = chart_monitor(client, Genre::AllMusic, Duration::from_millis(1))
+        .take(4)
+        .try_collect()
+        .await
+        .unwrap();
+
+    let summary: Vec<String> = deltas
+        .iter()
+        .map(|delta| match delta {
+            ChartDelta::Entered { track, rank } => format!("+{}@{}", track.id, rank),
+            ChartDelta::Moved { track, from, to } => format!("{}:{}->{}", track.id, from, to),
+            ChartDelta::Left { track, rank } => format!("-{}@{}", track.id, rank),
+        })
+        .collect();
+    assert_eq!(vec!["2:2->1", "+4@2", "1:1->3", "-3@3"], summary);
+}
+
+#[tokio::test]
+async fn test_download_info() {
+    let host = common::serve_with_headers(vec![
+        (
+            200,
+            vec![
+                ("Content-Type", "audio/x-wav"),
+                ("Content-Disposition", "attachment; filename=\"Track.WAV\""),
+            ],
+            "",
+        ),
+        (404, vec![], ""),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let mut track = Track::new_for_test(1);
+    track.downloadable = true;
+    track.download_url = Some(format!("{}/tracks/1/download", host));
+
+    let info = client.download_info(&track).await.unwrap();
+    assert!(info.available);
+    assert_eq!(Some("wav".to_owned()), info.format());
+
+    let info = client.download_info(&track).await.unwrap();
+    assert!(!info.available);
+}
+
+struct Prefix(&'static [u8]);
+
+impl MediaTransform for Prefix {
+    fn transform(&self, input: MediaReader) -> MediaReader {
+        use futures::io::{AsyncReadExt, Cursor};
+        Box::new(Cursor::new(self.0).chain(input))
+    }
+}
+
+#[tokio::test]
+async fn test_download_with_transform() {
+    let host = common::serve(vec![(200, "audio"), (200, "audio")]).await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let mut track = Track::new_for_test(1);
+    track.downloadable = true;
+    track.download_url = Some(format!("{}/tracks/1/download", host));
+
+    let mut output = vec![];
+    let num_bytes = client
+        .download_with(&track, &mut output, &Prefix(b"header:"))
+        .await
+        .unwrap();
+    assert_eq!(12, num_bytes);
+    assert_eq!(b"header:audio".to_vec(), output);
+
+    let mut output = vec![];
+    client.download(&track, &mut output).await.unwrap();
+    assert_eq!(b"audio".to_vec(), output);
+}
+
+#[tokio::test]
+async fn test_download_to_sink() {
+    use futures::SinkExt;
+
+    let host = common::serve(vec![(200, "audio")]).await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let mut track = Track::new_for_test(1);
+    track.downloadable = true;
+    track.download_url = Some(format!("{}/tracks/1/download", host));
+
+    let mut output: Vec<bytes::Bytes> = vec![];
+    let sink = (&mut output).sink_map_err(|_| std::io::Error::from(std::io::ErrorKind::Other));
+    let num_bytes = client.download_to_sink(&track, sink).await.unwrap();
+
+    assert_eq!(5, num_bytes);
+    assert_eq!(b"audio".to_vec(), output.concat());
+}
+
+#[tokio::test]
+async fn test_download_with_cancellation() {
+    // Only half of the announced body is sent before the transfer stalls.
+    let host = common::serve_raw(|_| {
+        vec![common::raw(
+            "HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: close\r\n\r\naudio",
+        )]
+    })
+    .await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let mut track = Track::new_for_test(1);
+    track.downloadable = true;
+    track.download_url = Some(format!("{}/tracks/1/download", host));
+    let cancellation = tokio::time::sleep(Duration::from_millis(200));
+
+    let mut output = vec![];
+    let result = tokio::time::timeout(
+        Duration::from_secs(2),
+        client.download_with_cancellation(&track, &mut output, cancellation),
+    )
+    .await
+    .expect("download should stop on cancellation");
+
+    assert!(matches!(result, Err(Error::Cancelled(5))));
+    assert_eq!(b"audio".to_vec(), output);
+}
+
+#[tokio::test]
+async fn test_download_verifies_checksum() {
+    let host = common::serve_with_headers(vec![
+        (
+            200,
+            vec![("ETag", "\"a5ca0b5894324f8bb54bb9fffad29d1e\"")],
+            "audio",
+        ),
+        (
+            200,
+            vec![("ETag", "\"a5ca0b5894324f8bb54bb9fffad29d1e\"")],
+            "aud",
+        ),
+        (200, vec![], "aud"),
+        (
+            200,
+            vec![("ETag", "W/\"a5ca0b5894324f8bb54bb9fffad29d1e\"")],
+            "aud",
+        ),
+        (
+            200,
+            vec![("Content-MD5", "pcoLWJQyT4u1S7n/+tKdHg==")],
+            "aud",
+        ),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let mut track = Track::new_for_test(1);
+    track.downloadable = true;
+    track.download_url = Some(format!("{}/tracks/1/download", host));
+    let options = DownloadOptions::builder()
+        .verify(Checksum::Md5)
+        .trust_etag(true)
+        .build();
+
+    let mut output = vec![];
+    let num_bytes = client
+        .download_with_options(&track, &mut output, &options)
+        .await
+        .unwrap();
+    assert_eq!(5, num_bytes);
+
+    let mut output = vec![];
+    assert!(matches!(
+        client
+            .download_with_options(&track, &mut output, &options)
+            .await,
+        Err(Error::ChecksumMismatch { .. })
+    ));
+
+    // Without a checksum from the CDN there's nothing to compare to.
+    let mut output = vec![];
+    assert!(client
+        .download_with_options(&track, &mut output, &options)
+        .await
+        .is_ok());
+
+    // Weak entity tags are never checksums.
+    let mut output = vec![];
+    assert!(client
+        .download_with_options(&track, &mut output, &options)
+        .await
+        .is_ok());
+
+    // Content-MD5 is always compared.
+    let options = DownloadOptions::builder().verify(Checksum::Md5).build();
+    let mut output = vec![];
+    assert!(matches!(
+        client
+            .download_with_options(&track, &mut output, &options)
+            .await,
+        Err(Error::ChecksumMismatch { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_download_ignores_etag_by_default() {
+    let host = common::serve_with_headers(vec![(
+        200,
+        vec![("ETag", "\"a5ca0b5894324f8bb54bb9fffad29d1e\"")],
+        "aud",
+    )])
+    .await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let mut track = Track::new_for_test(1);
+    track.downloadable = true;
+    track.download_url = Some(format!("{}/tracks/1/download", host));
+    let options = DownloadOptions::builder().verify(Checksum::Md5).build();
+
+    let mut output = vec![];
+    assert!(client
+        .download_with_options(&track, &mut output, &options)
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_zip_tracks() {
+    let host = common::serve(vec![(200, "first"), (200, "second")]).await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let tracks: Vec<Track> = ["One", "Two/Three"]
+        .iter()
+        .enumerate()
+        .map(|(index, title)| {
+            let mut track = Track::new_for_test(index as u64 + 1);
+            track.title = title.to_string();
+            track.streamable = true;
+            track.stream_url = Some(format!("{}/tracks/{}/stream", host, index + 1));
+            track
+        })
+        .collect();
+
+    let archive = export::zip_tracks(&client, &tracks, vec![]).await.unwrap();
+
+    let archive = async_zip::base::read::mem::ZipFileReader::new(archive)
+        .await
+        .unwrap();
+    let names: Vec<&str> = archive
+        .file()
+        .entries()
+        .iter()
+        .map(|entry| entry.filename().as_str().unwrap())
+        .collect();
+    assert_eq!(
+        vec![
+            "01 - One.mp3",
+            "01 - One.json",
+            "02 - Two_Three.mp3",
+            "02 - Two_Three.json"
+        ],
+        names
+    );
+}
+
+#[tokio::test]
+async fn test_page_size_above_maximum_is_split() {
+    use futures::TryStreamExt;
+
+    let first: Vec<usize> = (1..=200).collect();
+    let second: Vec<usize> = (201..=400).collect();
+    let first_page = common::users_page(&first).replace(
+        r#""next_href":null"#,
+        r#""next_href":"{host}/users/1/followers?cursor=2""#,
+    );
+    let host = common::serve(vec![
+        (200, Box::leak(first_page.into_boxed_str())),
+        (200, common::users_page(&second)),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let followers = client.user(1).followers();
+    let options = PageOptions::builder().page_size(250).build();
+
+    assert_eq!(Some(200), followers.effective_page_size(&options));
+
+    let users: Vec<User> = followers.get(options, 1).try_collect().await.unwrap();
+    assert_eq!(250, users.len());
+    assert_eq!(250, users[249].id);
+}
+
+#[tokio::test]
+async fn test_tracks_by_isrc() {
+    let tracks: Vec<Track> = [Some("US-RC1-76-07839"), Some("GBAYE0601498"), None]
+        .iter()
+        .enumerate()
+        .map(|(index, isrc)| {
+            let mut track = Track::new_for_test(index as u64 + 1);
+            track.isrc = isrc.map(str::to_owned);
+            track
+        })
+        .collect();
+    let body = serde_json::to_string(&tracks).unwrap();
+    let host = common::serve(vec![(200, Box::leak(body.into_boxed_str()))]).await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+
+    let tracks = client
+        .tracks()
+        .isrc(Some("usrc17607839"))
+        .get()
+        .await
+        .unwrap();
+
+    assert_eq!(vec![1], tracks.iter().map(|t| t.id).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_exact_username() {
+    let users: Vec<serde_json::Value> = [
+        (1, "Forss Fan", None, 900),
+        (2, "FORSS", None, 10),
+        (3, "Forss", Some("Jonas Forss"), 5),
+        (4, "forss", None, 50),
+    ]
+    .iter()
+    .map(|(id, username, full_name, followers)| {
+        let mut user: serde_json::Value = serde_json::from_str(common::user(*id)).unwrap();
+        user["username"] = serde_json::json!(username);
+        user["full_name"] = serde_json::json!(full_name);
+        user["followers_count"] = serde_json::json!(followers);
+        user
+    })
+    .collect();
+    let body = serde_json::to_string(&users).unwrap();
+    let body: &'static str = Box::leak(body.into_boxed_str());
+    let host = common::serve(vec![(200, body), (200, body), (200, "[]")]).await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+
+    let user = client.users().exact_username("Forss").await.unwrap();
+    assert_eq!(Some(3), user.map(|u| u.id));
+
+    let user = client.users().exact_username("fOrSs").await.unwrap();
+    assert_eq!(Some(4), user.map(|u| u.id));
+
+    let user = client.users().exact_username("Forss").await.unwrap();
+    assert!(user.is_none());
+}
+
+#[tokio::test]
+async fn test_users_by_ids() {
+    let first: Vec<String> = (1..=50).map(|id| common::user(id).to_owned()).collect();
+    let second: Vec<String> = (51..=60).map(|id| common::user(id).to_owned()).collect();
+    let first = format!("[{}]", first.join(","));
+    let second = format!("[{}]", second.join(","));
+    let host = common::serve(vec![
+        (200, Box::leak(first.into_boxed_str())),
+        (200, Box::leak(second.into_boxed_str())),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let mut ids: Vec<usize> = (1..=61).rev().collect();
+    ids.push(60);
+
+    let users = client.users_by_ids(&ids).await.unwrap();
+
+    let expected: Vec<usize> = (1..=60).rev().collect();
+    assert_eq!(expected, users.iter().map(|u| u.id).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_secret_token_info() {
+    let host = common::serve(vec![
+        (
+            200,
+            r#"{"kind":"secret-token","token":"s-old","uri":"https://api.soundcloud.com/tracks/1/secret-token"}"#,
+        ),
+        (200, r#"{"kind":"secret-token","token":"s-new"}"#),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let mut track = Track::new_for_test(1);
+    track.permalink_url = "https://soundcloud.com/user/track".to_owned();
+
+    let secret_token = client.track(1).secret_token_info().await.unwrap();
+    assert_eq!("s-old", secret_token.token);
+
+    let secret_token = client.track(1).refresh_secret_token().await.unwrap();
+    assert_eq!(SecretToken::new_for_test("s-new"), secret_token);
+    assert_eq!(
+        "https://soundcloud.com/user/track/s-new",
+        secret_token.share_url(&track)
+    );
+}
+
+#[tokio::test]
+async fn test_pagination_error() {
+    use futures::StreamExt;
+
+    let first_page = common::users_page(&[1]).replace(
+        r#""next_href":null"#,
+        r#""next_href":"{host}/users/1/followers?cursor=2""#,
+    );
+    let host = common::serve(vec![
+        (200, Box::leak(first_page.into_boxed_str())),
+        (500, "{}"),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let results: Vec<Result<User>> = client
+        .user(1)
+        .followers()
+        .iter(Default::default())
+        .collect()
+        .await;
+
+    assert_eq!(2, results.len());
+    assert!(results[0].is_ok());
+    match results[1] {
+        Err(ref error @ Error::Pagination { ref page_url, .. }) => {
+            assert_eq!(&format!("{}/users/1/followers?cursor=2", host), page_url);
+            assert!(error.is_retryable());
+        }
+        ref other => panic!("expected pagination error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_resume_after_pagination_error() {
+    use futures::{StreamExt, TryStreamExt};
+
+    let first_page = common::users_page(&[1]).replace(
+        r#""next_href":null"#,
+        r#""next_href":"{host}/users/1/followers?page_size=1&cursor=2""#,
+    );
+    let host = common::serve(vec![
+        (200, Box::leak(first_page.into_boxed_str())),
+        (503, "{}"),
+        (200, common::users_page(&[2])),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let results: Vec<Result<User>> = client
+        .user(1)
+        .followers()
+        .iter(Default::default())
+        .collect()
+        .await;
+    let resume_url = results[1].as_ref().unwrap_err().resume_url().unwrap();
+    assert_eq!(
+        format!("{}/users/1/followers?page_size=1&cursor=2", host),
+        resume_url
+    );
+
+    let users: Vec<User> = client
+        .stream_collection(resume_url, Default::default())
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(vec![2], users.iter().map(|u| u.id).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_media_redirects() {
+    let host = common::serve_with_headers(vec![
+        (302, vec![("Location", "{host}/cdn/1")], ""),
+        (302, vec![("Location", "{host}/cdn/2")], ""),
+        (200, vec![], "audio"),
+        (302, vec![("Location", "{host}/cdn/1")], ""),
+        (302, vec![("Location", "{host}/cdn/2")], ""),
+    ])
+    .await;
+    let mut track = Track::new_for_test(1);
+    track.downloadable = true;
+    track.download_url = Some(format!("{}/tracks/1/download", host));
+
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let mut output = vec![];
+    client.download(&track, &mut output).await.unwrap();
+    assert_eq!(b"audio".to_vec(), output);
+
+    let client = Client::builder("clientid")
+        .host(&host)
+        .media_redirects(1)
+        .build()
+        .unwrap();
+    let mut output = vec![];
+    assert!(client.download(&track, &mut output).await.is_err());
+}
+
+#[tokio::test]
+async fn test_connection_pool_options() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Server keeping connections open, counting how many are opened.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let host = format!("http://{}", listener.local_addr().unwrap());
+    let connections = Arc::new(AtomicUsize::new(0));
+    let accepted = connections.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            accepted.fetch_add(1, Ordering::SeqCst);
+            tokio::spawn(async move {
+                let mut buffer = vec![0; 8192];
+                while let Ok(read) = socket.read(&mut buffer).await {
+                    if read == 0 {
+                        break;
+                    }
+                    let body = common::user(1);
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                }
+            });
+        }
+    });
+
+    let pooled = Client::builder("clientid")
+        .host(&host)
+        .pool_idle_timeout(Duration::from_secs(5))
+        .http2(false)
+        .build()
+        .unwrap();
+    pooled.user(1).get().await.unwrap();
+    pooled.user(1).get().await.unwrap();
+    assert_eq!(1, connections.load(Ordering::SeqCst));
+
+    let unpooled = Client::builder("clientid")
+        .host(&host)
+        .pool_max_idle_per_host(0)
+        .http2(false)
+        .build()
+        .unwrap();
+    unpooled.user(1).get().await.unwrap();
+    unpooled.user(1).get().await.unwrap();
+    assert_eq!(3, connections.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_hedged_fetch() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let host = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        // The first request never gets an answer, the hedged one does.
+        let (stalled, _) = listener.accept().await.unwrap();
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buffer = vec![0; 8192];
+        let _ = socket.read(&mut buffer).await;
+        let body = common::user(1);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        drop(stalled);
+    });
+    let client = Client::builder("clientid")
+        .host(host)
+        .build()
+        .unwrap()
+        .with_options(
+            RequestOptions::builder()
+                .hedge_after(Duration::from_millis(50))
+                .build(),
+        );
+
+    let user = tokio::time::timeout(Duration::from_secs(2), client.fetch::<User>(1))
+        .await
+        .expect("hedged request should win")
+        .unwrap();
+    assert_eq!(1, user.id);
+}
+
+/// Transport answering every request with a user, or the given body, recording the requests.
+#[derive(Default)]
+struct RecordingTransport {
+    requests: Arc<Mutex<Vec<reqwest::Request>>>,
+    body: Option<&'static str>,
+}
+
+impl RecordingTransport {
+    fn with_body(body: &'static str) -> Self {
+        RecordingTransport {
+            body: Some(body),
+            ..Default::default()
+        }
+    }
+}
+
+impl HttpTransport for RecordingTransport {
+    fn execute(&self, request: reqwest::Request) -> BoxFuture<'_, Result<reqwest::Response>> {
+        self.requests.lock().unwrap().push(request);
+        let response = http::Response::builder()
+            .status(200)
+            .body(self.body.unwrap_or_else(|| common::user(1)))
+            .unwrap();
+        Box::pin(async move { Ok(response.into()) })
+    }
+}
+
+/// Returns the parts of a recorded multipart request as name and value, or file name for files.
+async fn form_parts(request: reqwest::Request) -> Vec<(String, String)> {
+    let content_type = request.headers()[reqwest::header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .to_owned();
+    let boundary = content_type.split("boundary=").nth(1).unwrap();
+    let body = common::request_body(request).await;
+
+    String::from_utf8_lossy(&body)
+        .split(&format!("--{}", boundary))
+        .filter_map(|part| {
+            let (headers, content) = part.split_once("\r\n\r\n")?;
+            let param = |name: &str| {
+                let start = headers.find(&format!("; {}=\"", name))? + name.len() + 4;
+                let end = start + headers[start..].find('"')?;
+                Some(headers[start..end].to_owned())
+            };
+            let value = param("filename").unwrap_or_else(|| content.trim_end().to_owned());
+            Some((param("name")?, value))
+        })
+        .collect()
+}
+
+/// Converts the expected form parts for comparison with [`form_parts`].
+fn parts(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+    expected
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_custom_transport() {
+    let transport = RecordingTransport::default();
+    let requests = transport.requests.clone();
+    let client = Client::builder("clientid")
+        .accept_language("de-DE")
+        .transport(transport)
+        .build()
+        .unwrap();
+    let user = client.user(1).get().await.unwrap();
+    let requests = requests.lock().unwrap();
+
+    assert_eq!(1, user.id);
+    assert_eq!(1, requests.len());
+    assert_eq!("/users/1", requests[0].url().path());
+    assert_eq!("de-DE", requests[0].headers()["accept-language"]);
+}
+
+#[tokio::test]
+async fn test_sign_out() {
+    let transport = RecordingTransport::default();
+    let requests = transport.requests.clone();
+    let mut client = Client::builder("clientid")
+        .auth_token("token")
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    client.sign_out().await.unwrap();
+    assert!(!client.is_authenticated());
+    client.sign_out().await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(1, requests.len());
+    assert_eq!(reqwest::Method::POST, requests[0].method());
+    assert_eq!(SIGN_OUT_URL, requests[0].url().as_str());
+    assert_eq!(
+        Some(br#"{"access_token":"token"}"#.as_ref()),
+        requests[0].body().and_then(|body| body.as_bytes())
+    );
+}
+
+#[tokio::test]
+async fn test_repost_with_caption() {
+    let transport = RecordingTransport::default();
+    let requests = transport.requests.clone();
+    let client = Client::builder("clientid")
+        .auth_token("token")
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    client
+        .track(1)
+        .repost_with_caption("Out now!")
+        .await
+        .unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(reqwest::Method::PUT, requests[0].method());
+    assert_eq!("/me/track_reposts/1", requests[0].url().path());
+    assert_eq!(
+        Some(br#"{"caption":"Out now!"}"#.as_ref()),
+        requests[0].body().and_then(|body| body.as_bytes())
+    );
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn test_accept_encoding() {
+    let (host, requests) =
+        common::serve_recording(vec![(200, vec![], common::user(1)), (200, vec![], "audio")]).await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let mut track = Track::new_for_test(1);
+    track.streamable = true;
+    track.stream_url = Some(format!("{}/tracks/1/stream", host));
+
+    client.user(1).get().await.unwrap();
+    let mut data = vec![];
+    client.stream(&track, &mut data).await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    let accept_encoding = |request: &str| -> String {
+        request
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_at(line.find(':')?);
+                if name.eq_ignore_ascii_case("accept-encoding") {
+                    Some(value[1..].trim().to_owned())
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_default()
+    };
+    assert!(accept_encoding(&requests[0]).contains("gzip"));
+    assert_eq!("identity", accept_encoding(&requests[1]));
+}
+
+#[cfg(feature = "tower")]
+#[tokio::test]
+async fn test_tower_service() {
+    use tower_service::Service;
+
+    let host = common::serve(vec![(200, common::user(1)), (404, "{}")]).await;
+    let mut client = Client::builder("clientid").host(host).build().unwrap();
+    let request = client
+        .api_request(reqwest::Method::GET, "/users/1")
+        .unwrap();
+
+    assert_eq!(Some("client_id=clientid"), request.url().query());
+    assert!(client.call(request).await.is_ok());
+
+    let request = client
+        .api_request(reqwest::Method::GET, "/users/2")
+        .unwrap();
+
+    assert!(matches!(
+        client.call(request).await,
+        Err(Error::HttpError(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_archive_user() {
+    let mut track = Track::new_for_test(1);
+    track.title = "One/Two".to_owned();
+    track.streamable = true;
+    track.stream_url = Some("{host}/tracks/1/stream".to_owned());
+    let page = format!(
+        r#"{{"collection":[{}],"next_href":null}}"#,
+        serde_json::to_string(&track).unwrap()
+    );
+    let page: &'static str = Box::leak(page.into_boxed_str());
+    let host = common::serve(vec![(200, page), (200, "audio"), (200, page)]).await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let dir = std::env::temp_dir().join(format!("soundcloud-archive-{}", std::process::id()));
+
+    let manifest = client
+        .archive_user(1, &dir, archive::ArchiveOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(1, manifest.tracks.len());
+    assert_eq!("1 - One_Two.mp3", manifest.tracks[0].file);
+    assert_eq!(archive::AudioSource::Stream, manifest.tracks[0].source);
+    assert_eq!(5, manifest.tracks[0].size);
+    assert_eq!(
+        b"audio".to_vec(),
+        std::fs::read(dir.join("1 - One_Two.mp3")).unwrap()
+    );
+
+    // Resuming only lists the tracks, as the track has already been saved.
+    let manifest = client
+        .archive_user(1, &dir, archive::ArchiveOptions::default())
+        .await
+        .unwrap();
+    let stored = archive::Manifest::read(&dir).await.unwrap().unwrap();
+
+    assert_eq!(1, manifest.tracks.len());
+    assert_eq!(1, stored.tracks.len());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_archive_best_quality() {
+    let transcoding = |quality: &str, path: &str, mime_type: &str| {
+        serde_json::json!({
+            "url": format!("{{host}}/media/{}", path),
+            "preset": "aac_256k",
+            "quality": quality,
+            "format": { "protocol": "progressive", "mime_type": mime_type },
+        })
+    };
+    let mut track = Track::new_for_test(1);
+    track.title = "One".to_owned();
+    track.streamable = true;
+    track.stream_url = Some("{host}/tracks/1/stream".to_owned());
+    let mut json = serde_json::to_value(&track).unwrap();
+    json["media"] = serde_json::json!({
+        "transcodings": [
+            transcoding("sq", "sq", "audio/mpeg"),
+            transcoding("hq", "hq", "audio/mp4; codecs=\"mp4a.40.2\""),
+        ]
+    });
+    let page = format!(r#"{{"collection":[{}],"next_href":null}}"#, json);
+    let me = common::user(1).replacen(
+        '{',
+        r#"{"consumer_subscription":{"product":{"id":"consumer-high-tier"}},"#,
+        1,
+    );
+    let host = common::serve(vec![
+        (200, Box::leak(page.into_boxed_str())),
+        (200, Box::leak(me.into_boxed_str())),
+        (200, r#"{"url":"{host}/cdn/hq"}"#),
+        (200, "audio"),
+    ])
+    .await;
+    let client = Client::builder("clientid")
+        .host(host)
+        .auth_token("token")
+        .build()
+        .unwrap();
+    let dir =
+        std::env::temp_dir().join(format!("soundcloud-archive-quality-{}", std::process::id()));
+
+    let manifest = client
+        .archive_user(1, &dir, archive::ArchiveOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(1, manifest.tracks.len());
+    assert_eq!("1 - One.m4a", manifest.tracks[0].file);
+    assert_eq!(archive::AudioSource::Stream, manifest.tracks[0].source);
+    assert_eq!(Some(Quality::High), manifest.tracks[0].quality);
+    assert_eq!(
+        b"audio".to_vec(),
+        std::fs::read(dir.join("1 - One.m4a")).unwrap()
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_archive_update() {
+    let tracks: Vec<Track> = [(1, "2020"), (2, "2021"), (3, "2019")]
+        .iter()
+        .map(|&(id, year)| {
+            let mut track = Track::new_for_test(id);
+            track.created_at = format!("{}-01-01T00:00:00Z", year);
+            track.streamable = true;
+            track.stream_url = Some(format!("{{host}}/tracks/{}/stream", id));
+            track
+        })
+        .collect();
+    let page = |tracks: &[&Track], next: &str| -> &'static str {
+        let page = format!(
+            r#"{{"collection":{},"next_href":{}}}"#,
+            serde_json::to_string(tracks).unwrap(),
+            next
+        );
+        Box::leak(page.into_boxed_str())
+    };
+    let host = common::serve(vec![
+        (200, page(&[&tracks[0]], "null")),
+        (200, "first"),
+        // The update stops at track 3, so the next page isn't requested.
+        (200, page(&[&tracks[1], &tracks[2]], r#""{host}/next""#)),
+        (200, "second"),
+        (200, page(&[&tracks[1], &tracks[2]], "null")),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let dir = std::env::temp_dir().join(format!("soundcloud-update-{}", std::process::id()));
+
+    client
+        .archive_user(1, &dir, archive::ArchiveOptions::default())
+        .await
+        .unwrap();
+    let manifest = client.archive_update(&dir).await.unwrap();
+
+    // Track 3 is older than the watermark, so it's not saved.
+    assert_eq!(
+        vec![1, 2],
+        manifest
+            .tracks
+            .iter()
+            .map(|track| track.id)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(Some("2021-01-01T00:00:00Z"), manifest.watermark.as_deref());
+    assert!(manifest.deleted.is_empty());
+
+    let manifest = client.archive_deletions(&dir).await.unwrap();
+    assert_eq!(vec![1], manifest.deleted);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_archive_update_interrupted() {
+    let newer = common::serve(vec![(200, "two"), (200, "two")]).await;
+    let failing = common::serve(vec![(404, "{}"), (200, "three")]).await;
+    let tracks: Vec<Track> = [
+        (1, "2020", "{host}"),
+        (2, "2022", &*newer),
+        (3, "2021", &*failing),
+    ]
+    .iter()
+    .map(|&(id, year, host)| {
+        let mut track = Track::new_for_test(id);
+        track.created_at = format!("{}-01-01T00:00:00Z", year);
+        track.streamable = true;
+        track.stream_url = Some(format!("{}/tracks/{}/stream", host, id));
+        track
+    })
+    .collect();
+    let page = |tracks: &[&Track]| -> &'static str {
+        let page = format!(
+            r#"{{"collection":{},"next_href":null}}"#,
+            serde_json::to_string(tracks).unwrap()
+        );
+        Box::leak(page.into_boxed_str())
+    };
+    let host = common::serve(vec![
+        (200, page(&[&tracks[0]])),
+        (200, "one"),
+        (200, page(&[&tracks[1], &tracks[2], &tracks[0]])),
+        (200, page(&[&tracks[1], &tracks[2], &tracks[0]])),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let dir = std::env::temp_dir().join(format!(
+        "soundcloud-update-interrupted-{}",
+        std::process::id()
+    ));
+
+    client
+        .archive_user(1, &dir, archive::ArchiveOptions::default())
+        .await
+        .unwrap();
+    assert!(client.archive_update(&dir).await.is_err());
+
+    // Track 3 failed, so the watermark stays before it although the newer track 2 was saved.
+    let stored = archive::Manifest::read(&dir).await.unwrap().unwrap();
+    assert_eq!(Some("2020-01-01T00:00:00Z"), stored.watermark.as_deref());
+
+    let manifest = client.archive_update(&dir).await.unwrap();
+    let mut ids: Vec<u64> = manifest.tracks.iter().map(|track| track.id).collect();
+    ids.sort_unstable();
+    assert_eq!(vec![1, 2, 3], ids);
+    assert_eq!(Some("2022-01-01T00:00:00Z"), manifest.watermark.as_deref());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_availability_watch() {
+    use futures::{StreamExt, TryStreamExt};
+    use soundcloud::watch::{availability, Availability};
+
+    let host = common::serve(vec![
+        (200, common::track(1)),
+        (200, common::track(2)),
+        (200, common::track(1)),
+        (404, "{}"),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let changes: Vec<_> = availability(client, vec![1, 2], Duration::from_millis(1))
+        .take(1)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(1, changes.len());
+    assert_eq!(2, changes[0].track_id);
+    assert_eq!(Availability::Available, changes[0].from);
+    assert_eq!(Availability::Deleted, changes[0].to);
+}
+
+#[tokio::test]
+async fn test_availability_watch_insufficient_scope() {
+    use futures::{StreamExt, TryStreamExt};
+    use soundcloud::watch::{availability, Availability};
+
+    let host = common::serve_with_headers(vec![
+        (200, vec![], common::track(1)),
+        (
+            403,
+            vec![(
+                "WWW-Authenticate",
+                r#"Bearer error="insufficient_scope", scope="non-expiring""#,
+            )],
+            "{}",
+        ),
+    ])
+    .await;
+    let client = Client::builder("clientid")
+        .host(host)
+        .auth_token("token")
+        .build()
+        .unwrap();
+    let changes: Vec<_> = availability(client, vec![1], Duration::from_millis(1))
+        .take(1)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(Availability::Available, changes[0].from);
+    assert_eq!(Availability::Private, changes[0].to);
+}
+
+#[tokio::test]
+async fn test_export_comments_ndjson() {
+    let comment = |id: usize| {
+        format!(
+            r#"{{"id":{0},"uri":"https://api.soundcloud.com/comments/{0}","created_at":"2020-01-01T00:00:00Z","body":"Nice","timestamp":1000,"user_id":1,"user":{1},"track_id":1}}"#,
+            id,
+            common::user(1)
+        )
+    };
+    let first = format!(
+        r#"{{"collection":[{}],"next_href":"{{host}}/tracks/1/comments?cursor=2"}}"#,
+        comment(1)
+    );
+    let second = format!(r#"{{"collection":[{}],"next_href":null}}"#, comment(2));
+    let host = common::serve(vec![
+        (200, Box::leak(first.into_boxed_str())),
+        (503, "{}"),
+        (200, Box::leak(second.into_boxed_str())),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let mut output = vec![];
+    let count = client
+        .track(1)
+        .comments()
+        .export_ndjson(&mut output)
+        .await
+        .unwrap();
+
+    let lines: Vec<serde_json::Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(2, count);
+    assert_eq!(2, lines.len());
+    assert_eq!(2, lines[1]["id"]);
+    assert_eq!("User", lines[1]["user_username"]);
+    assert_eq!("Nice", lines[1]["body"]);
+}
+
+#[tokio::test]
+async fn test_playlists_featuring_user() {
+    let page = |json: String| -> &'static str {
+        let page = format!(r#"{{"collection":{},"next_href":null}}"#, json);
+        Box::leak(page.into_boxed_str())
+    };
+    let playlists = |ids: &[u64]| {
+        let playlists: Vec<&str> = ids.iter().map(|id| common::playlist(*id)).collect();
+        page(format!("[{}]", playlists.join(",")))
+    };
+    let host = common::serve(vec![
+        (
+            200,
+            page(format!("[{},{}]", common::track(1), common::track(2))),
+        ),
+        (200, playlists(&[10, 11])),
+        (200, playlists(&[11, 12])),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let playlists = analytics::playlists_featuring_user(&client, 1, 1)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        vec![10, 11, 12],
+        playlists
+            .iter()
+            .map(|playlist| playlist.id)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn test_media_transfer_resumes_with_fresh_url() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let host = format!("http://{}", listener.local_addr().unwrap());
+    let base = host.clone();
+    let requests = Arc::new(Mutex::new(vec![]));
+    let received = requests.clone();
+    tokio::spawn(async move {
+        let redirect = |path: &str| {
+            format!(
+                "HTTP/1.1 302 Found\r\nLocation: {}/{}?Expires=4102444800\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                base, path
+            )
+        };
+        let responses = vec![
+            redirect("cdn1"),
+            // The connection drops after half of the announced body.
+            "HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: close\r\n\r\naudio".to_owned(),
+            // The signed url expired.
+            "HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_owned(),
+            redirect("cdn2"),
+            "HTTP/1.1 206 Partial Content\r\nContent-Length: 5\r\nConnection: close\r\n\r\n12345"
+                .to_owned(),
+        ];
+        for response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0; 8192];
+            let read = socket.read(&mut buffer).await.unwrap();
+            let request = String::from_utf8_lossy(&buffer[..read]).to_lowercase();
+            received.lock().unwrap().push(request);
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let mut track = Track::new_for_test(1);
+    track.streamable = true;
+    track.stream_url = Some(format!("{}/tracks/1/stream", host));
+
+    let mut output = vec![];
+    client.stream(&track, &mut output).await.unwrap();
+
+    assert_eq!(b"audio12345".to_vec(), output);
+    let requests = requests.lock().unwrap();
+    assert!(requests[2].starts_with("get /cdn1"));
+    assert!(requests[2].contains("range: bytes=5-"));
+    assert!(requests[3].starts_with("get /tracks/1/stream"));
+    assert!(requests[4].starts_with("get /cdn2"));
+}
+
+#[tokio::test]
+async fn test_media_urls_kept_per_token() {
+    let (host, requests) = common::serve_recording(vec![
+        (
+            302,
+            vec![("Location", "{host}/cdn1?Expires=4102444800")],
+            "",
+        ),
+        (200, vec![], "free"),
+        (
+            302,
+            vec![("Location", "{host}/cdn2?Expires=4102444800")],
+            "",
+        ),
+        (200, vec![], "goplus"),
+        (200, vec![], "free"),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let mut authenticated = client.clone();
+    authenticated.authenticate_with_token("token".to_owned());
+    let mut track = Track::new_for_test(1);
+    track.streamable = true;
+    track.stream_url = Some(format!("{}/tracks/1/stream", host));
+
+    let mut output = vec![];
+    client.stream(&track, &mut output).await.unwrap();
+    assert_eq!(b"free".to_vec(), output);
+    let mut output = vec![];
+    authenticated.stream(&track, &mut output).await.unwrap();
+    assert_eq!(b"goplus".to_vec(), output);
+    let mut output = vec![];
+    client.stream(&track, &mut output).await.unwrap();
+    assert_eq!(b"free".to_vec(), output);
+
+    let requests = requests.lock().unwrap();
+    assert!(requests[2].starts_with("GET /tracks/1/stream"));
+    assert!(requests[4].starts_with("GET /cdn1"));
+}
+
+#[tokio::test]
+async fn test_subscription() {
+    let me = common::user(1).replacen(
+        '{',
+        r#"{"consumer_subscription":{"product":{"id":"consumer-high-tier"}},"#,
+        1,
+    );
+    let host = common::serve(vec![
+        (200, Box::leak(me.into_boxed_str())),
+        (200, common::user(1)),
+    ])
+    .await;
+    let client = Client::builder("clientid")
+        .host(host)
+        .auth_token("token")
+        .build()
+        .unwrap();
+
+    assert_eq!(Subscription::GoPlus, client.subscription().await.unwrap());
+    assert_eq!(Subscription::Free, client.subscription().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_track_likers_with_timestamps() {
+    use futures::TryStreamExt;
+
+    let page = format!(
+        r#"{{"collection":[{{"created_at":"2020-01-01T00:00:00Z","user":{}}},{{"user":{}}}],"next_href":null}}"#,
+        common::user(1),
+        common::user(2)
+    );
+    let page: &'static str = Box::leak(page.into_boxed_str());
+    let host = common::serve(vec![(200, page), (200, common::users_page(&[1, 2]))]).await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let likers: Vec<TrackLiker> = client
+        .track(1)
+        .likers()
+        .iter_with_timestamps(Default::default())
+        .try_collect()
+        .await
+        .unwrap();
+    let users: Vec<User> = client
+        .track(1)
+        .likers()
+        .iter(Default::default())
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        Some("2020-01-01T00:00:00Z"),
+        likers[0].created_at.as_deref()
+    );
+    assert_eq!(None, likers[1].created_at);
+    assert_eq!(
+        vec![1, 2],
+        users.iter().map(|user| user.id).collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn test_snapshot_store() {
+    let user = common::user(1).replacen('{', r#"{"followers_count":10,"#, 1);
+    let user: &'static str = Box::leak(user.into_boxed_str());
+    let tracks = format!(
+        r#"{{"collection":[{},{}],"next_href":null}}"#,
+        common::track(1).replacen('{', r#"{"likes_count":3,"#, 1),
+        common::track(2).replacen('{', r#"{"likes_count":4,"#, 1)
+    );
+    let tracks: &'static str = Box::leak(tracks.into_boxed_str());
+    let host = common::serve(vec![(200, user), (200, tracks)]).await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let path = std::env::temp_dir().join(format!("soundcloud-stats-{}.json", std::process::id()));
+
+    let mut store = analytics::SnapshotStore::open(client.clone(), &path)
+        .await
+        .unwrap();
+    let snapshot = store.record(1).await.unwrap();
+
+    assert_eq!(Some(10), snapshot.followers_count);
+    assert_eq!(7, snapshot.likes_count);
+
+    let store = analytics::SnapshotStore::open(client, &path).await.unwrap();
+
+    assert_eq!(vec![snapshot], store.history(1));
+    assert!(store.history(2).is_empty());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_search_dedupes_across_kinds() {
+    use futures::TryStreamExt;
+
+    let kind = |kind: &str, json: &str| format!(r#"{{"kind":"{}",{}"#, kind, &json[1..]);
+    let playlist = common::playlist(1).replacen(
+        r#""track_count":0"#,
+        &format!(r#""track_count":1,"tracks":[{}]"#, common::track(2)),
+        1,
+    );
+    let page = format!(
+        r#"{{"collection":[{},{},{},{},{}]}}"#,
+        playlist,
+        kind("track", common::track(2)),
+        kind("track", common::track(3)),
+        kind("user", common::user(4)),
+        kind("track", common::track(3)),
+    );
+    let page: &'static str = Box::leak(page.into_boxed_str());
+    let host = common::serve(vec![(200, page); 6]).await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+
+    let all: Vec<SearchResult> = client
+        .search("lofi")
+        .iter(Default::default())
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(5, all.len());
+
+    // Results are deduplicated in every kind of stream.
+    let search = client.search("lofi").dedupe(true);
+    let streams = vec![
+        search.iter(Default::default()),
+        search.iter_lenient(Default::default()),
+        search.iter_streaming(Default::default()),
+        search.iter_resilient(Default::default(), RetryPolicy::default()),
+        soundcloud::crawler::sharded(&search, Default::default(), 2),
+    ];
+    for stream in streams {
+        let deduped: Vec<SearchResult> = stream.try_collect().await.unwrap();
+        let urns: Vec<String> = deduped
+            .iter()
+            .filter_map(|result| result.urn())
+            .map(|urn| urn.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "soundcloud:playlists:1",
+                "soundcloud:tracks:3",
+                "soundcloud:users:4"
+            ],
+            urns
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_skip_pages_and_start_from() {
+    use futures::TryStreamExt;
+
+    let host = common::serve(vec![
+        (
+            200,
+            common::users_page_with_next(&[1, 2], Some("/users/1/followers?cursor=2")),
+        ),
+        (
+            200,
+            common::users_page_with_next(&[3, 4], Some("/users/1/followers?cursor=3")),
+        ),
+        (200, common::users_page_with_next(&[5], None)),
+        (200, common::users_page_with_next(&[5], None)),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let followers = client.user(1).followers();
+
+    let skipped: Vec<usize> = followers
+        .skip_pages(Default::default(), 1)
+        .map_ok(|user| user.id)
+        .try_collect()
+        .await
+        .unwrap();
+    let started: Vec<usize> = followers
+        .start_from(&format!("{}/users/1/followers?cursor=3", host))
+        .map_ok(|user| user.id)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(vec![3, 4, 5], skipped);
+    assert_eq!(vec![5], started);
+}
+
+#[tokio::test]
+async fn test_sharded_crawl_keeps_order() {
+    use futures::TryStreamExt;
+
+    let host = common::serve(vec![
+        (
+            200,
+            common::users_page_with_next(&[1, 2], Some("/users/1/followers?cursor=2")),
+        ),
+        (
+            200,
+            common::users_page_with_next(&[3, 4], Some("/users/1/followers?cursor=3")),
+        ),
+        (200, common::users_page_with_next(&[5], None)),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+
+    let ids: Vec<usize> = crawler::sharded(&client.user(1).followers(), Default::default(), 3)
+        .map_ok(|user| user.id)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(vec![1, 2, 3, 4, 5], ids);
+}
+
+#[tokio::test]
+async fn test_sharded_crawl_ends_after_error() {
+    use futures::StreamExt;
+
+    let host = common::serve(vec![
+        (
+            200,
+            r#"{"collection":[],"next_href":"{host}/users/1/followers?cursor=2"}"#,
+        ),
+        (404, "{}"),
+    ])
+    .await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+
+    let results: Vec<_> = crawler::sharded(&client.user(1).followers(), Default::default(), 2)
+        .collect()
+        .await;
+
+    assert_eq!(1, results.len());
+    assert_eq!(
+        Some(format!("{}/users/1/followers?cursor=2", host).as_str()),
+        results[0].as_ref().unwrap_err().resume_url()
+    );
+}
+
+#[tokio::test]
+async fn test_max_response_size() {
+    let host = common::serve(vec![(200, common::user(1)), (200, common::user(1))]).await;
+    let limited = Client::builder("clientid")
+        .host(&host)
+        .max_response_size(16)
+        .build()
+        .unwrap();
+    let client = Client::builder("clientid")
+        .host(&host)
+        .max_response_size(64 * 1024)
+        .build()
+        .unwrap();
+
+    assert!(matches!(
+        limited.user(1).get().await,
+        Err(Error::ResponseTooLarge(16))
+    ));
+    let (user, meta) = client.fetch_with_meta::<User>(1).await.unwrap();
+    assert_eq!(1, user.id);
+    assert_eq!("/users/1", meta.url.path());
+}
+
+#[tokio::test]
+async fn test_iter_streaming_yields_items_before_page_ends() {
+    use futures::channel::oneshot;
+    use futures::StreamExt;
+
+    let (first_item, received) = oneshot::channel::<()>();
+    let host = common::serve_raw(|host| {
+        let head = format!(r#"{{"collection":[ {},"#, common::user(1));
+        let tail = format!(
+            r#" {} ],"next_href":"{}/users/1/followers?cursor=2","total_results":null}}"#,
+            common::user(2).replace(r#""username""#, r#""note":"a \"]},[\\","username""#),
+            host
+        );
+        let last = format!(r#"{{"next_href":null,"collection":[{}]}}"#, common::user(3));
+        let header = |length: usize| {
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                length
+            )
+        };
+
+        let first = format!("{}{}", header(head.len() + tail.len()), head);
+        // The rest of the page is only sent once the first item has been yielded.
+        let rest: Vec<Vec<u8>> = tail.as_bytes().chunks(5).map(<[u8]>::to_vec).collect();
+        let page = futures::stream::once(async move { first.into_bytes() })
+            .chain(
+                futures::stream::once(async move {
+                    received.await.unwrap();
+                    futures::stream::iter(rest)
+                })
+                .flatten(),
+            )
+            .boxed();
+        vec![page, common::raw(format!("{}{}", header(last.len()), last))]
+    })
+    .await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let followers = client.user(1).followers();
+    let mut users = followers.iter_streaming(Default::default());
+
+    let first = tokio::time::timeout(Duration::from_secs(2), users.next())
+        .await
+        .expect("first item should arrive before the page ends");
+    assert_eq!(1, first.unwrap().unwrap().id);
+    first_item.send(()).unwrap();
+
+    let rest: Vec<usize> = users.map(|user| user.unwrap().id).collect().await;
+    assert_eq!(vec![2, 3], rest);
+}
+
+#[tokio::test]
+async fn test_iter_streaming_max_response_size() {
+    use futures::channel::oneshot;
+    use futures::StreamExt;
+
+    let (first_item, received) = oneshot::channel::<()>();
+    let head = format!(r#"{{"collection":[ {},"#, common::user(1));
+    let limit = head.len() as u64 + 16;
+    let host = common::serve_raw(move |_| {
+        let tail = format!(r#" {} ],"next_href":null}}"#, common::user(2));
+        // Without a content length, the limit can only be checked while the body is read.
+        let first = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nConnection: close\r\n\r\n{}",
+            head
+        );
+        let page = futures::stream::once(async move { first.into_bytes() })
+            .chain(futures::stream::once(async move {
+                received.await.unwrap();
+                tail.into_bytes()
+            }))
+            .boxed();
+        vec![page]
+    })
+    .await;
+    let client = Client::builder("clientid")
+        .host(host)
+        .max_response_size(limit)
+        .build()
+        .unwrap();
+    let followers = client.user(1).followers();
+    let mut users = followers.iter_streaming(Default::default());
+
+    let first = tokio::time::timeout(Duration::from_secs(2), users.next())
+        .await
+        .expect("first item should arrive before the page ends");
+    assert_eq!(1, first.unwrap().unwrap().id);
+    first_item.send(()).unwrap();
+
+    match users.next().await {
+        Some(Err(Error::Pagination { source, .. })) => {
+            assert!(matches!(*source, Error::ResponseTooLarge(size) if size == limit))
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[tokio::test]
+async fn test_authentication_required() {
+    let host = common::serve(vec![(401, "{}"), (401, "{}")]).await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let no_params: Option<&[(&str, &str)]> = None;
+
+    assert!(!client.is_authenticated());
+    assert!(matches!(
+        client.get("/me/likes/tracks", no_params).await,
+        Err(Error::AuthenticationRequired(ref endpoint)) if endpoint == "/me/likes/tracks"
+    ));
+    // A rejected client id isn't mistaken for a missing token.
+    assert!(matches!(
+        client.get("/tracks/1", no_params).await,
+        Err(Error::HttpError(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_insufficient_scope() {
+    let host = common::serve_with_headers(vec![
+        (
+            403,
+            vec![(
+                "WWW-Authenticate",
+                r#"Bearer error="insufficient_scope", scope="non-expiring""#,
+            )],
+            "{}",
+        ),
+        (
+            403,
+            vec![],
+            r#"{"error":"insufficient_scope","scope":"upload non-expiring"}"#,
+        ),
+        (403, vec![], r#"{"error":"forbidden"}"#),
+    ])
+    .await;
+    let client = Client::builder("clientid")
+        .host(host)
+        .auth_token("token")
+        .build()
+        .unwrap();
+    assert!(matches!(
+        client.put("/me/likes/tracks/1").await,
+        Err(Error::InsufficientScope { ref required }) if required == &["non-expiring"]
+    ));
+    assert!(matches!(
+        client.put("/me/likes/tracks/1").await,
+        Err(Error::InsufficientScope { ref required }) if required == &["upload", "non-expiring"]
+    ));
+    assert!(matches!(
+        client.put("/me/likes/tracks/1").await,
+        Err(Error::HttpError(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_session_manager() {
+    let host = common::serve(vec![(200, common::user(2)), (200, common::user(1))]).await;
+    let client = Client::builder("clientid").host(host).build().unwrap();
+    let mut sessions = SessionManager::new(client);
+
+    assert_eq!(2, sessions.sign_in("label").await.unwrap().id);
+    assert_eq!(1, sessions.sign_in("artist").await.unwrap().id);
+
+    assert_eq!(vec![1, 2], sessions.accounts().collect::<Vec<_>>());
+    assert!(sessions.with_account(1).unwrap().is_authenticated());
+    assert!(sessions.with_account(3).is_none());
+    assert!(sessions.remove(2).is_some());
+    assert_eq!(vec![1], sessions.accounts().collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_set_artwork() {
+    let transport = RecordingTransport::with_body(common::track(1));
+    let requests = transport.requests.clone();
+    let client = Client::builder("clientid")
+        .auth_token("token")
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    let track = client
+        .track(1)
+        .set_artwork(b"\x89PNG\r\n".to_vec())
+        .await
+        .unwrap();
+
+    assert_eq!(1, track.id);
+    let request = requests.lock().unwrap().remove(0);
+    assert_eq!(reqwest::Method::PUT, request.method());
+    assert_eq!("/tracks/1", request.url().path());
+    assert_eq!(
+        parts(&[("track[artwork_data]", "artwork.png")]),
+        form_parts(request).await
+    );
+}
+
+#[tokio::test]
+async fn test_upload_track_retries_parts() {
+    use futures::TryStreamExt;
+    use soundcloud::upload::{TrackUpload, UploadEvent, MIN_PART_SIZE};
+
+    let host = common::serve_with_headers(vec![
+        (200, vec![], r#"{"quota":{"upload_seconds_left":3600}}"#),
+        (
+            200,
+            vec![],
+            r#"{"uid":"u1","parts":[{"part_number":1,"url":"{host}/part/1"},{"part_number":2,"url":"{host}/part/2"}]}"#,
+        ),
+        (500, vec![], "{}"),
+        (200, vec![("ETag", "\"e1\"")], ""),
+        (200, vec![("ETag", "\"e2\"")], ""),
+        (200, vec![], "{}"),
+        (201, vec![], common::track(1)),
+    ])
+    .await;
+    let client = Client::builder("clientid")
+        .host(host)
+        .auth_token("token")
+        .build()
+        .unwrap();
+    let size = MIN_PART_SIZE + 4;
+    let data = io::Cursor::new(vec![0; size as usize]);
+    let mut upload = TrackUpload::new("Live set", "set.mp3", data);
+    upload.part_size(MIN_PART_SIZE).retry_policy(
+        RetryPolicy::builder()
+            .initial_backoff(Duration::from_millis(1))
+            .build(),
+    );
+
+    let events: Vec<UploadEvent> = client.upload_track(upload).try_collect().await.unwrap();
+
+    assert_eq!(4, events.len());
+    let progress: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            UploadEvent::PartUploaded { bytes_sent, .. } => Some(*bytes_sent),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(vec![MIN_PART_SIZE, size], progress);
+    match &events[3] {
+        UploadEvent::Finished(handle) => {
+            assert_eq!("u1", handle.uid);
+            assert_eq!(1, handle.track.id);
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[tokio::test]
+async fn test_upload_rejects_small_parts() {
+    use futures::TryStreamExt;
+    use soundcloud::upload::{TrackUpload, UploadEvent};
+
+    let transport = RecordingTransport::default();
+    let requests = transport.requests.clone();
+    let client = Client::builder("clientid")
+        .auth_token("token")
+        .transport(transport)
+        .build()
+        .unwrap();
+    let mut upload = TrackUpload::new(
+        "Live set",
+        "set.mp3",
+        io::Cursor::new(b"0123456789".to_vec()),
+    );
+    upload.part_size(6);
+
+    let result: Result<Vec<UploadEvent>> = client.upload_track(upload).try_collect().await;
+
+    assert!(matches!(result, Err(Error::InvalidPartSize(6))));
+    assert!(requests.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_upload_wait_until_ready() {
+    use futures::TryStreamExt;
+    use soundcloud::upload::{TrackUpload, UploadEvent};
+
+    let processing: &'static str = Box::leak(
+        common::track(1)
+            .replace(
+                r#""streamable":true"#,
+                r#""state":"processing","streamable":false"#,
+            )
+            .into_boxed_str(),
+    );
+    let finished: &'static str = Box::leak(
+        common::track(1)
+            .replace(
+                r#""streamable":true"#,
+                r#""state":"finished","streamable":true"#,
+            )
+            .into_boxed_str(),
+    );
+    let host = common::serve_with_headers(vec![
+        (200, vec![], r#"{"quota":{"upload_seconds_left":3600}}"#),
+        (
+            200,
+            vec![],
+            r#"{"uid":"u1","parts":[{"part_number":1,"url":"{host}/part/1"}]}"#,
+        ),
+        (200, vec![("ETag", "\"e1\"")], ""),
+        (200, vec![], "{}"),
+        (201, vec![], processing),
+        (200, vec![], processing),
+        (200, vec![], finished),
+    ])
+    .await;
+    let client = Client::builder("clientid")
+        .host(host)
+        .auth_token("token")
+        .build()
+        .unwrap();
+
+    let events: Vec<UploadEvent> = client
+        .upload_track(TrackUpload::new(
+            "Live set",
+            "set.mp3",
+            io::Cursor::new(b"0123".to_vec()),
+        ))
+        .try_collect()
+        .await
+        .unwrap();
+    let mut handle = match events.into_iter().last() {
+        Some(UploadEvent::Finished(handle)) => handle,
+        event => panic!("unexpected event {:?}", event),
+    };
+    assert_eq!(Some(TrackState::Processing), handle.track.state);
+
+    let track = handle
+        .poll_interval(Duration::from_millis(1))
+        .wait_until_ready(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    assert_eq!(Some(TrackState::Finished), track.state);
+}
+
+#[tokio::test]
+async fn test_upload_quota_exceeded() {
+    use futures::TryStreamExt;
+    use soundcloud::upload::{TrackUpload, UploadEvent};
+
+    let host = common::serve(vec![(
+        200,
+        r#"{"quota":{"unlimited_upload_quota":false,"upload_seconds_used":10200,"upload_seconds_left":600}}"#,
+    )])
+    .await;
+    let client = Client::builder("clientid")
+        .host(host)
+        .auth_token("token")
+        .build()
+        .unwrap();
+    let mut upload = TrackUpload::new("Live set", "set.mp3", io::Cursor::new(b"0123".to_vec()));
+    upload.length(Duration::from_secs(7200));
+
+    let result: Result<Vec<UploadEvent>> = client.upload_track(upload).try_collect().await;
+
+    match result {
+        Err(Error::QuotaExceeded(remaining)) => assert_eq!(Duration::from_secs(600), remaining),
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[tokio::test]
+async fn test_upload_without_quota() {
+    use futures::TryStreamExt;
+    use soundcloud::upload::{TrackUpload, UploadEvent};
+
+    let host = common::serve_with_headers(vec![
+        (200, vec![], common::user(1)),
+        (200, vec![], common::user(1)),
+        (
+            200,
+            vec![],
+            r#"{"uid":"u1","parts":[{"part_number":1,"url":"{host}/part/1"}]}"#,
+        ),
+        (200, vec![("ETag", "\"e1\"")], ""),
+        (200, vec![], "{}"),
+        (201, vec![], common::track(1)),
+    ])
+    .await;
+    let client = Client::builder("clientid")
+        .host(host)
+        .auth_token("token")
+        .build()
+        .unwrap();
+    assert_eq!(None, client.quota().await.unwrap());
+
+    let mut upload = TrackUpload::new("Live set", "set.mp3", io::Cursor::new(b"0123".to_vec()));
+    upload.length(Duration::from_secs(7200));
+    let events: Vec<UploadEvent> = client.upload_track(upload).try_collect().await.unwrap();
+
+    assert!(matches!(events.last(), Some(UploadEvent::Finished(_))));
+}
+
+#[tokio::test]
+async fn test_update_playlist() {
+    let transport = RecordingTransport::with_body(common::playlist(1));
+    let requests = transport.requests.clone();
+    let client = Client::builder("clientid")
+        .auth_token("token")
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    let playlist = client
+        .playlist(1)
+        .update(PlaylistUpdate {
+            title: Some("Playlist 1".to_owned()),
+            sharing: Some(Sharing::Private),
+            artwork: Some(FileUpload::new("cover.png", b"\x89PNG\r\n".to_vec())),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!("Playlist 1", playlist.title);
+    let request = requests.lock().unwrap().remove(0);
+    assert_eq!(reqwest::Method::PUT, request.method());
+    assert_eq!("/playlists/1", request.url().path());
+    assert_eq!(
+        parts(&[
+            ("playlist[title]", "Playlist 1"),
+            ("playlist[sharing]", "private"),
+            ("playlist[artwork_data]", "cover.png"),
+        ]),
+        form_parts(request).await
+    );
+}
+
+#[tokio::test]
+async fn test_post_comment_paced() {
+    let host = common::serve(vec![(201, common::comment(1)), (201, common::comment(2))]).await;
+    let client = Client::builder("clientid")
+        .host(host)
+        .auth_token("token")
+        .comment_interval(Duration::from_millis(200))
+        .build()
+        .unwrap();
+    let comments = client.track(1).comments();
+
+    let start = std::time::Instant::now();
+    let first = comments.post("Nice", Some(1000)).await.unwrap();
+    let second = comments.post("Nice", None).await.unwrap();
+
+    assert_eq!((1, 2), (first.id, second.id));
+    assert!(start.elapsed() >= Duration::from_millis(200));
+}
+
+#[tokio::test]
+async fn test_post_comment_captcha_required() {
+    let host = common::serve(vec![(
+        422,
+        r#"{"errors":[{"error_message":"Captcha required"}]}"#,
+    )])
+    .await;
+    let client = Client::builder("clientid")
+        .host(host)
+        .auth_token("token")
+        .build()
+        .unwrap();
+
+    let result = client.track(1).comments().post("Nice", None).await;
+
+    assert!(matches!(result, Err(Error::CaptchaRequired)));
+}
+
+#[tokio::test]
+async fn test_post_comment_rejected() {
+    let host = common::serve(vec![(
+        422,
+        r#"{"errors":[{"error_message":"Body mentions no captcha"}]}"#,
+    )])
+    .await;
+    let client = Client::builder("clientid")
+        .host(host)
+        .auth_token("token")
+        .build()
+        .unwrap();
+
+    let result = client.track(1).comments().post("Nice", None).await;
+
+    assert!(matches!(result, Err(Error::HttpError(ref error))
+        if error.status() == Some(reqwest::StatusCode::UNPROCESSABLE_ENTITY)));
+}
+
+#[tokio::test]
+async fn test_waveform() {
+    let host = common::serve(vec![(
+        200,
+        r#"{"width":3,"height":140,"samples":[0,70,140]}"#,
+    )])
+    .await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let mut track = Track::new_for_test(1);
+    track.waveform_url = format!("{}/waveform.png", host);
+
+    let waveform = client.waveform(&track).await.unwrap();
+
+    assert_eq!(vec![0, 70, 140], waveform.samples);
+    assert_eq!(vec![0.0, 0.5, 1.0], waveform.normalized());
+}
+
+#[cfg(feature = "waveform-png")]
+#[tokio::test]
+async fn test_waveform_png_fallback() {
+    // A 3x4 image whose transparent pixels draw amplitudes of 1, 3 and 0.
+    const PNG: &[u8] = b"\x89\x50\x4e\x47\x0d\x0a\x1a\x0a\x00\x00\x00\x0d\x49\x48\x44\x52\x00\x00\x00\x03\x00\x00\x00\x04\x08\x04\x00\x00\x00\xe1\x24\x4d\x8c\x00\x00\x00\x1f\x49\x44\x41\x54\x78\x9c\x63\x38\xf1\xff\x04\xc3\x89\xff\x8c\x27\x18\x80\xe0\x3f\x13\xc3\x7f\x10\xcd\x02\x62\x33\x30\x00\x00\x98\x53\x08\x23\xc5\x71\xf2\x14\x00\x00\x00\x00\x49\x45\x4e\x44\xae\x42\x60\x82";
+
+    let host = common::serve_raw(|_| {
+        let header = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            PNG.len()
+        );
+        vec![
+            common::raw("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"),
+            common::raw([header.as_bytes(), PNG].concat()),
+        ]
+    })
+    .await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let mut track = Track::new_for_test(1);
+    track.waveform_url = format!("{}/waveform.png", host);
+
+    let waveform = client.waveform(&track).await.unwrap();
+
+    assert_eq!((3, 4), (waveform.width, waveform.height));
+    assert_eq!(vec![1, 3, 0], waveform.samples);
+}
+
+#[cfg(feature = "waveform-png")]
+#[tokio::test]
+async fn test_waveform_png_truncated() {
+    const PNG: &[u8] = b"\x89\x50\x4e\x47\x0d\x0a\x1a\x0a\x00\x00\x00\x0d\x49\x48\x44\x52\x00\x00\x00\x03\x00\x00\x00\x04\x08\x04\x00\x00\x00\xe1\x24\x4d\x8c\x00\x00\x00\x1f\x49\x44\x41\x54\x78\x9c\x63\x38\xf1\xff\x04\xc3\x89\xff\x8c\x27\x18\x80\xe0\x3f\x13\xc3\x7f\x10\xcd\x02\x62\x33\x30\x00\x00\x98\x53\x08\x23\xc5\x71\xf2\x14\x00\x00\x00\x00\x49\x45\x4e\x44\xae\x42\x60\x82";
+    // The image data ends after the first scanlines.
+    let truncated = &PNG[..52];
+
+    let host = common::serve_raw(|_| {
+        let header = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            truncated.len()
+        );
+        vec![
+            common::raw("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"),
+            common::raw([header.as_bytes(), truncated].concat()),
+        ]
+    })
+    .await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let mut track = Track::new_for_test(1);
+    track.waveform_url = format!("{}/waveform.png", host);
+
+    let result = client.waveform(&track).await;
+
+    assert!(matches!(result, Err(Error::InvalidWaveform(_))));
+}
+
+struct CountingProvider(Arc<Mutex<usize>>);
+
+impl ClientIdProvider for CountingProvider {
+    fn client_id(&self) -> BoxFuture<'_, Result<String>> {
+        *self.0.lock().unwrap() += 1;
+        Box::pin(async { Ok("rotated".to_owned()) })
+    }
+}
+
+#[tokio::test]
+async fn test_client_id_rotated_on_rejected_request() {
+    let host = common::serve(vec![(401, "{}"), (200, common::user(1))]).await;
+    let rotations = Arc::new(Mutex::new(0));
+    let client = Client::builder("clientid")
+        .host(host)
+        .client_id_provider(CountingProvider(rotations.clone()))
+        .build()
+        .unwrap();
+
+    let user = client.user(1).get().await.unwrap();
+
+    assert_eq!(1, user.id);
+    assert_eq!(1, *rotations.lock().unwrap());
+    assert_eq!("clientid", client.client_id());
+    assert_eq!("rotated", client.current_client_id());
+}
+
+#[tokio::test]
+async fn test_client_id_kept_on_rejected_token() {
+    let host = common::serve(vec![(
+        401,
+        r#"{"errors": [{"error_message": "invalid_token"}]}"#,
+    )])
+    .await;
+    let rotations = Arc::new(Mutex::new(0));
+    let mut client = Client::builder("clientid")
+        .host(host)
+        .client_id_provider(CountingProvider(rotations.clone()))
+        .build()
+        .unwrap();
+    client.authenticate_with_token("token".to_owned());
+
+    let result = client.user(1).get().await;
+
+    assert!(matches!(result, Err(Error::HttpError(ref error))
+        if error.status() == Some(reqwest::StatusCode::UNAUTHORIZED)));
+    assert_eq!(0, *rotations.lock().unwrap());
+    assert_eq!("clientid", client.current_client_id());
+}
+
+#[tokio::test]
+async fn test_client_id_rotated_on_rejected_client_id_with_token() {
+    let host = common::serve(vec![
+        (
+            401,
+            r#"{"errors": [{"error_message": "invalid client_id"}]}"#,
+        ),
+        (200, common::user(1)),
+    ])
+    .await;
+    let rotations = Arc::new(Mutex::new(0));
+    let mut client = Client::builder("clientid")
+        .host(host)
+        .client_id_provider(CountingProvider(rotations.clone()))
+        .build()
+        .unwrap();
+    client.authenticate_with_token("token".to_owned());
+
+    assert_eq!(1, client.user(1).get().await.unwrap().id);
+    assert_eq!(1, *rotations.lock().unwrap());
+}
+
+#[tokio::test]
+async fn test_update_track() {
+    let transport = RecordingTransport::with_body(common::track(1));
+    let requests = transport.requests.clone();
+    let client = Client::builder("clientid")
+        .auth_token("token")
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    let track = client
+        .track(1)
+        .update(TrackUpdate {
+            title: Some("Track 1".to_owned()),
+            tags: Some("live set".to_owned()),
+            sharing: Some(Sharing::Private),
+            downloadable: Some(false),
+            artwork: Some(FileUpload::new("cover.jpg", b"\xff\xd8".to_vec())),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(1, track.id);
+    let request = requests.lock().unwrap().remove(0);
+    assert_eq!(reqwest::Method::PUT, request.method());
+    assert_eq!("/tracks/1", request.url().path());
+    assert_eq!(
+        parts(&[
+            ("track[title]", "Track 1"),
+            ("track[tag_list]", "live set"),
+            ("track[sharing]", "private"),
+            ("track[downloadable]", "false"),
+            ("track[artwork_data]", "cover.jpg"),
+        ]),
+        form_parts(request).await
+    );
+}
+
+#[tokio::test]
+async fn test_delete_track() {
+    let transport = RecordingTransport::default();
+    let requests = transport.requests.clone();
+    let client = Client::builder("clientid")
+        .auth_token("token")
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    client.track(1).delete().await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(1, requests.len());
+    assert_eq!(reqwest::Method::DELETE, requests[0].method());
+    assert_eq!("/tracks/1", requests[0].url().path());
+}
+
+#[tokio::test]
+async fn test_delete_comment() {
+    let transport = RecordingTransport::default();
+    let requests = transport.requests.clone();
+    let client = Client::builder("clientid")
+        .auth_token("token")
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    client.track(1).comments().delete_comment(2).await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(1, requests.len());
+    assert_eq!(reqwest::Method::DELETE, requests[0].method());
+    assert_eq!("/tracks/1/comments/2", requests[0].url().path());
+}
+
+#[tokio::test]
+async fn test_pending_comments() {
+    use futures::TryStreamExt;
+
+    let page: &'static str = Box::leak(
+        format!(
+            r#"{{"collection":[{},{}],"next_href":null}}"#,
+            common::comment(1),
+            common::comment(2)
+        )
+        .into_boxed_str(),
+    );
+    let transport = RecordingTransport::with_body(page);
+    let requests = transport.requests.clone();
+    let client = Client::builder("clientid")
+        .auth_token("token")
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    let comments: Vec<Comment> = client
+        .track(1)
+        .comments()
+        .pending()
+        .iter(Default::default())
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        vec![1, 2],
+        comments.iter().map(|c| c.id).collect::<Vec<_>>()
+    );
+    let requests = requests.lock().unwrap();
+    assert_eq!(1, requests.len());
+    assert_eq!(reqwest::Method::GET, requests[0].method());
+    assert_eq!("/tracks/1/comments/pending", requests[0].url().path());
+}
+
+#[tokio::test]
+async fn test_report_play() {
+    let transport = RecordingTransport::default();
+    let requests = transport.requests.clone();
+    let client = Client::builder("clientid")
+        .auth_token("token")
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    client.report_play(&Track::new_for_test(1)).await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(1, requests.len());
+    assert_eq!(reqwest::Method::POST, requests[0].method());
+    assert_eq!("/me/play-history", requests[0].url().path());
+    assert_eq!("OAuth token", requests[0].headers()["authorization"]);
+    let body: serde_json::Value =
+        serde_json::from_slice(requests[0].body().unwrap().as_bytes().unwrap()).unwrap();
+    assert_eq!(
+        serde_json::json!({"track_urn": "soundcloud:tracks:1"}),
+        body
+    );
+}
+
+#[tokio::test]
+async fn test_update_track_scheduled_release() {
+    let transport = RecordingTransport::with_body(common::track(1));
+    let requests = transport.requests.clone();
+    let client = Client::builder("clientid")
+        .auth_token("token")
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    client
+        .track(1)
+        .update(TrackUpdate {
+            sharing: Some(Sharing::Private),
+            release_date: Some("2024-05-01".to_owned()),
+            publish_at: Some("2024-05-01T12:00:00Z".to_owned()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let request = requests.lock().unwrap().remove(0);
+    assert_eq!(
+        parts(&[
+            ("track[sharing]", "private"),
+            ("track[release_date]", "2024-05-01"),
+            ("track[publish_at]", "2024-05-01T12:00:00Z"),
+        ]),
+        form_parts(request).await
+    );
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::len_zero, clippy::useless_format)]
+
 use url::Url;
 
 use futures::prelude::*;
@@ -253,3 +255,177 @@ async fn test_related_tracks() {
 
     assert_eq!(30, tracks.len());
 }
+
+#[tokio::test]
+async fn test_charts() {
+    let charts = client().charts(ChartKind::Top, Genre::HipHopRap);
+    let entries: Vec<ChartEntry> = charts
+        .iter(Default::default())
+        .take(20)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(20, entries.len());
+}
+
+#[tokio::test]
+async fn test_oembed() {
+    let options = OEmbedOptions {
+        max_height: Some(166),
+        ..Default::default()
+    };
+    let oembed = client()
+        .oembed(
+            "https://soundcloud.com/djmaksgermany/invites-feat-maks-warm-up-mix",
+            options,
+        )
+        .await
+        .unwrap();
+
+    assert!(oembed.html.contains("<iframe"));
+}
+
+#[tokio::test]
+async fn test_repost_track() {
+    let client = authenticated_client();
+    let track = client.track(TRACK_ID);
+
+    track.repost().await.unwrap();
+    track.unrepost().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_like_playlist() {
+    let client = authenticated_client();
+    let playlist = client.playlist(965640322);
+
+    playlist.like().await.unwrap();
+    playlist.unlike().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_update_me() {
+    let client = authenticated_client();
+    let update = ProfileUpdate {
+        city: Some("Berlin".to_owned()),
+        ..Default::default()
+    };
+    let me = client.update_me(update).await.unwrap();
+
+    assert_eq!(me.city.as_deref(), Some("Berlin"));
+}
+
+#[tokio::test]
+async fn test_client_with_locale() {
+    let client = Client::builder(env!("SOUNDCLOUD_CLIENT_ID"))
+        .app_locale("de")
+        .accept_language("de-DE")
+        .build()
+        .unwrap();
+    let track = client.track(TRACK_ID).get().await.unwrap();
+
+    assert_eq!(track.id as usize, TRACK_ID);
+}
+
+#[tokio::test]
+async fn test_user_likes_lenient() {
+    let likes = client().user(USER_ID).likes();
+    let tracks: Vec<Result<Track>> = likes
+        .iter_lenient(Default::default())
+        .take(50)
+        .collect()
+        .await;
+
+    assert_eq!(50, tracks.len());
+}
+
+#[tokio::test]
+async fn test_verify() {
+    client().verify().await.unwrap();
+    authenticated_client().verify().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_website_client_id_provider() {
+    let client_id = WebsiteClientIdProvider::new().client_id().await.unwrap();
+
+    assert_eq!(32, client_id.len());
+}
+
+#[tokio::test]
+async fn test_request_timeout() {
+    let options = RequestOptions::builder()
+        .timeout(std::time::Duration::from_millis(1))
+        .build();
+    let result = client().with_options(options).track(TRACK_ID).get().await;
+
+    assert!(matches!(result, Err(Error::HttpError(ref e)) if e.is_timeout()));
+}
+
+#[tokio::test]
+async fn test_fetch_many() {
+    let tracks: Vec<Track> = client()
+        .fetch_many(vec![263801976, TRACK_ID as u64])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        vec![263801976, TRACK_ID as u64],
+        tracks.iter().map(|t| t.id).collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn test_track_station() {
+    let client = client();
+    let track = client.track(TRACK_ID).get().await.unwrap();
+    let station = track.station(&client);
+    let tracks: Vec<Track> = station
+        .get(Default::default(), 1)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert!(!tracks.is_empty());
+}
+
+#[tokio::test]
+async fn test_playlist_tracks() {
+    let tracks = client().playlist(565064082).tracks();
+    let tracks: Vec<Track> = tracks.iter(Default::default()).try_collect().await.unwrap();
+
+    assert!(!tracks.is_empty());
+}
+
+#[tokio::test]
+async fn test_user_liked_playlists() {
+    let likes = client().user(USER_ID).liked_playlists();
+    let likes: Vec<PlaylistLike> = likes
+        .get(Default::default(), 1)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert!(!likes.is_empty());
+}
+
+#[tokio::test]
+async fn test_user_snapshot() {
+    let snapshot = client().user_snapshot(USER_ID).await.unwrap();
+
+    assert_eq!(snapshot.user.id, USER_ID);
+    assert!(!snapshot.tracks.is_empty());
+}
+
+#[tokio::test]
+async fn test_my_followings() {
+    let followings = authenticated_client().my_followings();
+    let followings: Vec<User> = followings
+        .get(Default::default(), 1)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert!(!followings.is_empty());
+}
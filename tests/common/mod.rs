@@ -0,0 +1,197 @@
+use std::sync::{Arc, Mutex};
+
+use futures::stream::{self, BoxStream, StreamExt};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A canned response, given as status code, additional headers and body.
+pub type Response = (u16, Vec<(&'static str, &'static str)>, &'static str);
+
+/// Starts a local HTTP server which answers each request with the next of the `responses`,
+/// given as status code and body, and returns its base url.
+pub async fn serve(responses: Vec<(u16, &'static str)>) -> String {
+    let responses = responses
+        .into_iter()
+        .map(|(status, body)| (status, vec![], body))
+        .collect();
+    serve_with_headers(responses).await
+}
+
+/// Like [`serve`], with additional response headers given as name and value.
+///
+/// `{host}` in a body or header value is replaced with the base url of the server, e.g. for a
+/// `next_href`.
+pub async fn serve_with_headers(responses: Vec<Response>) -> String {
+    let (host, _) = serve_recording(responses).await;
+    host
+}
+
+/// Like [`serve_with_headers`], also returning the request line and headers of each request
+/// received, e.g. to check the headers reqwest adds itself.
+pub async fn serve_recording(responses: Vec<Response>) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let host = format!("http://{}", addr);
+    let base = host.clone();
+    let requests = Arc::new(Mutex::new(vec![]));
+    let recorded = requests.clone();
+
+    tokio::spawn(async move {
+        for (status, headers, body) in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0; 8192];
+            let read = socket.read(&mut buffer).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&buffer[..read]);
+            let head = request.split("\r\n\r\n").next().unwrap_or_default();
+            recorded.lock().unwrap().push(head.to_owned());
+            let headers: String = headers
+                .iter()
+                .map(|(name, value)| format!("{}: {}\r\n", name, value.replace("{host}", &base)))
+                .collect();
+            let response = format!(
+                "HTTP/1.1 {} Status\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                headers,
+                body.replace("{host}", &base).len(),
+                body.replace("{host}", &base)
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+
+    (host, requests)
+}
+
+/// A raw HTTP response, written to the connection chunk by chunk.
+pub type RawResponse = BoxStream<'static, Vec<u8>>;
+
+/// Returns a raw response written at once.
+pub fn raw<B: Into<Vec<u8>>>(response: B) -> RawResponse {
+    stream::iter(vec![response.into()]).boxed()
+}
+
+/// Starts a local HTTP server like [`serve`] for special cases, like binary, stalled or slowly
+/// sent bodies, which writes the raw `responses` built from its base url.
+///
+/// Connections stay open until the test ends, so a response can announce more of the body than
+/// it sends.
+pub async fn serve_raw<F>(responses: F) -> String
+where
+    F: FnOnce(&str) -> Vec<RawResponse>,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let host = format!("http://{}", listener.local_addr().unwrap());
+    let responses = responses(&host);
+
+    tokio::spawn(async move {
+        let mut sockets = vec![];
+        for mut response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0; 8192];
+            let _ = socket.read(&mut buffer).await;
+            while let Some(chunk) = response.next().await {
+                let _ = socket.write_all(&chunk).await;
+                let _ = socket.flush().await;
+            }
+            sockets.push(socket);
+        }
+        futures::future::pending::<()>().await;
+    });
+
+    host
+}
+
+/// Returns a minimal user with the given id, as served by the API.
+pub fn user(id: usize) -> &'static str {
+    let user = format!(
+        r#"{{"id":{0},"permalink":"user","username":"User","uri":"https://api.soundcloud.com/users/{0}","permalink_url":"https://soundcloud.com/user","avatar_url":"https://i1.sndcdn.com/avatars.jpg"}}"#,
+        id
+    );
+    Box::leak(user.into_boxed_str())
+}
+
+/// Returns a page of minimal users with the given ids, as served by the API.
+pub fn users_page(ids: &[usize]) -> &'static str {
+    let users: Vec<&str> = ids.iter().map(|id| user(*id)).collect();
+    let page = format!(r#"{{"collection":[{}],"next_href":null}}"#, users.join(","));
+    Box::leak(page.into_boxed_str())
+}
+
+/// Returns a page of minimal users with the given ids, linking to the `next` path on the server.
+pub fn users_page_with_next(ids: &[usize], next: Option<&str>) -> &'static str {
+    let users: Vec<&str> = ids.iter().map(|id| user(*id)).collect();
+    let next = next.map_or("null".to_owned(), |next| format!(r#""{{host}}{}""#, next));
+    let page = format!(
+        r#"{{"collection":[{}],"next_href":{}}}"#,
+        users.join(","),
+        next
+    );
+    Box::leak(page.into_boxed_str())
+}
+
+/// Returns a minimal track with the given id, as served by the API.
+pub fn track(id: u64) -> &'static str {
+    let track = format!(
+        r#"{{"id":{0},"created_at":"2020-01-01T00:00:00Z","user":{1},"title":"Track {0}","permalink_url":"https://soundcloud.com/user/track","uri":"https://api.soundcloud.com/tracks/{0}","sharing":"public","duration":1000,"streamable":true,"downloadable":false,"license":"all-rights-reserved","waveform_url":"https://wave.sndcdn.com/waveform.json","commentable":true}}"#,
+        id,
+        user(1)
+    );
+    Box::leak(track.into_boxed_str())
+}
+
+/// Returns a minimal playlist with the given id, as served by the API.
+pub fn playlist(id: u64) -> &'static str {
+    let playlist = format!(
+        r#"{{"id":{0},"duration":0,"permalink_url":"https://soundcloud.com/user/sets/playlist","permalink":"playlist","uri":"https://api.soundcloud.com/playlists/{0}","track_count":0,"user_id":1,"kind":"playlist","title":"Playlist {0}","user":{1}}}"#,
+        id,
+        user(1)
+    );
+    Box::leak(playlist.into_boxed_str())
+}
+
+/// Returns a minimal comment with the given id on track 1, as served by the API.
+pub fn comment(id: usize) -> &'static str {
+    let comment = format!(
+        r#"{{"id":{0},"uri":"https://api.soundcloud.com/comments/{0}","created_at":"2020-01-01T00:00:00Z","body":"Nice","timestamp":1000,"user_id":1,"user":{1},"track_id":1}}"#,
+        id,
+        user(1)
+    );
+    Box::leak(comment.into_boxed_str())
+}
+
+/// Returns the body of a recorded `request`, e.g. a multipart form, by sending it to a local
+/// server, as streamed bodies can't be read otherwise.
+pub async fn request_body(mut request: reqwest::Request) -> Vec<u8> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    *request.url_mut() = url.parse().unwrap();
+    let length: usize = request.headers()[reqwest::header::CONTENT_LENGTH]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut data = vec![];
+        let mut buffer = vec![0; 8192];
+        let body_start = loop {
+            let read = socket.read(&mut buffer).await.unwrap();
+            data.extend_from_slice(&buffer[..read]);
+            if let Some(end) = data.windows(4).position(|window| window == b"\r\n\r\n") {
+                break end + 4;
+            }
+        };
+        while data.len() < body_start + length {
+            let read = socket.read(&mut buffer).await.unwrap();
+            data.extend_from_slice(&buffer[..read]);
+        }
+        let _ = socket
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .await;
+        data.split_off(body_start)
+    });
+    reqwest::Client::new().execute(request).await.unwrap();
+
+    server.await.unwrap()
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "metrics")]
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use soundcloud::*;
+
+#[allow(dead_code)]
+mod common;
+
+/// Recorder keeping the counters by name and labels, e.g. `soundcloud_requests_total{kind=api}`.
+#[derive(Clone, Default)]
+struct CountingRecorder {
+    counters: Arc<Mutex<HashMap<String, Arc<AtomicU64>>>>,
+}
+
+impl CountingRecorder {
+    fn value(&self, key: &str) -> u64 {
+        self.counters
+            .lock()
+            .unwrap()
+            .get(key)
+            .map_or(0, |counter| counter.load(Ordering::SeqCst))
+    }
+}
+
+impl Recorder for CountingRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let labels: Vec<String> = key
+            .labels()
+            .map(|label| format!("{}={}", label.key(), label.value()))
+            .collect();
+        let name = if labels.is_empty() {
+            key.name().to_owned()
+        } else {
+            format!("{}{{{}}}", key.name(), labels.join(","))
+        };
+        let counter = self
+            .counters
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_default()
+            .clone();
+        Counter::from_arc(counter)
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+#[tokio::test]
+async fn test_request_and_download_metrics() {
+    let recorder = CountingRecorder::default();
+    metrics::set_global_recorder(recorder.clone()).unwrap();
+
+    let host = common::serve(vec![(200, common::user(1)), (200, "audio"), (404, "{}")]).await;
+    let client = Client::builder("clientid").host(&host).build().unwrap();
+    let mut track = Track::new_for_test(1);
+    track.streamable = true;
+    track.stream_url = Some(format!("{}/tracks/1/stream", host));
+
+    client.user(1).get().await.unwrap();
+    let mut data = vec![];
+    client.stream(&track, &mut data).await.unwrap();
+    assert!(client.user(2).get().await.is_err());
+
+    assert_eq!(2, recorder.value("soundcloud_requests_total{kind=api}"));
+    assert_eq!(1, recorder.value("soundcloud_requests_total{kind=media}"));
+    assert_eq!(
+        1,
+        recorder.value("soundcloud_request_errors_total{kind=api,status=404}")
+    );
+    assert_eq!(5, recorder.value("soundcloud_downloaded_bytes_total"));
+}
@@ -0,0 +1,510 @@
+use soundcloud::*;
+
+#[test]
+fn test_genre_from_str() {
+    assert_eq!(
+        "soundcloud:genres:hiphoprap".parse::<Genre>().unwrap(),
+        Genre::HipHopRap
+    );
+    assert_eq!("danceedm".parse::<Genre>().unwrap(), Genre::DanceEdm);
+    assert_eq!("Drum & Bass".parse::<Genre>().unwrap(), Genre::DrumBass);
+    assert!("polka".parse::<Genre>().is_err());
+}
+
+#[test]
+fn test_genre_roundtrip() {
+    for genre in Genre::ALL {
+        assert_eq!(genre.urn().parse::<Genre>().unwrap(), *genre);
+        assert_eq!(genre.name().parse::<Genre>().unwrap(), *genre);
+    }
+}
+
+fn user_json(id: usize) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "permalink": "user",
+        "username": "User",
+        "uri": format!("https://api.soundcloud.com/users/{}", id),
+        "permalink_url": "https://soundcloud.com/user",
+        "avatar_url": "https://i1.sndcdn.com/avatars.jpg",
+    })
+}
+
+fn track_json(id: u64, title: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "created_at": "2020-01-01T00:00:00Z",
+        "user": user_json(1),
+        "title": title,
+        "permalink_url": "https://soundcloud.com/user/track",
+        "uri": format!("https://api.soundcloud.com/tracks/{}", id),
+        "sharing": "public",
+        "duration": 1000,
+        "streamable": true,
+        "downloadable": false,
+        "license": "all-rights-reserved",
+        "waveform_url": "https://wave.sndcdn.com/waveform.json",
+        "commentable": true,
+    })
+}
+
+#[test]
+fn test_track_set_dedups_by_id() {
+    let a: Track = serde_json::from_value(track_json(1, "a")).unwrap();
+    let b: Track = serde_json::from_value(track_json(1, "b")).unwrap();
+    let c: Track = serde_json::from_value(track_json(2, "c")).unwrap();
+
+    let mut tracks = TrackSet::new();
+    assert!(tracks.insert(a));
+    assert!(!tracks.insert(b));
+    assert!(tracks.insert(c));
+    assert_eq!(2, tracks.len());
+}
+
+#[test]
+fn test_user_eq_by_id() {
+    let a: User = serde_json::from_value(user_json(1)).unwrap();
+    let mut b: User = serde_json::from_value(user_json(1)).unwrap();
+    b.username = "Renamed".to_string();
+
+    assert_eq!(a, b);
+    assert_eq!(1, vec![a, b].into_iter().collect::<UserSet>().len());
+}
+
+#[test]
+fn test_new_for_test_constructors() {
+    let mut track = Track::new_for_test(42);
+    track.title = "Title".to_string();
+    track.user = User::new_for_test(7);
+
+    assert_eq!(42, track.id);
+    assert_eq!(7, track.user.id);
+    assert_eq!(Playlist::new_for_test(1), Playlist::new_for_test(1));
+    assert_eq!(Comment::new_for_test(3).id, 3);
+}
+
+#[test]
+fn test_oembed_deserialize() {
+    let oembed: OEmbed = serde_json::from_value(serde_json::json!({
+        "version": 1.0,
+        "type": "rich",
+        "provider_name": "SoundCloud",
+        "provider_url": "https://soundcloud.com",
+        "height": 400,
+        "width": "100%",
+        "title": "Flickermood by Forss",
+        "description": null,
+        "thumbnail_url": "https://i1.sndcdn.com/artworks.jpg",
+        "html": "<iframe></iframe>",
+        "author_name": "Forss",
+        "author_url": "https://soundcloud.com/forss",
+    }))
+    .unwrap();
+
+    assert_eq!(EmbedSize::Pixels(400), oembed.height);
+    assert_eq!(EmbedSize::Relative("100%".to_string()), oembed.width);
+}
+
+#[test]
+fn test_track_policy_deserialize() {
+    let mut json = track_json(1, "blocked");
+    json["policy"] = serde_json::json!("BLOCK");
+    let track: Track = serde_json::from_value(json).unwrap();
+
+    assert_eq!(Some(Policy::Block), track.policy);
+}
+
+#[tokio::test]
+async fn test_stream_geo_blocked_track() {
+    let client = Client::new("client_id");
+    let mut track = Track::new_for_test(1);
+    track.policy = Some(Policy::Block);
+    track.stream_url = Some("https://api.soundcloud.com/tracks/1/stream".to_string());
+
+    let result = client.stream(&track, futures::io::sink()).await;
+    assert!(matches!(result, Err(Error::GeoBlocked)));
+}
+
+#[tokio::test]
+async fn test_stream_without_stream_url() {
+    let client = Client::new("client_id");
+    let track = Track::new_for_test(1);
+
+    let result = client.stream(&track, futures::io::sink()).await;
+    assert!(matches!(result, Err(Error::TrackNotStreamable)));
+}
+
+#[test]
+fn test_client_id_validation() {
+    assert!(Client::try_new("a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6").is_ok());
+    assert!(matches!(
+        Client::try_new(""),
+        Err(Error::InvalidClientId(_))
+    ));
+    assert!(matches!(
+        Client::try_new("client id"),
+        Err(Error::InvalidClientId(_))
+    ));
+    assert!(Client::builder("my-id").build().is_ok());
+    assert!(Client::builder("").build().is_ok());
+    assert_eq!("", Client::new("").client_id());
+}
+
+#[test]
+fn test_kind_deserialize() {
+    let kinds: Vec<Kind> =
+        serde_json::from_str(r#"["track", "web-profile", "system-playlist", "station"]"#).unwrap();
+
+    assert_eq!(
+        vec![
+            Kind::Track,
+            Kind::WebProfile,
+            Kind::SystemPlaylist,
+            Kind::Unknown
+        ],
+        kinds
+    );
+    assert_eq!(Kind::Track, Track::new_for_test(1).kind());
+    assert_eq!(Kind::Playlist, Playlist::new_for_test(1).kind());
+}
+
+#[test]
+fn test_station_urn_fallback() {
+    let client = Client::new("clientid");
+    let mut user = User::new_for_test(5);
+
+    assert_eq!(
+        "soundcloud:system-playlists:artist-stations:5",
+        user.station(&client).urn()
+    );
+
+    user.station_urn = Some("soundcloud:system-playlists:artist-stations:6".to_string());
+    assert_eq!(
+        "soundcloud:system-playlists:artist-stations:6",
+        user.station(&client).urn()
+    );
+}
+
+#[test]
+fn test_follow_deserialize() {
+    let mut json = user_json(3);
+    let follow: Follow = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(3, follow.user.id);
+    assert_eq!(None, follow.followed_at);
+
+    json["followed_at"] = serde_json::json!("2020-01-01T00:00:00Z");
+    let follow: Follow = serde_json::from_value(json).unwrap();
+    assert_eq!(Some("2020-01-01T00:00:00Z"), follow.followed_at.as_deref());
+}
+
+#[test]
+fn test_track_sharing_and_embeddable_by() {
+    let mut json = track_json(1, "a");
+    json["sharing"] = "private".into();
+    json["embeddable_by"] = "me".into();
+    let track: Track = serde_json::from_value(json).unwrap();
+
+    assert_eq!(Sharing::Private, track.sharing);
+    assert_eq!(Some(EmbeddableBy::Me), track.embeddable_by);
+}
+
+#[test]
+fn test_playlist_diff() {
+    let tracks = |ids: &[u64]| Some(ids.iter().map(|id| Track::new_for_test(*id)).collect());
+    let mut old = Playlist::new_for_test(1);
+    old.tracks = tracks(&[1, 2, 3, 4]);
+    let mut new = Playlist::new_for_test(1);
+    new.tracks = tracks(&[3, 2, 4, 5]);
+
+    let diff = old.diff(&new);
+
+    assert_eq!(vec![5], diff.added);
+    assert_eq!(vec![1], diff.removed);
+    assert_eq!(vec![3], diff.moved);
+    assert!(old.diff(&old).is_empty());
+}
+
+#[test]
+fn test_playlist_diff_moved_track() {
+    let tracks = |ids: &[u64]| Some(ids.iter().map(|id| Track::new_for_test(*id)).collect());
+    let mut old = Playlist::new_for_test(1);
+    old.tracks = tracks(&[1, 2, 3, 4, 5]);
+    let mut new = Playlist::new_for_test(1);
+    new.tracks = tracks(&[1, 3, 4, 5, 2]);
+
+    let diff = old.diff(&new);
+
+    assert!(diff.added.is_empty());
+    assert!(diff.removed.is_empty());
+    assert_eq!(vec![2], diff.moved);
+}
+
+#[test]
+fn test_track_engagement() {
+    let mut json = track_json(1, "a");
+    json["playback_count"] = 100.into();
+    json["likes_count"] = 10.into();
+    json["reposts_count"] = 2.into();
+    let track: Track = serde_json::from_value(json.clone()).unwrap();
+    let engagement: TrackEngagement = serde_json::from_value(json).unwrap();
+
+    assert_eq!(Some(100), engagement.playback_count);
+    assert_eq!(Some(10), engagement.likes_count);
+    assert_eq!(Some(2), engagement.reposts_count);
+    assert_eq!(None, engagement.comment_count);
+    assert_eq!(engagement, TrackEngagement::from(&track));
+}
+
+#[test]
+fn test_track_created_with_partial_app() {
+    let mut json = track_json(1, "a");
+    json["created_with"] = serde_json::json!({ "id": 64, "name": "SoundCloud for iOS" });
+    let track: Track = serde_json::from_value(json).unwrap();
+    let app = track.created_with.unwrap();
+
+    assert_eq!(64, app.id);
+    assert_eq!(Some("SoundCloud for iOS"), app.name.as_deref());
+    assert_eq!(None, app.external_url);
+}
+
+#[test]
+fn test_track_length() {
+    let track: Track = serde_json::from_value(track_json(1, "full")).unwrap();
+    assert_eq!(std::time::Duration::from_secs(1), track.length());
+    assert_eq!(track.length(), track.full_length());
+
+    let mut json = track_json(2, "preview");
+    json["full_duration"] = serde_json::json!(180000);
+    let track: Track = serde_json::from_value(json).unwrap();
+    assert_eq!(std::time::Duration::from_secs(1), track.length());
+    assert_eq!(std::time::Duration::from_secs(180), track.full_length());
+}
+
+#[test]
+fn test_urn_helpers() {
+    assert_eq!(
+        "soundcloud:genres:hiphoprap",
+        Urn::genre("hiphoprap").unwrap().as_str()
+    );
+    assert!(matches!(Urn::genre("Hip Hop"), Err(Error::InvalidGenre(_))));
+    assert_eq!(
+        Urn::from(Genre::HipHopRap),
+        Urn::genre("hiphoprap").unwrap()
+    );
+    assert_eq!(
+        "soundcloud:system-playlists:track-stations:1",
+        Urn::track_station(1).to_string()
+    );
+    assert_eq!(
+        "soundcloud:system-playlists:artist-stations:2",
+        Urn::artist_station(2).as_str()
+    );
+
+    assert_eq!(
+        Urn::track_station(3),
+        "soundcloud:system-playlists:track-stations:3"
+            .parse()
+            .unwrap()
+    );
+    assert!(matches!(
+        "soundcloud:tracks:".parse::<Urn>(),
+        Err(Error::InvalidUrn(_))
+    ));
+    assert!("spotify:track:1".parse::<Urn>().is_err());
+}
+
+#[test]
+fn test_track_qualities() {
+    let transcoding = |quality: &str, protocol: &str| {
+        serde_json::json!({
+            "url": "https://api-v2.soundcloud.com/media/soundcloud:tracks:1/stream",
+            "preset": "aac_256k",
+            "snipped": false,
+            "quality": quality,
+            "format": { "protocol": protocol, "mime_type": "audio/mp4" },
+        })
+    };
+    let mut json = track_json(1, "a");
+    json["media"] = serde_json::json!({
+        "transcodings": [
+            transcoding("hq", "hls"),
+            transcoding("sq", "hls"),
+            transcoding("sq", "progressive"),
+        ]
+    });
+    let track: Track = serde_json::from_value(json).unwrap();
+
+    assert_eq!(
+        vec![Quality::Standard],
+        track.available_qualities(Subscription::Free)
+    );
+    assert_eq!(
+        vec![Quality::Standard, Quality::High],
+        track.available_qualities(Subscription::GoPlus)
+    );
+    assert_eq!(
+        "progressive",
+        track
+            .transcoding(Quality::Standard, Subscription::Free)
+            .unwrap()
+            .format
+            .protocol
+    );
+    assert!(matches!(
+        track.transcoding(Quality::High, Subscription::Go),
+        Err(Error::SubscriptionRequired(Quality::High))
+    ));
+}
+
+#[test]
+fn test_snipped_track_duration() {
+    let mut json = track_json(1, "a");
+    json["duration"] = serde_json::json!(200000);
+    let track: Track = serde_json::from_value(json.clone()).unwrap();
+
+    assert_eq!(Playback::Full, track.playback());
+    assert_eq!(
+        std::time::Duration::from_secs(200),
+        track.effective_stream_duration()
+    );
+
+    json["policy"] = serde_json::json!("SNIP");
+    let track: Track = serde_json::from_value(json).unwrap();
+
+    assert_eq!(Playback::PreviewOnly, track.playback());
+    assert_eq!(PREVIEW_LENGTH, track.effective_stream_duration());
+}
+
+#[test]
+fn test_player_iframe() {
+    use soundcloud::embed::{player_iframe, PlayerOptions};
+
+    let playlist = Playlist::new_for_test(2);
+    let options = PlayerOptions {
+        visual: Some(true),
+        start_track: Some(3),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        r#"<iframe width="100%" height="450" scrolling="no" frameborder="no" allow="autoplay" src="https://w.soundcloud.com/player/?url=https%3A%2F%2Fapi.soundcloud.com%2Fplaylists%2F2&amp;visual=true&amp;start_track=3"></iframe>"#,
+        player_iframe(&playlist, &options)
+    );
+}
+
+#[test]
+fn test_repost_with_caption() {
+    let repost: Repost = serde_json::from_value(serde_json::json!({
+        "created_at": "2020-01-02T00:00:00Z",
+        "type": "track-repost",
+        "caption": "Out now!",
+        "user": user_json(2),
+        "track": track_json(1, "Track"),
+    }))
+    .unwrap();
+
+    assert_eq!(Some("Out now!"), repost.caption.as_deref());
+    assert_eq!(2, repost.user.unwrap().id);
+    assert!(matches!(repost.item, RepostedItem::Track(ref track) if track.id == 1));
+}
+
+#[test]
+fn test_track_scheduled_release() {
+    let mut json = track_json(1, "Track");
+    json["release_date"] = "2024-05-01".into();
+    json["publish_at"] = "2024-05-01T12:00:00Z".into();
+    let track: Track = serde_json::from_value(json).unwrap();
+
+    assert_eq!(Some("2024-05-01"), track.release_date.as_deref());
+    assert_eq!(Some("2024-05-01T12:00:00Z"), track.publish_at.as_deref());
+    assert_eq!(
+        None,
+        serde_json::from_value::<Track>(track_json(2, "Track"))
+            .unwrap()
+            .publish_at
+    );
+}
+
+#[test]
+fn test_quota() {
+    let quota: Quota = serde_json::from_str(r#"{"unlimited_upload_quota":true}"#).unwrap();
+    assert_eq!(None, quota.remaining());
+    assert!(quota.allows(std::time::Duration::from_secs(100_000)));
+
+    let quota: Quota = serde_json::from_str(r#"{"upload_seconds_used":60}"#).unwrap();
+    assert_eq!(None, quota.remaining());
+    assert!(quota.allows(std::time::Duration::from_secs(100_000)));
+
+    let quota = Quota::new_for_test(60);
+    assert!(quota.allows(std::time::Duration::from_secs(60)));
+    assert!(!quota.allows(std::time::Duration::from_secs(61)));
+}
+
+#[test]
+fn test_builders_from_models() {
+    let client = Client::new("clientid");
+
+    let mut comment = Comment::new_for_test(1);
+    comment.track_id = 2;
+    comment.user_id = 3;
+    assert_eq!(2, comment.track(&client).id);
+    assert_eq!(3, comment.user(&client).id);
+
+    let mut track = Track::new_for_test(1);
+    track.user = User::new_for_test(4);
+    assert_eq!(4, track.user_builder(&client).id);
+
+    let mut playlist = Playlist::new_for_test(1);
+    playlist.user_id = 5;
+    assert_eq!(5, playlist.owner(&client).id);
+}
+
+#[test]
+fn test_format_helpers() {
+    use soundcloud::fmt::format_count;
+
+    let mut track = Track::new_for_test(1);
+    track.duration = 59_999;
+    assert_eq!("0:59", track.duration_hms());
+    track.duration = 3_600_000;
+    assert_eq!("1:00:00", track.duration_hms());
+
+    assert_eq!("0", format_count(0));
+    assert_eq!("1K", format_count(1_000));
+    assert_eq!("1.9K", format_count(1_999));
+    assert_eq!("999K", format_count(999_999));
+    assert_eq!("2B", format_count(2_000_000_000));
+}
+
+#[test]
+fn test_web_profile_service() {
+    let json = r#"{"kind":"web-profile","id":1,"service":"twitter","title":"","url":"","username":"artist","created_at":"2020-01-01T00:00:00Z"}"#;
+    let web_profile: WebProfile = serde_json::from_str(json).unwrap();
+    assert_eq!(Service::Twitter, web_profile.service);
+    assert_eq!("https://x.com/artist", web_profile.normalized_url());
+    let json = json.replace(r#""twitter""#, r#""X""#);
+    let web_profile: WebProfile = serde_json::from_str(&json).unwrap();
+    assert_eq!(Service::Other("X".to_owned()), web_profile.service);
+    assert_eq!("https://x.com/artist", web_profile.normalized_url());
+
+    let mut web_profile = WebProfile::new_for_test(2);
+    web_profile.url = "http://example.com/music".to_owned();
+    assert_eq!("http://example.com/music", web_profile.normalized_url());
+    web_profile.url = "example.com".to_owned();
+    assert_eq!("https://example.com", web_profile.normalized_url());
+    web_profile.url = "//example.com".to_owned();
+    assert_eq!("https://example.com", web_profile.normalized_url());
+    web_profile.url = "example.com/?from=https://soundcloud.com".to_owned();
+    assert_eq!(
+        "https://example.com/?from=https://soundcloud.com",
+        web_profile.normalized_url()
+    );
+
+    let service: Service = serde_json::from_str(r#""mixcloud""#).unwrap();
+    assert_eq!(Service::Other("mixcloud".to_owned()), service);
+    assert_eq!(r#""mixcloud""#, serde_json::to_string(&service).unwrap());
+    for name in &[r#""x""#, r#""X""#, r#""Instagram""#, r#""twitter""#] {
+        let service: Service = serde_json::from_str(name).unwrap();
+        assert_eq!(*name, serde_json::to_string(&service).unwrap());
+    }
+}
@@ -0,0 +1,54 @@
+use soundcloud::{Endpoint, QueryParams};
+
+#[test]
+fn test_encode_escapes_reserved_characters() {
+    let mut params = QueryParams::new();
+    params.push("q", "drum & bass").push("genres", "R&B & Soul");
+
+    assert_eq!("q=drum+%26+bass&genres=R%26B+%26+Soul", params.encode());
+}
+
+#[test]
+fn test_encode_unicode() {
+    let mut params = QueryParams::new();
+    params.push("q", "björk 東京");
+
+    assert_eq!("q=bj%C3%B6rk+%E6%9D%B1%E4%BA%AC", params.encode());
+}
+
+#[test]
+fn test_encode_bracketed_keys() {
+    let mut params = QueryParams::new();
+    params.push("track[title]", "Flickermood");
+
+    assert_eq!("track%5Btitle%5D=Flickermood", params.encode());
+}
+
+#[test]
+fn test_optional_and_list_values() {
+    let mut params = QueryParams::new();
+    params
+        .push_opt("q", None::<&str>)
+        .push_opt("limit", Some(50))
+        .push_list("ids", Some(vec![1, 2, 3]));
+
+    assert_eq!(None, params.get("q"));
+    assert_eq!(Some("50"), params.get("limit"));
+    assert_eq!("limit=50&ids=1%2C2%2C3", params.encode());
+}
+
+#[test]
+fn test_endpoint_encodes_segments() {
+    let endpoint = Endpoint::new("/stations")
+        .segment("soundcloud:system-playlists:track-stations:1")
+        .segment("tracks");
+    assert_eq!(
+        "/stations/soundcloud:system-playlists:track-stations:1/tracks",
+        endpoint.to_string()
+    );
+
+    let mut params = QueryParams::new();
+    params.push("q", "a&b");
+    let endpoint = Endpoint::new("/users").segment("björk?/#1").params(&params);
+    assert_eq!("/users/bj%C3%B6rk%3F%2F%231?q=a%26b", endpoint.to_string());
+}